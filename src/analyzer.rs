@@ -0,0 +1,503 @@
+// Static recursive-descent analysis of a ROM image. Code is discovered by
+// following control flow from the entry point and the RST/interrupt vectors,
+// so data mixed into the ROM is never decoded as instructions.
+
+use crate::opcodes::{BranchKind, OpInfo, OpTable};
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+const BANK_SIZE: usize = 0x4000;
+
+const ENTRY_POINT: u16 = 0x0100;
+const RST_VECTORS: [u16; 8] = [0x00, 0x08, 0x10, 0x18, 0x20, 0x28, 0x30, 0x38];
+const INTERRUPT_VECTORS: [(u16, &str); 5] = [
+    (0x40, "VBlankInterrupt"),
+    (0x48, "LCDCInterrupt"),
+    (0x50, "TimerOverflowInterrupt"),
+    (0x58, "SerialTransferCompleteInterrupt"),
+    (0x60, "JoypadTransitionInterrupt"),
+];
+
+// op_cmds keys of the instructions used to track constants for bank switches
+const LD_A_N: u16 = 0x3E;
+const XOR_A: u16 = 0xAF;
+const LD_HL_NN: u16 = 0x21;
+const LD_NN_A: u16 = 0xEA;
+const LD_HL_A: u16 = 0x77;
+const LD_HL_N: u16 = 0x36;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct RomAddr {
+    pub bank: u16,
+    pub addr: u16,
+}
+
+impl RomAddr {
+    pub fn new(bank: u16, addr: u16) -> Self {
+        Self {
+            bank,
+            addr,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.bank as usize * BANK_SIZE + (self.addr as usize & (BANK_SIZE - 1))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdgeKind {
+    Fallthrough,
+    Jump,
+    Call,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ByteKind {
+    Data,
+    Code,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Mbc {
+    None,
+    Mbc1,
+    Mbc2,
+    Mbc3,
+    Mbc5,
+}
+
+impl Mbc {
+    fn from_header(cart_type: u8) -> Self {
+        match cart_type {
+            0x01..=0x03 => Mbc::Mbc1,
+            0x05 | 0x06 => Mbc::Mbc2,
+            0x0F..=0x13 => Mbc::Mbc3,
+            0x19..=0x1E => Mbc::Mbc5,
+            _ => Mbc::None,
+        }
+    }
+
+    // rom bank selected by writing `val` to `addr`, if it is a bank register
+    fn bank_write(&self, addr: u16, val: u8) -> Option<u16> {
+        match self {
+            Mbc::Mbc1 if (0x2000..0x4000).contains(&addr) => Some((val & 0x1F).max(1) as u16),
+            Mbc::Mbc2 if addr < 0x4000 && addr & 0x100 != 0 => Some((val & 0x0F).max(1) as u16),
+            Mbc::Mbc3 if (0x2000..0x4000).contains(&addr) => Some((val & 0x7F).max(1) as u16),
+            Mbc::Mbc5 if (0x2000..0x3000).contains(&addr) => Some(val as u16),
+            _ => None,
+        }
+    }
+}
+
+pub struct Instr {
+    pub key: u16,
+    pub imm: u16,
+    pub length: u8,
+    pub branch: BranchKind,
+}
+
+pub struct Block {
+    pub start: RomAddr,
+    pub end: RomAddr,
+    pub succs: Vec<(RomAddr, EdgeKind)>,
+}
+
+// values known to be in registers along a straight run of code
+#[derive(Clone, Copy)]
+struct Consts {
+    bank: Option<u16>,
+    a: Option<u8>,
+    hl: Option<u16>,
+}
+
+impl Consts {
+    fn update(&mut self, info: &OpInfo, imm: u16, mbc: Mbc) {
+        let write = match info.key {
+            LD_NN_A => self.a.map(|a| (imm, a)),
+            LD_HL_A => self.a.and_then(|a| self.hl.map(|hl| (hl, a))),
+            LD_HL_N => self.hl.map(|hl| (hl, imm as u8)),
+            _ => None,
+        };
+        if let Some(bank) = write.and_then(|(addr, val)| mbc.bank_write(addr, val)) {
+            self.bank = Some(bank);
+        }
+
+        match info.key {
+            LD_A_N => self.a = Some(imm as u8),
+            XOR_A => self.a = Some(0),
+            LD_HL_NN => self.hl = Some(imm),
+            _ => {
+                if writes_a(info) {
+                    self.a = None;
+                }
+                if writes_hl(info) {
+                    self.hl = None;
+                }
+            },
+        }
+    }
+}
+
+// register or memory operand written by the instruction, if any
+fn dest(info: &OpInfo) -> Option<&str> {
+    match info.cmd.as_str() {
+        "CP" | "BIT" | "PUSH" => None,
+        "SET" | "RES" => info.args.get(1).map(|arg| arg.as_str()),
+        _ => info.args.first().map(|arg| arg.as_str()),
+    }
+}
+
+fn is_call(info: &OpInfo) -> bool {
+    matches!(info.branch_kind(), BranchKind::Call | BranchKind::CondCall)
+}
+
+fn writes_a(info: &OpInfo) -> bool {
+    let implicit = ["SUB", "AND", "OR", "XOR", "DAA", "CPL", "RLCA", "RLA", "RRCA", "RRA"];
+    implicit.contains(&info.cmd.as_str()) || matches!(dest(info), Some("A") | Some("AF")) || is_call(info)
+}
+
+fn writes_hl(info: &OpInfo) -> bool {
+    ["LDI", "LDD", "LDHL"].contains(&info.cmd.as_str())
+        || matches!(dest(info), Some("H") | Some("L") | Some("HL"))
+        || is_call(info)
+}
+
+pub struct Analysis {
+    pub instrs: BTreeMap<RomAddr, Instr>,
+    pub blocks: BTreeMap<RomAddr, Block>,
+    pub routines: BTreeMap<RomAddr, String>,
+    map: Vec<ByteKind>,
+}
+
+struct Analyzer<'a> {
+    rom: &'a [u8],
+    table: OpTable,
+    mbc: Mbc,
+    num_banks: u16,
+    instrs: BTreeMap<RomAddr, Instr>,
+    edges: BTreeMap<RomAddr, Vec<(RomAddr, EdgeKind)>>,
+    leaders: BTreeSet<RomAddr>,
+    routines: BTreeMap<RomAddr, String>,
+    visited: HashSet<RomAddr>,
+    work: Vec<(RomAddr, Consts)>,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(rom: &'a [u8]) -> Self {
+        let cart_type = rom.get(0x147).copied().unwrap_or(0);
+        Self {
+            rom,
+            table: OpTable::new(),
+            mbc: Mbc::from_header(cart_type),
+            num_banks: rom.len().div_ceil(BANK_SIZE) as u16,
+            instrs: BTreeMap::new(),
+            edges: BTreeMap::new(),
+            leaders: BTreeSet::new(),
+            routines: BTreeMap::new(),
+            visited: HashSet::new(),
+            work: Vec::new(),
+        }
+    }
+
+    // maps a cpu address to a rom location given the bank mapped at 0x4000
+    fn resolve(&self, addr: u16, bank: Option<u16>) -> Option<RomAddr> {
+        let loc = match addr {
+            0x0000..=0x3FFF => RomAddr::new(0, addr),
+            0x4000..=0x7FFF => RomAddr::new(bank?, addr),
+            _ => return None,
+        };
+        if loc.bank < self.num_banks && loc.offset() < self.rom.len() {
+            Some(loc)
+        }
+        else {
+            None
+        }
+    }
+
+    fn read(&self, loc: RomAddr, addr: u16) -> Option<u8> {
+        // instructions never straddle the end of their bank window
+        if (addr ^ loc.addr) & 0xC000 != 0 {
+            return None;
+        }
+        self.rom.get(RomAddr::new(loc.bank, addr).offset()).copied()
+    }
+
+    fn seed(&mut self, addr: u16, name: &str) {
+        let consts = Consts {
+            bank: if self.num_banks > 1 { Some(1) } else { None },
+            a: None,
+            hl: None,
+        };
+        if let Some(loc) = self.resolve(addr, consts.bank) {
+            self.routines.insert(loc, name.to_string());
+            self.leaders.insert(loc);
+            self.work.push((loc, consts));
+        }
+    }
+
+    fn add_target(&mut self, from: RomAddr, target: u16, kind: EdgeKind, consts: Consts) {
+        let loc = match self.resolve(target, consts.bank) {
+            Some(loc) => loc,
+            None => return,
+        };
+        if kind == EdgeKind::Call && !self.routines.contains_key(&loc) {
+            let name = format!("Call_{:02x}_{:04x}", loc.bank, loc.addr);
+            self.routines.insert(loc, name);
+        }
+        self.leaders.insert(loc);
+        self.edges.entry(from).or_default().push((loc, kind));
+        let callee = Consts {
+            bank: consts.bank,
+            a: None,
+            hl: None,
+        };
+        self.work.push((loc, if kind == EdgeKind::Call { callee } else { consts }));
+    }
+
+    fn trace(&mut self, start: RomAddr, mut consts: Consts) {
+        let mut loc = start;
+        if loc.bank > 0 {
+            consts.bank = Some(loc.bank);
+        }
+
+        while self.visited.insert(loc) {
+            let (key, imm, length, branch, target) = {
+                let (info, imm) = match self.table.decode(loc.addr, |addr| self.read(loc, addr)) {
+                    Some(decoded) => decoded,
+                    None => return,
+                };
+                consts.update(info, imm, self.mbc);
+                (info.key, imm, info.length, info.branch_kind(), info.target(loc.addr, imm))
+            };
+            self.instrs.insert(loc, Instr {
+                key,
+                imm,
+                length,
+                branch,
+            });
+
+            if let Some(target) = target {
+                let kind = match branch {
+                    BranchKind::Call | BranchKind::CondCall => EdgeKind::Call,
+                    _ => EdgeKind::Jump,
+                };
+                self.add_target(loc, target, kind, consts);
+            }
+
+            let next = match self.resolve(loc.addr.wrapping_add(length as u16), consts.bank) {
+                Some(next) => next,
+                None => return,
+            };
+            match branch {
+                BranchKind::Jump | BranchKind::JumpIndirect | BranchKind::Ret => {
+                    self.leaders.insert(next);
+                    return;
+                },
+                BranchKind::None => (),
+                _ => {
+                    self.leaders.insert(next);
+                    self.edges.entry(loc).or_default().push((next, EdgeKind::Fallthrough));
+                },
+            }
+            loc = next;
+        }
+    }
+
+    fn build_blocks(&self) -> BTreeMap<RomAddr, Block> {
+        let mut blocks = BTreeMap::new();
+        let mut current: Option<Block> = None;
+
+        for (&loc, instr) in &self.instrs {
+            let starts_new = match &current {
+                Some(block) => block.end != loc || self.leaders.contains(&loc),
+                None => true,
+            };
+            if starts_new {
+                if let Some(mut block) = current.take() {
+                    if block.end == loc {
+                        block.succs.push((loc, EdgeKind::Fallthrough));
+                    }
+                    blocks.insert(block.start, block);
+                }
+                current = Some(Block {
+                    start: loc,
+                    end: loc,
+                    succs: Vec::new(),
+                });
+            }
+
+            let block = current.as_mut().unwrap();
+            block.end = RomAddr::new(loc.bank, loc.addr.wrapping_add(instr.length as u16));
+            if instr.branch != BranchKind::None {
+                if let Some(edges) = self.edges.get(&loc) {
+                    block.succs.extend(edges.iter().copied());
+                }
+                let block = current.take().unwrap();
+                blocks.insert(block.start, block);
+            }
+        }
+        if let Some(block) = current {
+            blocks.insert(block.start, block);
+        }
+        blocks
+    }
+
+    fn build_map(&self) -> Vec<ByteKind> {
+        let mut map = vec![ByteKind::Data; self.rom.len()];
+        for (loc, instr) in &self.instrs {
+            let start = loc.offset();
+            let end = (start + instr.length as usize).min(map.len());
+            for byte in &mut map[start..end] {
+                *byte = ByteKind::Code;
+            }
+        }
+        map
+    }
+}
+
+impl Analysis {
+    pub fn new(rom: &[u8]) -> Self {
+        let mut analyzer = Analyzer::new(rom);
+        analyzer.seed(ENTRY_POINT, "Entry");
+        for vector in RST_VECTORS.iter() {
+            analyzer.seed(*vector, &format!("RST_{:02x}", vector));
+        }
+        for (vector, name) in INTERRUPT_VECTORS.iter() {
+            analyzer.seed(*vector, name);
+        }
+
+        while let Some((loc, consts)) = analyzer.work.pop() {
+            analyzer.trace(loc, consts);
+        }
+
+        let blocks = analyzer.build_blocks();
+        let map = analyzer.build_map();
+        Self {
+            instrs: analyzer.instrs,
+            blocks,
+            routines: analyzer.routines,
+            map,
+        }
+    }
+
+    pub fn byte_kind(&self, offset: usize) -> Option<ByteKind> {
+        self.map.get(offset).copied()
+    }
+
+    // RGBDS compatible symbol file of the discovered routines
+    pub fn to_sym(&self) -> String {
+        let mut out = String::from("; generated by RustGB\n");
+        for (loc, name) in &self.routines {
+            writeln!(out, "{:02x}:{:04x} {}", loc.bank, loc.addr, name).unwrap();
+        }
+        out
+    }
+
+    // code/data map as one line per run of same kind bytes
+    pub fn to_map(&self) -> String {
+        let mut out = String::new();
+        let mut start = 0;
+        for offset in 1..=self.map.len() {
+            if offset < self.map.len() && self.map[offset] == self.map[start] && offset % BANK_SIZE != 0 {
+                continue;
+            }
+            let first = rom_addr_of(start);
+            let last = rom_addr_of(offset - 1);
+            let kind = match self.map[start] {
+                ByteKind::Code => "code",
+                ByteKind::Data => "data",
+            };
+            writeln!(out, "{:02x}:{:04x}-{:04x} {}", first.bank, first.addr, last.addr, kind).unwrap();
+            start = offset;
+        }
+        out
+    }
+}
+
+fn rom_addr_of(offset: usize) -> RomAddr {
+    let bank = (offset / BANK_SIZE) as u16;
+    let base = if bank == 0 { 0 } else { BANK_SIZE as u16 };
+    RomAddr::new(bank, base + (offset % BANK_SIZE) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `banks` banks of 0xFF with a ret at every vector, `main` at 0x0150
+    // behind a jump over the header, and `cart_type` in the header
+    fn rom(banks: usize, cart_type: u8, main: &[u8]) -> Vec<u8> {
+        let mut rom = vec![0xFF; banks * BANK_SIZE];
+        for vector in RST_VECTORS.iter().chain(INTERRUPT_VECTORS.iter().map(|(vector, _)| vector)) {
+            rom[*vector as usize] = 0xC9;
+        }
+        // jp $0150
+        rom[0x0100..0x0103].copy_from_slice(&[0xC3, 0x50, 0x01]);
+        rom[0x0147] = cart_type;
+        rom[0x0150..0x0150 + main.len()].copy_from_slice(main);
+        rom
+    }
+
+    #[test]
+    fn data_between_code_stays_data() {
+        // call sub; loop: jr loop; sub: ld a, 1; ret
+        let analysis = Analysis::new(&rom(2, 0, &[0xCD, 0x55, 0x01, 0x18, 0xFE, 0x3E, 0x01, 0xC9]));
+        assert_eq!(analysis.byte_kind(0x0100), Some(ByteKind::Code));
+        assert_eq!(analysis.byte_kind(0x0103), Some(ByteKind::Data));
+        assert_eq!(analysis.byte_kind(0x014F), Some(ByteKind::Data));
+        assert_eq!(analysis.byte_kind(0x0150), Some(ByteKind::Code));
+        // ld a,1 and ret end at 0x0158
+        assert_eq!(analysis.byte_kind(0x0157), Some(ByteKind::Code));
+        assert_eq!(analysis.byte_kind(0x0158), Some(ByteKind::Data));
+    }
+
+    #[test]
+    fn calls_become_routines() {
+        // call sub; loop: jr loop; sub: ret
+        let analysis = Analysis::new(&rom(2, 0, &[0xCD, 0x55, 0x01, 0x18, 0xFE, 0xC9]));
+        let sub = RomAddr::new(0, 0x0155);
+        assert_eq!(analysis.routines.get(&sub).map(|name| name.as_str()), Some("Call_00_0155"));
+        let sym = analysis.to_sym();
+        assert!(sym.contains("00:0100 Entry\n"));
+        assert!(sym.contains("00:0040 VBlankInterrupt\n"));
+        assert!(sym.contains("00:0155 Call_00_0155\n"));
+    }
+
+    #[test]
+    fn blocks_end_at_branches() {
+        // ld a, 1; jr z, skip; inc a; skip: ret
+        let analysis = Analysis::new(&rom(2, 0, &[0x3E, 0x01, 0x28, 0x01, 0x3C, 0xC9]));
+        let block = &analysis.blocks[&RomAddr::new(0, 0x0150)];
+        assert_eq!(block.end, RomAddr::new(0, 0x0154));
+        assert!(block.succs.contains(&(RomAddr::new(0, 0x0155), EdgeKind::Jump)));
+        assert!(block.succs.contains(&(RomAddr::new(0, 0x0154), EdgeKind::Fallthrough)));
+    }
+
+    // a constant written to the MBC1 bank register decides which bank a
+    // call into 0x4000-0x7FFF lands in
+    #[test]
+    fn follows_bank_switches() {
+        // ld a, 3; ld [$2000], a; call $4000; loop: jr loop
+        let mut rom = rom(4, 0x01, &[0x3E, 0x03, 0xEA, 0x00, 0x20, 0xCD, 0x00, 0x40, 0x18, 0xFE]);
+        rom[3 * BANK_SIZE] = 0xC9;
+        let analysis = Analysis::new(&rom);
+        let target = RomAddr::new(3, 0x4000);
+        assert!(analysis.routines.contains_key(&target));
+        assert_eq!(analysis.byte_kind(target.offset()), Some(ByteKind::Code));
+        assert_eq!(analysis.byte_kind(BANK_SIZE), Some(ByteKind::Data));
+    }
+
+    #[test]
+    fn map_runs() {
+        // loop: jr loop
+        let analysis = Analysis::new(&rom(2, 0, &[0x18, 0xFE]));
+        let map = analysis.to_map();
+        assert!(map.contains("00:0100-0102 code\n"));
+        assert!(map.contains("00:0103-014f data\n"));
+        assert!(map.contains("00:0150-0151 code\n"));
+        // runs split at bank boundaries
+        assert!(map.ends_with("01:4000-7fff data\n"));
+    }
+}
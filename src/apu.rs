@@ -0,0 +1,768 @@
+// Audio processing unit, NR10-NR52 (0xFF10-0xFF26) and wave RAM
+// (0xFF30-0xFF3F). Two pulse channels (the first with a frequency sweep), a
+// wave channel and a noise channel step on the dot clock, and a 512 Hz frame
+// sequencer driven by a falling edge of the timer's divider clocks their
+// length counters, sweep and volume envelopes. The channels are panned by
+// NR51 and scaled by the NR50 master volume. Every change in the mixed level
+// goes into a band-limited synthesis buffer at the dot it happened, which
+// resamples it to `sample_rate`, and the output then passes through the
+// console's high pass filter. Each channel's DAC output can also be
+// recorded on its own, before panning and mixing, as a mono stem. Clearing
+// the power bit in NR52 resets every register but leaves wave RAM alone.
+
+use crate::blip::{BlipBuf, HighPass};
+use crate::channel::{Noise, Pulse, Wave, WAVE_RAM_SIZE};
+
+pub const NR10: u16 = 0xFF10;
+pub const NR11: u16 = 0xFF11;
+pub const NR12: u16 = 0xFF12;
+pub const NR13: u16 = 0xFF13;
+pub const NR14: u16 = 0xFF14;
+pub const NR21: u16 = 0xFF16;
+pub const NR22: u16 = 0xFF17;
+pub const NR23: u16 = 0xFF18;
+pub const NR24: u16 = 0xFF19;
+pub const NR30: u16 = 0xFF1A;
+pub const NR31: u16 = 0xFF1B;
+pub const NR32: u16 = 0xFF1C;
+pub const NR33: u16 = 0xFF1D;
+pub const NR34: u16 = 0xFF1E;
+pub const NR41: u16 = 0xFF20;
+pub const NR42: u16 = 0xFF21;
+pub const NR43: u16 = 0xFF22;
+pub const NR44: u16 = 0xFF23;
+pub const NR50: u16 = 0xFF24;
+pub const NR51: u16 = 0xFF25;
+pub const NR52: u16 = 0xFF26;
+pub const WAVE_START: u16 = 0xFF30;
+pub const WAVE_END: u16 = 0xFF3F;
+
+pub const START: u16 = NR10;
+pub const END: u16 = WAVE_END;
+
+// dots per second, the rate the channels step at
+pub const CLOCK_RATE: u32 = 4_194_304;
+pub const DEFAULT_SAMPLE_RATE: u32 = 48_000;
+
+// `dots` since the start in units of `rate` a second. Dots are counted from
+// the cycles the instructions take, so this comes out the same on every run,
+// and scaling each stamp from the start rather than adding up the gaps keeps
+// rounding errors from building up over a long run
+pub fn scale_dots(dots: u64, rate: u64) -> u64 {
+    dots * rate / CLOCK_RATE as u64
+}
+
+const NR52_POWER: u8 = 0x80;
+
+// dots between moving samples out of the synthesis buffers
+const BLIP_FRAME: u32 = 4096;
+
+// how much of its charge the output capacitor keeps per dot
+const HIGH_PASS_DMG: f64 = 0.999958;
+const HIGH_PASS_CGB: f64 = 0.998943;
+
+// bits that read back as 1 in each register from NR10 to NR52
+const READ_MASKS: [u8; 23] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10-NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // NR21-NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30-NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // NR41-NR44
+    0x00, 0x00, 0x70, // NR50-NR52
+];
+
+// divider bit whose falling edge clocks the frame sequencer, in T-cycles of
+// the counter: bit 4 of DIV, or bit 5 in double speed
+const FRAME_BIT: u16 = 1 << 12;
+const FRAME_BIT_DOUBLE: u16 = 1 << 13;
+
+// what one channel is playing. `freq` is the 11 bit frequency, or NR43 for
+// the noise channel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Voice {
+    pub on: bool,
+    pub freq: u16,
+    pub volume: u8,
+}
+
+// one resampled, filtered output signal
+struct Stream {
+    buf: BlipBuf,
+    filter: HighPass,
+    // level last fed to the buffer
+    level: f32,
+    samples: Vec<i16>,
+}
+
+impl Stream {
+    fn new(charge: f64, sample_rate: u32) -> Self {
+        Self {
+            buf: BlipBuf::new(CLOCK_RATE, sample_rate),
+            filter: HighPass::new(charge, CLOCK_RATE, sample_rate),
+            level: 0.0,
+            samples: Vec::new(),
+        }
+    }
+
+    fn feed(&mut self, time: u32, level: f32) {
+        if level != self.level {
+            self.buf.add_delta(time, level - self.level);
+            self.level = level;
+        }
+    }
+
+    // ends the synthesis frame `time` dots in, moving its samples through
+    // the filter
+    fn flush(&mut self, time: u32) {
+        self.buf.end_frame(time);
+        let mut levels = Vec::new();
+        self.buf.read_samples(&mut levels);
+        for level in levels {
+            let out = self.filter.filter(level);
+            self.samples.push((out * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16);
+        }
+    }
+}
+
+pub struct Apu {
+    // the model, not the mode: a DMG keeps its length counters when powered
+    // off, only lets wave RAM be reached while channel 3 plays, and has a
+    // weaker high pass filter
+    cgb: bool,
+    power: bool,
+    regs: [u8; READ_MASKS.len()],
+    wave_ram: [u8; WAVE_RAM_SIZE],
+    ch1: Pulse,
+    ch2: Pulse,
+    ch3: Wave,
+    ch4: Noise,
+    // next frame sequencer step, 0-7
+    step: u8,
+    sample_rate: u32,
+    left: Stream,
+    right: Stream,
+    stems: Option<[Stream; 4]>,
+    // dots into the synthesis buffers' frame
+    time: u32,
+    // dots since the output was last reset
+    dots: u64,
+}
+
+impl Apu {
+    pub fn new() -> Self {
+        Self {
+            cgb: false,
+            power: false,
+            regs: [0; READ_MASKS.len()],
+            wave_ram: [0; WAVE_RAM_SIZE],
+            ch1: Pulse::new(),
+            ch2: Pulse::new(),
+            ch3: Wave::new(),
+            ch4: Noise::new(),
+            step: 0,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            left: Stream::new(HIGH_PASS_DMG, DEFAULT_SAMPLE_RATE),
+            right: Stream::new(HIGH_PASS_DMG, DEFAULT_SAMPLE_RATE),
+            stems: None,
+            time: 0,
+            dots: 0,
+        }
+    }
+
+    pub fn set_cgb(&mut self, cgb: bool) {
+        self.cgb = cgb;
+        self.reset_output();
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, rate: u32) {
+        self.sample_rate = rate;
+        self.reset_output();
+    }
+
+    // records each channel's DAC output as a mono stem alongside the mix
+    pub fn set_stems(&mut self, on: bool) {
+        self.stems = on.then(|| std::array::from_fn(|_| Stream::new(self.charge(), self.sample_rate)));
+        self.reset_output();
+    }
+
+    fn charge(&self) -> f64 {
+        if self.cgb { HIGH_PASS_CGB } else { HIGH_PASS_DMG }
+    }
+
+    // starts the output over for the current sample rate and model. A run
+    // fed the same dots from here makes the same samples
+    pub fn reset_output(&mut self) {
+        let (charge, rate) = (self.charge(), self.sample_rate);
+        self.left = Stream::new(charge, rate);
+        self.right = Stream::new(charge, rate);
+        if let Some(stems) = &mut self.stems {
+            *stems = std::array::from_fn(|_| Stream::new(charge, rate));
+        }
+        self.time = 0;
+        self.dots = 0;
+    }
+
+    // writes that bring a powered off APU to the current register state.
+    // Channels that are playing aren't retriggered
+    pub fn snapshot(&self) -> Vec<(u16, u8)> {
+        let mut writes = vec![(NR52, if self.power { NR52_POWER } else { 0 })];
+        for addr in NR10..NR52 {
+            let val = match addr {
+                NR14 | NR24 | NR34 | NR44 => self.reg(addr) & 0x7F,
+                _ => self.reg(addr),
+            };
+            writes.push((addr, val));
+        }
+        writes.extend(self.wave_ram.iter().enumerate().map(|(i, val)| (WAVE_START + i as u16, *val)));
+        writes
+    }
+
+    pub fn dots(&self) -> u64 {
+        self.dots
+    }
+
+    // channel `idx`, 0-3
+    pub fn voice(&self, idx: usize) -> Voice {
+        let (on, freq, volume) = match idx {
+            0 => (self.ch1.enabled, self.ch1.freq(), self.ch1.envelope.volume()),
+            1 => (self.ch2.enabled, self.ch2.freq(), self.ch2.envelope.volume()),
+            2 => (self.ch3.enabled, self.ch3.freq(), self.ch3.volume()),
+            _ => (self.ch4.enabled, self.ch4.poly() as u16, self.ch4.envelope.volume()),
+        };
+        Voice {
+            on,
+            freq,
+            volume,
+        }
+    }
+
+    // the stereo samples made since the last call, left first
+    pub fn take_samples(&mut self) -> Vec<i16> {
+        self.flush();
+        let left = std::mem::take(&mut self.left.samples);
+        let right = std::mem::take(&mut self.right.samples);
+        left.into_iter().zip(right).flat_map(|(l, r)| [l, r]).collect()
+    }
+
+    // the samples of each channel's stem made since the last call, if
+    // recording them
+    pub fn take_stems(&mut self) -> Option<[Vec<i16>; 4]> {
+        self.flush();
+        let stems = self.stems.as_mut()?;
+        Some(std::array::from_fn(|i| std::mem::take(&mut stems[i].samples)))
+    }
+
+    fn flush(&mut self) {
+        self.left.flush(self.time);
+        self.right.flush(self.time);
+        for stem in self.stems.iter_mut().flatten() {
+            stem.flush(self.time);
+        }
+        self.time = 0;
+    }
+
+    // sets a register the way the boot rom leaves it, without triggering
+    pub fn init(&mut self, addr: u16, val: u8) {
+        self.power = true;
+        match addr {
+            NR14 | NR24 | NR34 | NR44 => self.write(addr, val & 0x7F),
+            // the boot sound has faded out, but channel 1 is still on
+            NR52 => self.ch1.enabled = val & 0x01 != 0,
+            _ => self.write(addr, val),
+        }
+    }
+
+    // steps the channels by `dots` dots, feeding each change in the mixed
+    // level to the synthesis buffers at the dot it happens
+    pub fn tick(&mut self, dots: u8) {
+        for _ in 0..dots {
+            if self.power {
+                self.ch1.step();
+                self.ch2.step();
+                self.ch3.step(&self.wave_ram);
+                self.ch4.step();
+            }
+            let outputs = self.dac_outputs();
+            let (left, right) = self.mix(&outputs);
+            self.left.feed(self.time, left);
+            self.right.feed(self.time, right);
+            // stems keep the share of the mix a channel gets at full volume
+            for (stem, output) in self.stems.iter_mut().flatten().zip(outputs) {
+                stem.feed(self.time, output / 4.0);
+            }
+            self.time += 1;
+            self.dots += 1;
+        }
+        if self.time >= BLIP_FRAME {
+            self.flush();
+        }
+    }
+
+    // each DAC maps its 0-15 input onto -1.0 to 1.0, and is silent when off
+    fn dac_outputs(&self) -> [f32; 4] {
+        if !self.power {
+            return [0.0; 4];
+        }
+        let levels = [
+            (self.ch1.dac_on(), self.ch1.output()),
+            (self.ch2.dac_on(), self.ch2.output()),
+            (self.ch3.dac_on(), self.ch3.output()),
+            (self.ch4.dac_on(), self.ch4.output()),
+        ];
+        levels.map(|(on, level)| if on { 1.0 - level as f32 / 7.5 } else { 0.0 })
+    }
+
+    // left and right levels, -1.0 to 1.0
+    fn mix(&self, outputs: &[f32; 4]) -> (f32, f32) {
+        let nr50 = self.reg(NR50);
+        let nr51 = self.reg(NR51);
+        let (mut left, mut right) = (0.0, 0.0);
+        for (i, out) in outputs.iter().enumerate() {
+            if nr51 & (0x10 << i) != 0 {
+                left += out;
+            }
+            if nr51 & (0x01 << i) != 0 {
+                right += out;
+            }
+        }
+        let left_volume = ((nr50 >> 4) & 0x07) as f32 + 1.0;
+        let right_volume = (nr50 & 0x07) as f32 + 1.0;
+        (left / 4.0 * left_volume / 8.0, right / 4.0 * right_volume / 8.0)
+    }
+
+    // called with the timer's counter before and after it moved, which is
+    // at most one frame sequencer step
+    pub fn clock_div(&mut self, old: u16, new: u16, double_speed: bool) {
+        let bit = if double_speed { FRAME_BIT_DOUBLE } else { FRAME_BIT };
+        if self.power && old & bit != 0 && new & bit == 0 {
+            self.clock_frame();
+        }
+    }
+
+    fn clock_frame(&mut self) {
+        if self.step & 1 == 0 {
+            self.ch1.clock_length();
+            self.ch2.clock_length();
+            self.ch3.clock_length();
+            self.ch4.clock_length();
+        }
+        if self.step == 2 || self.step == 6 {
+            self.ch1.clock_sweep();
+        }
+        if self.step == 7 {
+            self.ch1.clock_envelope();
+            self.ch2.clock_envelope();
+            self.ch4.clock_envelope();
+        }
+        self.step = (self.step + 1) & 7;
+    }
+
+    fn reg(&self, addr: u16) -> u8 {
+        self.regs[(addr - START) as usize]
+    }
+
+    fn status(&self) -> u8 {
+        let on = [self.ch1.enabled, self.ch2.enabled, self.ch3.enabled, self.ch4.enabled];
+        on.iter().enumerate().fold(0, |status, (i, on)| status | ((*on as u8) << i))
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            NR52 => {
+                let power = if self.power { NR52_POWER } else { 0 };
+                power | READ_MASKS[(NR52 - START) as usize] | self.status()
+            },
+            NR10..=NR51 => self.reg(addr) | READ_MASKS[(addr - START) as usize],
+            WAVE_START..=WAVE_END => match self.wave_index(addr) {
+                Some(idx) => self.wave_ram[idx],
+                None => 0xFF,
+            },
+            _ => 0xFF,
+        }
+    }
+
+    // wave RAM byte `addr` reaches. While channel 3 plays it's whichever byte
+    // the channel reads, on a DMG only at the moment of the read, which this
+    // treats as never
+    fn wave_index(&self, addr: u16) -> Option<usize> {
+        if !self.ch3.enabled {
+            Some((addr - WAVE_START) as usize)
+        }
+        else if self.cgb {
+            Some(self.ch3.ram_index())
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, val: u8) {
+        if let WAVE_START..=WAVE_END = addr {
+            if let Some(idx) = self.wave_index(addr) {
+                self.wave_ram[idx] = val;
+            }
+            return;
+        }
+        if addr == NR52 {
+            self.write_power(val & NR52_POWER != 0);
+            return;
+        }
+        if !(NR10..NR52).contains(&addr) {
+            return;
+        }
+        if !self.power {
+            // a DMG's length counters can still be loaded
+            if !self.cgb {
+                match addr {
+                    NR11 => self.ch1.length.load(val & 0x3F),
+                    NR21 => self.ch2.length.load(val & 0x3F),
+                    NR31 => self.ch3.write_nr31(val),
+                    NR41 => self.ch4.write_nr41(val),
+                    _ => (),
+                }
+            }
+            return;
+        }
+        self.regs[(addr - START) as usize] = val;
+
+        // the next step clocking length is even, so an odd one next means the
+        // length enable quirk applies
+        let extra = self.step & 1 == 1;
+        match addr {
+            NR10 => self.ch1.write_sweep(val),
+            NR11 => self.ch1.write_nrx1(val),
+            NR12 => self.ch1.write_nrx2(val),
+            NR13 => self.ch1.write_nrx3(val),
+            NR14 => self.ch1.write_nrx4(val, extra, true),
+            NR21 => self.ch2.write_nrx1(val),
+            NR22 => self.ch2.write_nrx2(val),
+            NR23 => self.ch2.write_nrx3(val),
+            NR24 => self.ch2.write_nrx4(val, extra, false),
+            NR30 => self.ch3.write_nr30(val),
+            NR31 => self.ch3.write_nr31(val),
+            NR32 => self.ch3.write_nr32(val),
+            NR33 => self.ch3.write_nr33(val),
+            NR34 => self.ch3.write_nr34(val, extra),
+            NR41 => self.ch4.write_nr41(val),
+            NR42 => self.ch4.write_nr42(val),
+            NR43 => self.ch4.write_nr43(val),
+            NR44 => self.ch4.write_nr44(val, extra),
+            _ => (),
+        }
+    }
+
+    fn write_power(&mut self, on: bool) {
+        if on && !self.power {
+            // the frame sequencer starts over at step 0
+            self.step = 0;
+            self.ch1.reset_duty();
+            self.ch2.reset_duty();
+        }
+        else if !on && self.power {
+            let lengths = [
+                self.ch1.length.counter(),
+                self.ch2.length.counter(),
+                self.ch3.length.counter(),
+                self.ch4.length.counter(),
+            ];
+            self.regs = [0; READ_MASKS.len()];
+            self.ch1 = Pulse::new();
+            self.ch2 = Pulse::new();
+            self.ch3 = Wave::new();
+            self.ch4 = Noise::new();
+            if !self.cgb {
+                self.ch1.length.set_counter(lengths[0]);
+                self.ch2.length.set_counter(lengths[1]);
+                self.ch3.length.set_counter(lengths[2]);
+                self.ch4.length.set_counter(lengths[3]);
+            }
+        }
+        self.power = on;
+    }
+}
+
+impl Default for Apu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn powered(cgb: bool) -> Apu {
+        let mut apu = Apu::new();
+        apu.set_cgb(cgb);
+        apu.write(NR52, NR52_POWER);
+        apu
+    }
+
+    fn frames(apu: &mut Apu, count: usize) {
+        for _ in 0..count {
+            apu.clock_div(FRAME_BIT, 0, false);
+        }
+    }
+
+    #[test]
+    fn read_masks() {
+        let mut apu = powered(false);
+        for addr in NR10..NR52 {
+            apu.write(addr, 0);
+        }
+        assert_eq!(apu.read(NR10), 0x80);
+        assert_eq!(apu.read(NR13), 0xFF);
+        assert_eq!(apu.read(NR14), 0xBF);
+        assert_eq!(apu.read(NR30), 0x7F);
+        assert_eq!(apu.read(NR50), 0x00);
+        assert_eq!(apu.read(NR52), 0xF0);
+        // the gaps between the channels
+        assert_eq!(apu.read(0xFF15), 0xFF);
+        assert_eq!(apu.read(0xFF1F), 0xFF);
+        assert_eq!(apu.read(0xFF27), 0xFF);
+    }
+
+    #[test]
+    fn power_off() {
+        let mut apu = powered(true);
+        apu.write(NR50, 0x77);
+        apu.write(NR12, 0xF0);
+        apu.write(NR14, 0x80);
+        apu.write(WAVE_START, 0x12);
+        apu.write(NR52, 0);
+        assert_eq!(apu.read(NR52), 0x70);
+        assert_eq!(apu.read(NR50), 0x00);
+        assert_eq!(apu.read(NR12), 0x00);
+        assert_eq!(apu.read(WAVE_START), 0x12);
+        // writes are ignored while off
+        apu.write(NR50, 0x77);
+        assert_eq!(apu.read(NR50), 0x00);
+        apu.write(NR52, NR52_POWER);
+        assert_eq!(apu.read(NR52), 0xF0);
+    }
+
+    #[test]
+    fn lengths_while_off() {
+        let mut apu = powered(false);
+        apu.write(NR11, 10);
+        apu.write(NR52, 0);
+        assert_eq!(apu.ch1.length.counter(), 54);
+        apu.write(NR21, 20);
+        assert_eq!(apu.ch2.length.counter(), 44);
+        let mut apu = powered(true);
+        apu.write(NR11, 10);
+        apu.write(NR52, 0);
+        assert_eq!(apu.ch1.length.counter(), 0);
+        apu.write(NR21, 20);
+        assert_eq!(apu.ch2.length.counter(), 0);
+    }
+
+    #[test]
+    fn trigger_and_dac() {
+        let mut apu = powered(false);
+        apu.write(NR14, 0x80);
+        assert_eq!(apu.read(NR52) & 0x0F, 0);
+        apu.write(NR12, 0xF0);
+        apu.write(NR14, 0x80);
+        apu.write(NR30, 0x80);
+        apu.write(NR34, 0x80);
+        assert_eq!(apu.read(NR52) & 0x0F, 0x05);
+        apu.write(NR12, 0x00);
+        apu.write(NR30, 0x00);
+        assert_eq!(apu.read(NR52) & 0x0F, 0);
+    }
+
+    #[test]
+    fn length_runs_out() {
+        let mut apu = powered(false);
+        apu.write(NR22, 0xF0);
+        apu.write(NR21, 62);
+        apu.write(NR24, 0xC0);
+        // length clocks on even steps
+        frames(&mut apu, 2);
+        assert!(apu.ch2.enabled);
+        frames(&mut apu, 1);
+        assert!(!apu.ch2.enabled);
+    }
+
+    #[test]
+    fn length_enable_quirk() {
+        let mut apu = powered(false);
+        apu.write(NR22, 0xF0);
+        apu.write(NR21, 63);
+        apu.write(NR24, 0x80);
+        frames(&mut apu, 1);
+        // the next step doesn't clock length, so enabling it clocks it now
+        apu.write(NR24, 0x40);
+        assert!(!apu.ch2.enabled);
+    }
+
+    #[test]
+    fn envelope() {
+        let mut apu = powered(false);
+        apu.write(NR12, 0xF1);
+        apu.write(NR14, 0x80);
+        assert_eq!(apu.ch1.envelope.volume(), 15);
+        frames(&mut apu, 7);
+        assert_eq!(apu.ch1.envelope.volume(), 15);
+        frames(&mut apu, 1);
+        assert_eq!(apu.ch1.envelope.volume(), 14);
+        apu.write(NR42, 0x09);
+        apu.write(NR44, 0x80);
+        frames(&mut apu, 8);
+        assert_eq!(apu.ch4.envelope.volume(), 1);
+    }
+
+    #[test]
+    fn sweep() {
+        let mut apu = powered(false);
+        apu.write(NR12, 0xF0);
+        apu.write(NR10, 0x12);
+        apu.write(NR13, 0x00);
+        apu.write(NR14, 0x84);
+        // steps 2 and 6 clock the sweep
+        frames(&mut apu, 3);
+        assert_eq!(apu.ch1.freq(), 0x500);
+        frames(&mut apu, 4);
+        assert_eq!(apu.ch1.freq(), 0x640);
+        // the second overflow check, with the new frequency
+        frames(&mut apu, 4);
+        assert!(!apu.ch1.enabled);
+        // the overflow check at trigger
+        apu.write(NR13, 0xFF);
+        apu.write(NR14, 0x87);
+        assert!(!apu.ch1.enabled);
+    }
+
+    #[test]
+    fn sweep_negate_cleared() {
+        let mut apu = powered(false);
+        apu.write(NR12, 0xF0);
+        apu.write(NR10, 0x19);
+        apu.write(NR14, 0x84);
+        frames(&mut apu, 3);
+        assert!(apu.ch1.enabled);
+        apu.write(NR10, 0x11);
+        assert!(!apu.ch1.enabled);
+    }
+
+    #[test]
+    fn frame_sequencer_edge() {
+        let mut apu = powered(false);
+        apu.write(NR12, 0xF1);
+        apu.write(NR14, 0x80);
+        // only a falling edge of the divider bit counts
+        for _ in 0..8 {
+            apu.clock_div(0, FRAME_BIT, false);
+            apu.clock_div(FRAME_BIT_DOUBLE, 0, false);
+        }
+        assert_eq!(apu.ch1.envelope.volume(), 15);
+        for _ in 0..8 {
+            apu.clock_div(FRAME_BIT_DOUBLE, 0, true);
+        }
+        assert_eq!(apu.ch1.envelope.volume(), 14);
+    }
+
+    #[test]
+    fn wave_ram_while_playing() {
+        for cgb in [false, true] {
+            let mut apu = powered(cgb);
+            for i in 0..WAVE_RAM_SIZE as u16 {
+                apu.write(WAVE_START + i, i as u8 * 0x11);
+            }
+            apu.write(NR30, 0x80);
+            apu.write(NR33, 0xFF);
+            apu.write(NR34, 0x87);
+            apu.tick(6);
+            let expected = if cgb { 0x11 } else { 0xFF };
+            assert_eq!(apu.read(WAVE_END), expected);
+            apu.write(WAVE_END, 0xAB);
+            apu.write(NR30, 0x00);
+            let expected = if cgb { 0xAB } else { 0x11 };
+            assert_eq!(apu.read(WAVE_START + 1), expected);
+        }
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut apu = powered(false);
+        apu.write(NR50, 0x35);
+        apu.write(NR51, 0xA5);
+        apu.write(NR22, 0xA3);
+        apu.write(NR23, 0x42);
+        apu.write(NR24, 0x85);
+        apu.write(WAVE_START + 3, 0x9C);
+        let mut copy = Apu::new();
+        for (addr, val) in apu.snapshot() {
+            copy.write(addr, val);
+        }
+        for addr in START..=END {
+            if addr != NR52 {
+                assert_eq!(copy.read(addr), apu.read(addr), "{:04X}", addr);
+            }
+        }
+        // playing channels aren't retriggered
+        assert_eq!(copy.read(NR52), 0xF0);
+    }
+
+    #[test]
+    fn sample_rate() {
+        let mut apu = powered(false);
+        apu.set_sample_rate(32_000);
+        for _ in 0..CLOCK_RATE / 128 {
+            apu.tick(128);
+        }
+        let samples = apu.take_samples();
+        assert!((samples.len() as i64 - 64_000).abs() < 64, "{}", samples.len());
+        assert_eq!(apu.dots(), CLOCK_RATE as u64);
+        apu.reset_output();
+        assert_eq!(apu.dots(), 0);
+        assert!(apu.take_samples().is_empty());
+    }
+
+    fn play_ch2(apu: &mut Apu, nr51: u8) -> Vec<i16> {
+        apu.write(NR50, 0x77);
+        apu.write(NR51, nr51);
+        apu.write(NR21, 0x80);
+        apu.write(NR22, 0xF0);
+        apu.write(NR23, 0x00);
+        apu.write(NR24, 0x87);
+        for _ in 0..64 {
+            apu.tick(128);
+        }
+        apu.take_samples()
+    }
+
+    #[test]
+    fn panning() {
+        let samples = play_ch2(&mut powered(false), 0x20);
+        assert!(samples.iter().step_by(2).any(|s| *s != 0));
+        assert!(samples.iter().skip(1).step_by(2).all(|s| *s == 0));
+        let samples = play_ch2(&mut powered(false), 0x02);
+        assert!(samples.iter().step_by(2).all(|s| *s == 0));
+        assert!(samples.iter().skip(1).step_by(2).any(|s| *s != 0));
+    }
+
+    #[test]
+    fn silent_when_off() {
+        let mut apu = Apu::new();
+        assert!(play_ch2(&mut apu, 0xFF).iter().all(|s| *s == 0));
+    }
+
+    #[test]
+    fn stems() {
+        let mut apu = powered(false);
+        apu.set_stems(true);
+        play_ch2(&mut apu, 0x00);
+        let stems = apu.take_stems().unwrap();
+        assert!(stems[1].iter().any(|s| *s != 0));
+        for idx in [0, 2, 3] {
+            assert!(stems[idx].iter().all(|s| *s == 0));
+        }
+        apu.set_stems(false);
+        assert!(apu.take_stems().is_none());
+    }
+}
@@ -0,0 +1,87 @@
+// A log of the writes the cpu makes to the APU registers and wave RAM, each
+// stamped with the dot it landed on, for exporting the music played (see
+// vgm.rs). The motherboard advances the log by the dots each instruction
+// takes, and the loop point can be marked now or at a later dot.
+
+pub struct ApuWrite {
+    // dots since the log started
+    pub time: u64,
+    pub addr: u16,
+    pub val: u8,
+}
+
+pub struct ApuLog {
+    time: u64,
+    writes: Vec<ApuWrite>,
+    // index of the first write after the loop point, and the loop point's time
+    loop_start: Option<(usize, u64)>,
+    // time to mark the loop point at, once the log gets there
+    loop_at: Option<u64>,
+}
+
+impl ApuLog {
+    // starts a log with `initial` writes setting up the APU as it is now
+    pub fn new(initial: Vec<(u16, u8)>) -> Self {
+        let writes = initial
+            .into_iter()
+            .map(|(addr, val)| ApuWrite {
+                time: 0,
+                addr,
+                val,
+            })
+            .collect();
+        Self {
+            time: 0,
+            writes,
+            loop_start: None,
+            loop_at: None,
+        }
+    }
+
+    pub fn advance(&mut self, dots: u8) {
+        self.time += dots as u64;
+        if matches!(self.loop_at, Some(at) if self.time >= at) {
+            self.mark_loop();
+        }
+    }
+
+    pub fn record(&mut self, addr: u16, val: u8) {
+        self.writes.push(ApuWrite {
+            time: self.time,
+            addr,
+            val,
+        });
+    }
+
+    // marks the current time as where playback loops back to
+    pub fn mark_loop(&mut self) {
+        self.loop_start = Some((self.writes.len(), self.time));
+        self.loop_at = None;
+    }
+
+    // marks the loop point when the log reaches `time` dots
+    pub fn loop_at(&mut self, time: u64) {
+        self.loop_at = Some(time);
+        if self.time >= time {
+            self.mark_loop();
+        }
+    }
+
+    pub fn clear_loop(&mut self) {
+        self.loop_start = None;
+        self.loop_at = None;
+    }
+
+    pub fn loop_start(&self) -> Option<(usize, u64)> {
+        self.loop_start
+    }
+
+    // dots logged so far
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn writes(&self) -> &[ApuWrite] {
+        &self.writes
+    }
+}
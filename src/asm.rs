@@ -0,0 +1,749 @@
+// Small RGBDS style assembler for writing test programs. Encodings come from
+// the same opcode table op_cmds.rs is generated from, so anything assembled
+// here executes with exactly the handler it was encoded for.
+
+use crate::opcodes::{OpInfo, OpTable};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[macro_export]
+macro_rules! asm {
+    ($src:expr) => {
+        $crate::asm::assemble($src, 0x0000).unwrap_or_else(|err| panic!("{}", err))
+    };
+    ($src:expr, $base:expr) => {
+        $crate::asm::assemble($src, $base).unwrap_or_else(|err| panic!("{}", err))
+    };
+}
+
+#[derive(Debug)]
+pub struct AsmError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "asm error on line {}: {}", self.line, self.msg)
+    }
+}
+
+type AsmResult<T> = Result<T, String>;
+
+// operand shapes as they appear in the opcode table
+#[derive(Clone, PartialEq, Debug)]
+enum Pat {
+    Reg(String),
+    Cond(String),
+    Imm8,
+    Imm16,
+    Rel8,
+    Mem(String),
+    MemImm,
+    HighImm,
+    HighC,
+    SpOff,
+    Bit(u8),
+    Vec(u16),
+}
+
+// operands as written in the source
+#[derive(Clone, Debug)]
+enum Operand {
+    Reg(String),
+    Expr(String),
+    Mem(String),
+    MemExpr(String),
+    HighExpr(String),
+    HighC,
+    SpOff(String),
+    Str(String),
+}
+
+const REGS: [&str; 13] = ["a", "b", "c", "d", "e", "h", "l", "af", "bc", "de", "hl", "sp", "pc"];
+const CONDS: [&str; 4] = ["nz", "z", "nc", "c"];
+
+fn table_pats(info: &OpInfo) -> (String, Vec<Pat>) {
+    let flag = info.cmd.ends_with("_FLAG");
+    let mnemonic = info.cmd.trim_end_matches("_FLAG").to_lowercase();
+    let mut pats: Vec<Pat> = info.args.iter().enumerate().map(|(idx, arg)| {
+        let inner = arg.trim_start_matches('(').trim_end_matches(')');
+        if flag && idx == 0 {
+            Pat::Cond(arg.to_lowercase())
+        }
+        else if arg == "n" && mnemonic == "jr" {
+            Pat::Rel8
+        }
+        else if arg == "n" {
+            Pat::Imm8
+        }
+        else if arg == "nn" {
+            Pat::Imm16
+        }
+        else if arg == "(nn)" {
+            Pat::MemImm
+        }
+        else if arg == "($FF00+n)" {
+            Pat::HighImm
+        }
+        else if arg == "($FF00+C)" {
+            Pat::HighC
+        }
+        else if arg == "(HL)" && mnemonic == "jp" {
+            Pat::Reg("hl".to_string())
+        }
+        else if arg.starts_with('(') {
+            Pat::Mem(inner.to_lowercase())
+        }
+        else if ["bit", "set", "res"].contains(&mnemonic.as_str()) && idx == 0 {
+            Pat::Bit(arg[1..].parse().unwrap())
+        }
+        else if mnemonic == "rst" {
+            Pat::Vec(u16::from_str_radix(arg.trim_end_matches('H'), 16).unwrap())
+        }
+        else {
+            Pat::Reg(arg.to_lowercase())
+        }
+    }).collect();
+
+    match mnemonic.as_str() {
+        "ldi" | "ldd" => {
+            let post = if mnemonic == "ldi" { "hl+" } else { "hl-" };
+            for pat in pats.iter_mut() {
+                if *pat == Pat::Mem("hl".to_string()) {
+                    *pat = Pat::Mem(post.to_string());
+                }
+            }
+            ("ld".to_string(), pats)
+        },
+        "ldhl" => ("ld".to_string(), vec![Pat::Reg("hl".to_string()), Pat::SpOff]),
+        _ => (mnemonic, pats),
+    }
+}
+
+fn split_operands(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut cur = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '(') | (None, '[') => depth += 1,
+            (None, ')') | (None, ']') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                out.push(cur.trim().to_string());
+                cur.clear();
+                continue;
+            },
+            _ => (),
+        }
+        cur.push(c);
+    }
+    if !cur.trim().is_empty() {
+        out.push(cur.trim().to_string());
+    }
+    out
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (idx, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ';' => return &line[..idx],
+            None => (),
+        }
+    }
+    line
+}
+
+fn parse_operand(text: &str) -> Operand {
+    let lower = text.to_lowercase();
+    if text.starts_with('"') {
+        return Operand::Str(text.trim_matches('"').to_string());
+    }
+    if (lower.starts_with('[') && lower.ends_with(']')) || (lower.starts_with('(') && lower.ends_with(')') && is_mem_paren(&lower)) {
+        let inner = lower[1..lower.len() - 1].replace(' ', "");
+        let raw_inner = text[1..text.len() - 1].trim().to_string();
+        return match inner.as_str() {
+            "hli" | "hl+" => Operand::Mem("hl+".to_string()),
+            "hld" | "hl-" => Operand::Mem("hl-".to_string()),
+            "hl" | "bc" | "de" | "c" => Operand::Mem(inner),
+            "$ff00+c" | "0xff00+c" => Operand::HighC,
+            _ => {
+                for prefix in ["$ff00+", "0xff00+"].iter() {
+                    if inner.starts_with(prefix) {
+                        return Operand::HighExpr(raw_inner[raw_inner.find('+').unwrap() + 1..].to_string());
+                    }
+                }
+                Operand::MemExpr(raw_inner)
+            },
+        };
+    }
+    let compact = lower.replace(' ', "");
+    if REGS.contains(&compact.as_str()) || CONDS.contains(&compact.as_str()) {
+        return Operand::Reg(compact);
+    }
+    if compact.starts_with("sp+") || compact.starts_with("sp-") {
+        let offset = text.trim()[2..].trim();
+        return Operand::SpOff(format!("0{}", offset));
+    }
+    Operand::Expr(text.to_string())
+}
+
+// parens around a whole operand are a memory reference only when they wrap
+// a register or address rather than a parenthesized sub expression
+fn is_mem_paren(text: &str) -> bool {
+    let mut depth = 0;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 && idx != text.len() - 1 {
+                    return false;
+                }
+            },
+            _ => (),
+        }
+    }
+    true
+}
+
+struct Expr<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    asm: &'a Assembler,
+    pc: u16,
+}
+
+impl<'a> Expr<'a> {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos) == Some(&' ') || self.chars.get(self.pos) == Some(&'\t') {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        self.peek();
+        let op_chars: Vec<char> = op.chars().collect();
+        if self.chars[self.pos..].starts_with(&op_chars) {
+            self.pos += op_chars.len();
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> AsmResult<i64> {
+        const LEVELS: [&[&str]; 6] = [&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/", "%"]];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut val = self.binary(level + 1)?;
+        'outer: loop {
+            for op in LEVELS[level].iter() {
+                if self.eat(op) {
+                    let rhs = self.binary(level + 1)?;
+                    if (*op == "<<" || *op == ">>") && !(0..64).contains(&rhs) {
+                        return Err(format!("shift by {} out of range", rhs));
+                    }
+                    if (*op == "/" || *op == "%") && rhs == 0 {
+                        return Err("division by zero".to_string());
+                    }
+                    val = match *op {
+                        "|" => Some(val | rhs),
+                        "^" => Some(val ^ rhs),
+                        "&" => Some(val & rhs),
+                        "<<" => val.checked_shl(rhs as u32),
+                        ">>" => val.checked_shr(rhs as u32),
+                        "+" => val.checked_add(rhs),
+                        "-" => val.checked_sub(rhs),
+                        "*" => val.checked_mul(rhs),
+                        "/" => val.checked_div(rhs),
+                        _ => val.checked_rem(rhs),
+                    }.ok_or("arithmetic overflow")?;
+                    continue 'outer;
+                }
+            }
+            return Ok(val);
+        }
+    }
+
+    fn unary(&mut self) -> AsmResult<i64> {
+        if self.eat("-") {
+            return self.unary()?.checked_neg().ok_or_else(|| "arithmetic overflow".to_string());
+        }
+        if self.eat("~") {
+            return Ok(!self.unary()?);
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("(") {
+            let val = self.binary(0)?;
+            if !self.eat(")") {
+                return Err("missing )".to_string());
+            }
+            return Ok(val);
+        }
+        self.atom()
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, pred: F) -> String {
+        let start = self.pos;
+        while self.pos < self.chars.len() && pred(self.chars[self.pos]) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn atom(&mut self) -> AsmResult<i64> {
+        let c = self.peek().ok_or("expected expression")?;
+        let parse = |digits: String, radix| {
+            i64::from_str_radix(&digits.replace('_', ""), radix).map_err(|_| format!("bad number {}", digits))
+        };
+        if c == '$' {
+            self.pos += 1;
+            return parse(self.take_while(|c| c.is_ascii_hexdigit() || c == '_'), 16);
+        }
+        if c == '%' {
+            self.pos += 1;
+            return parse(self.take_while(|c| c == '0' || c == '1' || c == '_'), 2);
+        }
+        if c == '@' {
+            self.pos += 1;
+            return Ok(self.pc as i64);
+        }
+        if c == '\'' {
+            let val = *self.chars.get(self.pos + 1).ok_or("bad character literal")? as i64;
+            self.pos += 3;
+            return Ok(val);
+        }
+        if c.is_ascii_digit() {
+            let word = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            return match word.strip_prefix("0x") {
+                Some(hex) => parse(hex.to_string(), 16),
+                None => parse(word, 10),
+            };
+        }
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        if name.is_empty() {
+            return Err(format!("unexpected '{}'", c));
+        }
+        self.asm.symbol(&name)
+    }
+}
+
+struct Assembler {
+    table: HashMap<String, Vec<(Vec<Pat>, OpInfo)>>,
+    symbols: HashMap<String, i64>,
+    // names defined so far in the current pass
+    defined: HashSet<String>,
+    scope: String,
+    final_pass: bool,
+}
+
+impl Assembler {
+    fn new() -> Self {
+        let mut table: HashMap<String, Vec<(Vec<Pat>, OpInfo)>> = HashMap::new();
+        for info in OpTable::new().iter() {
+            let (mnemonic, pats) = table_pats(info);
+            table.entry(mnemonic).or_default().push((pats, info.clone()));
+        }
+        Self {
+            table,
+            symbols: HashMap::new(),
+            defined: HashSet::new(),
+            scope: String::new(),
+            final_pass: false,
+        }
+    }
+
+    fn full_name(&self, name: &str) -> String {
+        if name.starts_with('.') {
+            format!("{}{}", self.scope, name)
+        }
+        else {
+            name.to_string()
+        }
+    }
+
+    fn symbol(&self, name: &str) -> AsmResult<i64> {
+        match self.symbols.get(&self.full_name(name)) {
+            Some(val) => Ok(*val),
+            None if !self.final_pass => Ok(0),
+            None => Err(format!("undefined symbol {}", name)),
+        }
+    }
+
+    fn eval(&self, text: &str, pc: u16) -> AsmResult<i64> {
+        let mut expr = Expr {
+            chars: text.chars().collect(),
+            pos: 0,
+            asm: self,
+            pc,
+        };
+        let val = expr.binary(0)?;
+        match expr.peek() {
+            None => Ok(val),
+            Some(c) => Err(format!("unexpected '{}' in {}", c, text)),
+        }
+    }
+
+    fn define(&mut self, name: &str, val: i64) -> AsmResult<()> {
+        let full = self.full_name(name);
+        if !self.defined.insert(full.clone()) {
+            return Err(format!("{} defined twice", full));
+        }
+        match self.symbols.insert(full.clone(), val) {
+            Some(old) if old != val && self.final_pass => Err(format!("{} moved on the final pass", full)),
+            _ => Ok(()),
+        }
+    }
+
+    fn matches(&self, pat: &Pat, op: &Operand, pc: u16) -> bool {
+        match (pat, op) {
+            (Pat::Reg(reg), Operand::Reg(name)) => reg == name,
+            (Pat::Cond(cond), Operand::Reg(name)) => cond == name,
+            (Pat::Imm8, Operand::Expr(_)) | (Pat::Imm16, Operand::Expr(_)) | (Pat::Rel8, Operand::Expr(_)) => true,
+            (Pat::Mem(reg), Operand::Mem(name)) => reg == name,
+            (Pat::MemImm, Operand::MemExpr(_)) => true,
+            (Pat::HighImm, Operand::HighExpr(_)) => true,
+            (Pat::HighC, Operand::HighC) => true,
+            (Pat::SpOff, Operand::SpOff(_)) => true,
+            (Pat::Bit(bit), Operand::Expr(text)) => self.eval(text, pc) == Ok(*bit as i64),
+            (Pat::Vec(vec), Operand::Expr(text)) => self.eval(text, pc) == Ok(*vec as i64),
+            _ => false,
+        }
+    }
+
+    fn imm(&self, pat: &Pat, op: &Operand, pc: u16, len: u16) -> AsmResult<Vec<u8>> {
+        let (text, lo, hi) = match (pat, op) {
+            (Pat::Imm8, Operand::Expr(text)) => (text, -128, 0xFF),
+            (Pat::Imm16, Operand::Expr(text)) | (Pat::MemImm, Operand::MemExpr(text)) => (text, -0x8000, 0xFFFF),
+            (Pat::SpOff, Operand::SpOff(text)) => (text, -128, 127),
+            (Pat::HighImm, Operand::HighExpr(text)) => (text, 0, 0xFF),
+            (Pat::Rel8, Operand::Expr(text)) => {
+                let target = self.eval(text, pc)?;
+                let offset = target - (pc as i64 + len as i64);
+                if self.final_pass && !(-128..=127).contains(&offset) {
+                    return Err(format!("jr target out of range ({})", offset));
+                }
+                return Ok(vec![offset as u8]);
+            },
+            _ => return Ok(vec![]),
+        };
+        let val = self.eval(text, pc)?;
+        if self.final_pass && !(lo..=hi).contains(&val) {
+            return Err(format!("{} out of range", text));
+        }
+        match pat {
+            Pat::Imm16 | Pat::MemImm => Ok((val as u16).to_le_bytes().to_vec()),
+            _ => Ok(vec![val as u8]),
+        }
+    }
+
+    // rewrites RGBDS spellings into the operand forms of the opcode table
+    fn normalize(&self, mnemonic: &str, mut ops: Vec<Operand>) -> (String, Vec<Operand>) {
+        let mut mnemonic = mnemonic.to_string();
+        match mnemonic.as_str() {
+            "ldi" | "ldd" => {
+                let post = if mnemonic == "ldi" { "hl+" } else { "hl-" };
+                for op in ops.iter_mut() {
+                    if let Operand::Mem(reg) = op {
+                        if reg == "hl" {
+                            *reg = post.to_string();
+                        }
+                    }
+                }
+                mnemonic = "ld".to_string();
+            },
+            "ldh" => {
+                for op in ops.iter_mut() {
+                    match op {
+                        Operand::MemExpr(text) => *op = Operand::HighExpr(format!("({}) & $FF", text)),
+                        Operand::Mem(reg) if reg == "c" => *op = Operand::HighC,
+                        _ => (),
+                    }
+                }
+                mnemonic = "ld".to_string();
+            },
+            "ldhl" if ops.len() == 2 => {
+                if let Operand::Expr(text) = &ops[1] {
+                    ops = vec![Operand::Reg("hl".to_string()), Operand::SpOff(text.clone())];
+                }
+                mnemonic = "ld".to_string();
+            },
+            "add" | "adc" | "sbc" if ops.len() == 1 => ops.insert(0, Operand::Reg("a".to_string())),
+            "sub" | "and" | "or" | "xor" | "cp" if ops.len() == 2 => {
+                if let Operand::Reg(reg) = &ops[0] {
+                    if reg == "a" {
+                        ops.remove(0);
+                    }
+                }
+            },
+            "jp" if ops.len() == 1 => {
+                if let Operand::Mem(reg) = &ops[0] {
+                    if reg == "hl" {
+                        ops[0] = Operand::Reg("hl".to_string());
+                    }
+                }
+            },
+            _ => (),
+        }
+        for op in ops.iter_mut() {
+            if let Operand::Mem(reg) = op {
+                if reg == "c" {
+                    *op = Operand::HighC;
+                }
+            }
+        }
+        (mnemonic, ops)
+    }
+
+    fn encode(&self, text: &str, mnemonic: &str, ops: Vec<Operand>, pc: u16) -> AsmResult<Vec<u8>> {
+        let (mnemonic, ops) = self.normalize(mnemonic, ops);
+        let candidates = self.table.get(&mnemonic).ok_or(format!("unknown instruction {}", mnemonic))?;
+        for (pats, info) in candidates {
+            if pats.len() != ops.len() || !pats.iter().zip(ops.iter()).all(|(pat, op)| self.matches(pat, op, pc)) {
+                continue;
+            }
+            let mut bytes = info.opcode_bytes();
+            for (pat, op) in pats.iter().zip(ops.iter()) {
+                bytes.extend(self.imm(pat, op, pc, info.length as u16)?);
+            }
+            return Ok(bytes);
+        }
+        Err(format!("no encoding for '{}'", text))
+    }
+
+    fn data(&self, directive: &str, ops: &[Operand], pc: u16) -> AsmResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        for op in ops {
+            match (directive, op) {
+                ("db", Operand::Str(text)) => bytes.extend(text.bytes()),
+                ("db", Operand::Expr(text)) => bytes.push(self.eval(text, pc)? as u8),
+                ("dw", Operand::Expr(text)) => bytes.extend((self.eval(text, pc)? as u16).to_le_bytes().iter()),
+                _ => return Err(format!("bad {} operand {:?}", directive, op)),
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn line(&mut self, line: &str, pc: u16) -> AsmResult<Vec<u8>> {
+        let mut rest = strip_comment(line).trim();
+
+        // labels, global (`name:` / `name::`) or local (`.name:`)
+        if let Some(idx) = rest.find(':') {
+            let name = &rest[..idx];
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                if !name.starts_with('.') {
+                    self.scope = name.to_string();
+                }
+                self.define(name, pc as i64)?;
+                rest = rest[idx..].trim_start_matches(':').trim();
+            }
+        }
+        if rest.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (word, args) = match rest.find(char::is_whitespace) {
+            Some(idx) => (&rest[..idx], rest[idx..].trim()),
+            None => (rest, ""),
+        };
+        let lower = word.to_lowercase();
+
+        // `name equ expr`
+        if let Some(expr) = args.strip_prefix("equ ").or_else(|| args.strip_prefix("EQU ")) {
+            let val = self.eval(expr, pc)?;
+            self.define(word, val)?;
+            return Ok(vec![]);
+        }
+
+        let ops: Vec<Operand> = split_operands(args).iter().map(|op| parse_operand(op)).collect();
+        match lower.as_str() {
+            "db" | "dw" => self.data(&lower, &ops, pc),
+            "ds" => {
+                let count = match ops.first() {
+                    Some(Operand::Expr(text)) => self.eval(text, pc)?,
+                    _ => return Err("ds needs a size".to_string()),
+                };
+                let fill = match ops.get(1) {
+                    Some(Operand::Expr(text)) => self.eval(text, pc)? as u8,
+                    _ => 0,
+                };
+                Ok(vec![fill; count.max(0) as usize])
+            },
+            _ => self.encode(rest, &lower, ops, pc),
+        }
+    }
+
+    fn pass(&mut self, src: &str, base: u16) -> Result<Vec<u8>, AsmError> {
+        let mut out = Vec::new();
+        self.scope.clear();
+        self.defined.clear();
+        for (idx, line) in src.lines().enumerate() {
+            let pc = base.wrapping_add(out.len() as u16);
+            let bytes = self.line(line, pc).map_err(|msg| AsmError {
+                line: idx + 1,
+                msg,
+            })?;
+            out.extend(bytes);
+        }
+        Ok(out)
+    }
+}
+
+const MAX_PASSES: usize = 8;
+
+// assembles `src` as if loaded at `base`, returning the encoded bytes
+pub fn assemble(src: &str, base: u16) -> Result<Vec<u8>, AsmError> {
+    let mut asm = Assembler::new();
+    // `ds` sizes can depend on forward references, so repeat the first pass
+    // until every symbol settles
+    for _ in 0..MAX_PASSES {
+        let before = asm.symbols.clone();
+        asm.pass(src, base)?;
+        if asm.symbols == before {
+            break;
+        }
+    }
+    asm.final_pass = true;
+    asm.pass(src, base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(src: &str) -> AsmError {
+        assemble(src, 0x0000).unwrap_err()
+    }
+
+    #[test]
+    fn jumps() {
+        assert_eq!(crate::asm!("jp $1234"), vec![0xC3, 0x34, 0x12]);
+        assert_eq!(crate::asm!("jp nz, $1234"), vec![0xC2, 0x34, 0x12]);
+        assert_eq!(crate::asm!("jp hl"), vec![0xE9]);
+        assert_eq!(crate::asm!("jp [hl]"), vec![0xE9]);
+        assert_eq!(crate::asm!("call c, $0040\nret nc\nreti"), vec![0xDC, 0x40, 0x00, 0xD0, 0xD9]);
+        assert_eq!(crate::asm!("rst $38"), vec![0xFF]);
+    }
+
+    #[test]
+    fn jr_is_relative_to_the_next_instruction() {
+        assert_eq!(crate::asm!("jr $0000"), vec![0x18, 0xFE]);
+        assert_eq!(crate::asm!("jr z, $0012", 0x0010), vec![0x28, 0x00]);
+        assert_eq!(crate::asm!("nop\njr c, $0000"), vec![0x00, 0x38, 0xFD]);
+        assert_eq!(crate::asm!("jr $0081"), vec![0x18, 0x7F]);
+        assert_eq!(crate::asm!("jr $0082", 0x0100), vec![0x18, 0x80]);
+    }
+
+    #[test]
+    fn loads() {
+        assert_eq!(crate::asm!("ld [$C000], a"), vec![0xEA, 0x00, 0xC0]);
+        assert_eq!(crate::asm!("ld a, [$C000]"), vec![0xFA, 0x00, 0xC0]);
+        assert_eq!(crate::asm!("ld a, ($C000)"), vec![0xFA, 0x00, 0xC0]);
+        assert_eq!(crate::asm!("ld [$C000], sp"), vec![0x08, 0x00, 0xC0]);
+        assert_eq!(crate::asm!("ld b, c\nld [hl], $42"), vec![0x41, 0x36, 0x42]);
+        assert_eq!(crate::asm!("ld a, [hl+]\nldi [hl], a\nld a, [hld]"), vec![0x2A, 0x22, 0x3A]);
+        assert_eq!(crate::asm!("ld hl, sp+$01\nld sp, hl"), vec![0xF8, 0x01, 0xF9]);
+        assert_eq!(crate::asm!("add sp, -1"), vec![0xE8, 0xFF]);
+    }
+
+    #[test]
+    fn high_loads() {
+        assert_eq!(crate::asm!("ldh [$FF44], a"), vec![0xE0, 0x44]);
+        assert_eq!(crate::asm!("ldh a, [$44]"), vec![0xF0, 0x44]);
+        assert_eq!(crate::asm!("ld [c], a\nld a, [$FF00+c]"), vec![0xE2, 0xF2]);
+    }
+
+    #[test]
+    fn cb_prefixed() {
+        assert_eq!(crate::asm!("bit 7, h"), vec![0xCB, 0x7C]);
+        assert_eq!(crate::asm!("swap a"), vec![0xCB, 0x37]);
+        assert_eq!(crate::asm!("res 0, [hl]"), vec![0xCB, 0x86]);
+        assert_eq!(crate::asm!("set 3, b"), vec![0xCB, 0xD8]);
+        assert_eq!(crate::asm!("rlc b\nsrl a"), vec![0xCB, 0x00, 0xCB, 0x3F]);
+    }
+
+    #[test]
+    fn alu_spellings() {
+        assert_eq!(crate::asm!("add a, b\nadd b"), vec![0x80, 0x80]);
+        assert_eq!(crate::asm!("sub a, $10\nsub $10"), vec![0xD6, 0x10, 0xD6, 0x10]);
+        assert_eq!(crate::asm!("cp [hl]\nxor a"), vec![0xBE, 0xAF]);
+        assert_eq!(crate::asm!("sbc a, a\nadc a, $01"), vec![0x9F, 0xCE, 0x01]);
+    }
+
+    #[test]
+    fn labels() {
+        let src = "start: nop\n.loop: dec b\njr nz, .loop\njp start\nother:\n.loop: jr .loop";
+        assert_eq!(crate::asm!(src, 0x0150), vec![0x00, 0x05, 0x20, 0xFD, 0xC3, 0x50, 0x01, 0x18, 0xFE]);
+        // forward references resolve on the second pass
+        assert_eq!(crate::asm!("ld hl, data\nret\ndata: db 1, 2"), vec![0x21, 0x04, 0x00, 0xC9, 0x01, 0x02]);
+        assert_eq!(crate::asm!("VAL equ $20 + 2\nld a, VAL * 2"), vec![0x3E, 0x44]);
+        // sizes that depend on later symbols take extra passes to settle
+        assert_eq!(crate::asm!("ds size\nend: dw end\nsize equ 2"), vec![0x00, 0x00, 0x02, 0x00]);
+        assert_eq!(crate::asm!("ds y - x\nx: ds 1\ny: dw y"), vec![0x00, 0x00, 0x02, 0x00]);
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(crate::asm!("db \"Hi\", $FF\ndw $1234"), vec![b'H', b'i', 0xFF, 0x34, 0x12]);
+        assert_eq!(crate::asm!("ds 3, $AA ; padding"), vec![0xAA; 3]);
+        assert_eq!(crate::asm!("dw @", 0x4000), vec![0x00, 0x40]);
+    }
+
+    #[test]
+    fn bad_operands() {
+        assert_eq!(err("ld a, [bc+1]").msg, "undefined symbol bc");
+        assert!(err("jp $10000").msg.contains("out of range"));
+        assert!(err("ld a, $100").msg.contains("out of range"));
+        assert!(err("ld [hl], [hl]").msg.contains("no encoding"));
+        assert!(err("bit 8, a").msg.contains("no encoding"));
+        assert!(err("ld sp, af").msg.contains("no encoding"));
+        assert!(err("frob a").msg.contains("unknown instruction"));
+    }
+
+    #[test]
+    fn bad_programs() {
+        let error = err("nop\njp nowhere");
+        assert_eq!(error.line, 2);
+        assert!(error.msg.contains("undefined symbol nowhere"));
+        assert!(err("jr far\nds 200\nfar:").msg.contains("out of range"));
+        assert!(err("a: nop\na: nop").msg.contains("defined twice"));
+        assert!(err("a:\na:").msg.contains("defined twice"));
+        assert!(err("a:\n.b:\n.b:").msg.contains("a.b defined twice"));
+        assert!(err("n equ 1\nn equ 1").msg.contains("defined twice"));
+        assert!(err("ds 1 - x\nx:").msg.contains("x moved on the final pass"));
+        assert_eq!(err("ld a, (1").to_string(), "asm error on line 1: missing )");
+    }
+
+    #[test]
+    fn bad_arithmetic() {
+        assert!(err("db 1 << 64").msg.contains("out of range"));
+        assert!(err("db 1 >> -1").msg.contains("out of range"));
+        assert_eq!(err("dw $7FFFFFFFFFFFFFFF + 1").msg, "arithmetic overflow");
+        assert_eq!(err("dw -$7FFFFFFFFFFFFFFF - 2").msg, "arithmetic overflow");
+        assert_eq!(err("dw $7FFFFFFFFFFFFFFF * 2").msg, "arithmetic overflow");
+        assert_eq!(err("dw -(-$7FFFFFFFFFFFFFFF - 1)").msg, "arithmetic overflow");
+        assert_eq!(err("db 1 % 0").msg, "division by zero");
+        assert_eq!(crate::asm!("db 1 << 7, $80 >> 7"), vec![0x80, 0x01]);
+    }
+
+    #[test]
+    #[should_panic(expected = "asm error on line 1")]
+    fn macro_panics_on_errors() {
+        crate::asm!("ld a, b, c");
+    }
+}
@@ -0,0 +1,65 @@
+// Headless runner for Blargg's test roms (cpu_instrs, instr_timing,
+// mem_timing, halt_bug). They print their progress over the serial port and
+// finish by printing "Passed" or "Failed".
+
+use crate::boot_rom::BootRom;
+use crate::gameboy;
+use crate::runner::{self, Outcome};
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// two minutes of emulated time, enough for the combined cpu_instrs rom
+pub const DEFAULT_CYCLE_BUDGET: u64 = 4_194_304 * 120;
+
+pub struct BlarggResult {
+    pub outcome: Outcome,
+    pub output: String,
+    pub cycles: u64,
+}
+
+pub fn run_rom(rom: &[u8], boot_rom: Option<&BootRom>, budget: u64) -> BlarggResult {
+    let mut gb = runner::load(rom, boot_rom);
+    gb.mother.serial.start_output();
+
+    let mut seen = 0;
+    let outcome = loop {
+        if let Err(msg) = gb.try_step() {
+            break Outcome::Crashed(msg);
+        }
+        let output = gb.mother.serial.output();
+        if output.len() != seen {
+            seen = output.len();
+            let text = String::from_utf8_lossy(output);
+            if text.contains("Passed") {
+                break Outcome::Passed;
+            }
+            if text.contains("Failed") {
+                break Outcome::Failed;
+            }
+        }
+        if gb.cycles >= budget {
+            break Outcome::Timeout;
+        }
+    };
+
+    BlarggResult {
+        outcome,
+        output: String::from_utf8_lossy(gb.mother.serial.output()).to_string(),
+        cycles: gb.cycles,
+    }
+}
+
+// runs every rom under `dir`, in path order
+pub fn run_dir(dir: &Path, boot_rom: Option<&BootRom>, budget: u64) -> io::Result<Vec<(PathBuf, BlarggResult)>> {
+    let roms = runner::find_roms(dir)?;
+    gameboy::quiet_panics(|| {
+        roms.into_iter()
+            .map(|path| {
+                let result = run_rom(&fs::read(&path)?, boot_rom, budget);
+                Ok((path, result))
+            })
+            .collect()
+    })
+}
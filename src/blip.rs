@@ -0,0 +1,218 @@
+// Band-limited step synthesis, in the style of blip_buf. A signal made of
+// steps is described by the time (in clock ticks) and size of each change.
+// Rather than point sampling the steps, which aliases anything above half
+// the sample rate back down, each change is added as a windowed sinc
+// impulse into a buffer of sample differences, and reading integrates them
+// back into samples. Time is counted from the start of the current frame,
+// which end_frame moves forward. Positions are worked out in whole clock
+// ticks, so the samples made for a run depend only on the ticks fed in.
+
+use std::f64::consts::PI;
+
+// kernel taps per impulse, and how many sub-sample positions it's made for
+const WIDTH: usize = 16;
+const PHASES: usize = 32;
+
+// cutoff as a fraction of the output nyquist frequency
+const CUTOFF: f64 = 0.9;
+
+pub struct BlipBuf {
+    clock_rate: u64,
+    sample_rate: u64,
+    // clock ticks from the start to the current frame
+    clocks: u64,
+    // samples read so far
+    read: u64,
+    // sample differences from the first unread sample on
+    diffs: Vec<f32>,
+    // running sum of the differences read so far
+    level: f32,
+    kernel: [[f32; WIDTH]; PHASES + 1],
+}
+
+impl BlipBuf {
+    pub fn new(clock_rate: u32, sample_rate: u32) -> Self {
+        Self {
+            clock_rate: clock_rate as u64,
+            sample_rate: sample_rate as u64,
+            clocks: 0,
+            read: 0,
+            diffs: vec![0.0; WIDTH],
+            level: 0.0,
+            kernel: kernel(),
+        }
+    }
+
+    // adds a step of `delta` `time` clock ticks into the frame
+    pub fn add_delta(&mut self, time: u32, delta: f32) {
+        let pos = (self.clocks + time as u64) * self.sample_rate;
+        let start = (pos / self.clock_rate - self.read) as usize;
+        let frac = pos % self.clock_rate;
+        let phase = ((frac * PHASES as u64 * 2 + self.clock_rate) / (self.clock_rate * 2)) as usize;
+        if self.diffs.len() < start + WIDTH {
+            self.diffs.resize(start + WIDTH, 0.0);
+        }
+        for (diff, tap) in self.diffs[start..start + WIDTH].iter_mut().zip(self.kernel[phase]) {
+            *diff += delta * tap;
+        }
+    }
+
+    // ends the frame `time` clock ticks in, making the samples before it
+    // ready to read
+    pub fn end_frame(&mut self, time: u32) {
+        self.clocks += time as u64;
+        let avail = self.samples_avail();
+        if self.diffs.len() < avail + WIDTH {
+            self.diffs.resize(avail + WIDTH, 0.0);
+        }
+    }
+
+    pub fn samples_avail(&self) -> usize {
+        (self.clocks * self.sample_rate / self.clock_rate - self.read) as usize
+    }
+
+    // reads every ready sample into `out`
+    pub fn read_samples(&mut self, out: &mut Vec<f32>) {
+        let count = self.samples_avail();
+        for diff in self.diffs.drain(..count) {
+            self.level += diff;
+            out.push(self.level);
+        }
+        self.read += count as u64;
+    }
+}
+
+// the impulse for each sub-sample phase: a sinc cut off just under nyquist,
+// shaped by a blackman window and scaled so every phase sums to 1
+fn kernel() -> [[f32; WIDTH]; PHASES + 1] {
+    let mut kernel = [[0.0; WIDTH]; PHASES + 1];
+    for (phase, taps) in kernel.iter_mut().enumerate() {
+        let offset = phase as f64 / PHASES as f64;
+        let mut raw = [0.0; WIDTH];
+        for (i, tap) in raw.iter_mut().enumerate() {
+            let x = i as f64 - (WIDTH / 2) as f64 + 1.0 - offset;
+            let sinc = if x == 0.0 { 1.0 } else { (PI * CUTOFF * x).sin() / (PI * CUTOFF * x) };
+            let w = (x + WIDTH as f64 / 2.0) / WIDTH as f64;
+            let window = 0.42 - 0.5 * (2.0 * PI * w).cos() + 0.08 * (4.0 * PI * w).cos();
+            *tap = sinc * window.max(0.0);
+        }
+        let sum: f64 = raw.iter().sum();
+        for (tap, val) in taps.iter_mut().zip(raw) {
+            *tap = (val / sum) as f32;
+        }
+    }
+    kernel
+}
+
+// the analog high pass filter on the console's output: a capacitor that
+// slowly charges to the DC level and blocks it. `charge` is the fraction of
+// the difference it keeps per clock tick
+pub struct HighPass {
+    cap: f32,
+    factor: f32,
+}
+
+impl HighPass {
+    pub fn new(charge: f64, clock_rate: u32, sample_rate: u32) -> Self {
+        Self {
+            cap: 0.0,
+            factor: charge.powf(clock_rate as f64 / sample_rate as f64) as f32,
+        }
+    }
+
+    pub fn filter(&mut self, input: f32) -> f32 {
+        let out = input - self.cap;
+        self.cap = input - out * self.factor;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOCK: u32 = 4_194_304;
+    const RATE: u32 = 48_000;
+
+    fn read(buf: &mut BlipBuf) -> Vec<f32> {
+        let mut out = Vec::new();
+        buf.read_samples(&mut out);
+        out
+    }
+
+    #[test]
+    fn kernel_phases_sum_to_one() {
+        for taps in kernel() {
+            let sum: f32 = taps.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn sample_count() {
+        let mut buf = BlipBuf::new(CLOCK, RATE);
+        buf.end_frame(CLOCK / 2);
+        assert_eq!(buf.samples_avail(), RATE as usize / 2);
+        assert_eq!(read(&mut buf).len(), RATE as usize / 2);
+        assert_eq!(buf.samples_avail(), 0);
+        buf.end_frame(CLOCK / 2);
+        assert_eq!(read(&mut buf).len(), RATE as usize / 2);
+    }
+
+    #[test]
+    fn step_settles() {
+        let mut buf = BlipBuf::new(CLOCK, RATE);
+        buf.add_delta(1000, 0.5);
+        buf.end_frame(4000);
+        let out = read(&mut buf);
+        assert!(out[..5].iter().all(|s| *s == 0.0));
+        assert!((out.last().unwrap() - 0.5).abs() < 1e-5);
+        // the step is smeared around where it happened rather than landing
+        // on one sample
+        let start = 1000 * RATE as usize / CLOCK as usize;
+        let rising = out.iter().filter(|s| **s > 0.01 && **s < 0.49).count();
+        assert!(rising > 2 && rising < WIDTH);
+        assert!(out[start + WIDTH..].iter().all(|s| (s - 0.5).abs() < 1e-5));
+    }
+
+    #[test]
+    fn frames_dont_change_the_output() {
+        let deltas = [(10, 0.25), (3000, -0.5), (9000, 0.75), (20000, -0.5)];
+        let mut whole = BlipBuf::new(CLOCK, RATE);
+        for (time, delta) in deltas {
+            whole.add_delta(time, delta);
+        }
+        whole.end_frame(30000);
+        let whole = read(&mut whole);
+
+        let mut split = BlipBuf::new(CLOCK, RATE);
+        let mut out = Vec::new();
+        let mut frame = 0;
+        for (time, delta) in deltas {
+            if time - frame > 4096 {
+                split.end_frame(4096);
+                split.read_samples(&mut out);
+                frame += 4096;
+            }
+            split.add_delta(time - frame, delta);
+        }
+        split.end_frame(30000 - frame);
+        split.read_samples(&mut out);
+        assert_eq!(out, whole);
+    }
+
+    #[test]
+    fn high_pass() {
+        let mut filter = HighPass::new(0.998943, CLOCK, RATE);
+        let first = filter.filter(1.0);
+        assert_eq!(first, 1.0);
+        let mut out = first;
+        for _ in 0..RATE {
+            out = filter.filter(1.0);
+        }
+        // a second of DC is blocked
+        assert!(out.abs() < 1e-3);
+        // and a step back down swings the other way
+        assert!(filter.filter(0.0) < -0.99);
+    }
+}
@@ -0,0 +1,111 @@
+// User supplied boot roms. Nothing is bundled: a dump is loaded from a path,
+// checked against the known dumps and overlaid on the cartridge until the
+// program writes to 0xFF50.
+
+use crate::model::Model;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+pub const DMG_SIZE: usize = 0x100;
+pub const CGB_SIZE: usize = 0x900;
+
+// disables the boot rom overlay for good
+pub const BOOT_OFF: u16 = 0xFF50;
+// the cgb boot rom writes the cartridge's cgb flag here, or DMG_MODE for a
+// dmg cartridge. It takes effect, and locks, on BOOT_OFF
+pub const KEY0: u16 = 0xFF4C;
+pub const DMG_MODE: u8 = 0x04;
+
+// crc32 of the known dumps
+const KNOWN: [(u32, Model); 6] = [
+    (0xC2F5CC97, Model::DMG0),
+    (0x59C8598E, Model::DMG),
+    (0xE6920754, Model::MGB),
+    (0xEC8A83B9, Model::SGB),
+    (0x53D0DD63, Model::SGB2),
+    (0x41884E46, Model::CGB),
+];
+
+#[derive(Debug)]
+pub enum BootRomError {
+    Io(String),
+    BadSize(usize),
+    UnknownHash(u32),
+    WrongModel(Model, Model),
+}
+
+impl fmt::Display for BootRomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BootRomError::Io(msg) => write!(f, "failed to read boot rom: {}", msg),
+            BootRomError::BadSize(size) => {
+                write!(f, "boot rom is {} bytes, expected {} (dmg) or {} (cgb)", size, DMG_SIZE, CGB_SIZE)
+            },
+            BootRomError::UnknownHash(crc) => write!(f, "unknown boot rom (crc32 {:08x})", crc),
+            BootRomError::WrongModel(boot_rom, model) => write!(f, "{:?} boot rom can't start a {:?}", boot_rom, model),
+        }
+    }
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[derive(Clone)]
+pub struct BootRom {
+    data: Vec<u8>,
+    pub model: Model,
+}
+
+impl BootRom {
+    // accepts only the known dumps, identifying the model from the hash
+    pub fn new(data: Vec<u8>) -> Result<Self, BootRomError> {
+        if data.len() != DMG_SIZE && data.len() != CGB_SIZE {
+            return Err(BootRomError::BadSize(data.len()));
+        }
+        let crc = crc32(&data);
+        match KNOWN.iter().find(|(known, _)| *known == crc) {
+            Some((_, model)) => Ok(Self {
+                data,
+                model: *model,
+            }),
+            None => Err(BootRomError::UnknownHash(crc)),
+        }
+    }
+
+    // for replacement boot roms that match no dump, checking only the size
+    pub fn new_unverified(data: Vec<u8>, model: Model) -> Result<Self, BootRomError> {
+        let expected = if model.is_cgb() { CGB_SIZE } else { DMG_SIZE };
+        if data.len() != expected {
+            return Err(BootRomError::BadSize(data.len()));
+        }
+        Ok(Self {
+            data,
+            model,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BootRomError> {
+        let data = fs::read(path).map_err(|err| BootRomError::Io(err.to_string()))?;
+        Self::new(data)
+    }
+
+    // the byte the overlay puts at `addr`, if it covers it. cgb boot roms
+    // leave 0x0100-0x01FF to the cartridge header
+    pub fn read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x0000..=0x00FF => Some(self.data[addr as usize]),
+            0x0200..=0x08FF => self.data.get(addr as usize).copied(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// Recording what the APU plays over a stretch of emulated time. The APU
+// makes samples as the instructions run, and the recording is cut to exactly
+// the samples `cycles` dots make at the sample rate, even when the last
+// instruction runs past the end.
+
+use crate::apu;
+use crate::gameboy::GameBoy;
+use crate::wav;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct Recording {
+    pub sample_rate: u32,
+    // interleaved left and right samples
+    pub samples: Vec<i16>,
+    // each channel's DAC output on its own, before panning and mixing
+    pub stems: Option<[Vec<i16>; 4]>,
+}
+
+impl Recording {
+    // stereo frames recorded
+    pub fn len(&self) -> usize {
+        self.samples.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    // writes the mix to `path` and any stems next to it, see stem_path
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        wav::write(path, 2, self.sample_rate, &self.samples)?;
+        for (i, stem) in self.stems.iter().flatten().enumerate() {
+            wav::write(&stem_path(path, i + 1), 1, self.sample_rate, stem)?;
+        }
+        Ok(())
+    }
+}
+
+// where channel `channel`'s stem of a recording saved to `path` goes:
+// out.wav becomes out_ch1.wav
+pub fn stem_path(path: &Path, channel: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}_ch{}.wav", stem, channel))
+}
+
+// runs `gb` for `cycles` T-cycles of the 4 MiHz clock (the cpu's own,
+// outside double speed), recording at `sample_rate`
+pub fn record(gb: &mut GameBoy, cycles: u64, sample_rate: u32, stems: bool) -> Recording {
+    let apu = &mut gb.mother.apu;
+    apu.set_sample_rate(sample_rate);
+    apu.set_stems(stems);
+    while gb.mother.apu.dots() < cycles {
+        gb.step();
+    }
+
+    let frames = apu::scale_dots(cycles, sample_rate as u64) as usize;
+    let mut samples = gb.mother.apu.take_samples();
+    samples.truncate(frames * 2);
+    let stems = gb.mother.apu.take_stems().map(|stems| {
+        stems.map(|mut stem| {
+            stem.truncate(frames);
+            stem
+        })
+    });
+    gb.mother.apu.set_stems(false);
+    Recording {
+        sample_rate,
+        samples,
+        stems,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apu::CLOCK_RATE;
+    use crate::model::Model;
+    use crate::motherboard::Motherboard;
+
+    // playing a square wave on channel 2, panned to the left, with the rest
+    // of the APU reset
+    fn gb() -> GameBoy {
+        let code = crate::asm!(
+            "xor a
+            ldh [$FF26], a
+            ld a, $80
+            ldh [$FF26], a
+            ld a, $77
+            ldh [$FF24], a
+            ld a, $20
+            ldh [$FF25], a
+            ld a, $F0
+            ldh [$FF17], a
+            ld a, $87
+            ldh [$FF19], a
+            jr @",
+            0x0100
+        );
+        let mut rom = vec![0; 0x8000];
+        rom[0x0100..0x0100 + code.len()].copy_from_slice(&code);
+        let mut mother = Motherboard::new();
+        mother.load_rom(&rom);
+        mother.skip_boot(Model::DMG);
+        let mut gb = GameBoy::new(mother);
+        for _ in 0..13 {
+            gb.step();
+        }
+        gb
+    }
+
+    #[test]
+    fn length() {
+        let recording = record(&mut gb(), CLOCK_RATE as u64 / 8, 48_000, false);
+        assert_eq!(recording.len(), 6000);
+        assert_eq!(recording.samples.len(), 12000);
+        assert!(recording.stems.is_none());
+        assert!(recording.samples.iter().step_by(2).any(|s| *s != 0));
+        assert!(recording.samples.iter().skip(1).step_by(2).all(|s| *s == 0));
+    }
+
+    #[test]
+    fn repeatable() {
+        let first = record(&mut gb(), 100_000, 44_100, true);
+        let second = record(&mut gb(), 100_000, 44_100, true);
+        assert_eq!(first.samples, second.samples);
+        assert_eq!(first.stems, second.stems);
+    }
+
+    #[test]
+    fn stems() {
+        let recording = record(&mut gb(), 100_000, 32_000, true);
+        let stems = recording.stems.as_ref().unwrap();
+        for stem in stems {
+            assert_eq!(stem.len(), recording.len());
+        }
+        assert!(stems[1].iter().any(|s| *s != 0));
+        // the wave channel's DAC is off
+        assert!(stems[2].iter().all(|s| *s == 0));
+    }
+
+    #[test]
+    fn stem_paths() {
+        assert_eq!(stem_path(Path::new("out/song.wav"), 3), Path::new("out/song_ch3.wav"));
+        assert_eq!(stem_path(Path::new("song"), 1), Path::new("song_ch1.wav"));
+    }
+}
@@ -0,0 +1,555 @@
+// The four sound generators the APU mixes, and the length counter and volume
+// envelope units they share. Every generator counts down a timer in dots and
+// outputs a digital 0-15 level; the APU turns those into samples.
+
+const DUTY_PATTERNS: [u8; 4] = [0b0000_0001, 0b1000_0001, 0b1000_0111, 0b0111_1110];
+
+// noise timer divisor for each NR43 divisor code
+const NOISE_DIVISORS: [u32; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
+
+// right shifts of the wave sample for each NR32 volume code
+const WAVE_SHIFTS: [u8; 4] = [4, 0, 1, 2];
+
+pub const WAVE_RAM_SIZE: usize = 16;
+
+// turns the channel off when it runs out, if enabled in NRx4
+pub struct Length {
+    counter: u16,
+    max: u16,
+    enabled: bool,
+}
+
+impl Length {
+    fn new(max: u16) -> Self {
+        Self {
+            counter: 0,
+            max,
+            enabled: false,
+        }
+    }
+
+    pub fn load(&mut self, val: u8) {
+        self.counter = self.max - val as u16;
+    }
+
+    pub fn counter(&self) -> u16 {
+        self.counter
+    }
+
+    pub fn set_counter(&mut self, counter: u16) {
+        self.counter = counter;
+    }
+
+    // frame sequencer clock, returning true when the channel should stop
+    fn clock(&mut self) -> bool {
+        if self.enabled && self.counter > 0 {
+            self.counter -= 1;
+            return self.counter == 0;
+        }
+        false
+    }
+
+    // NRx4 length enable. Turning it on while the frame sequencer's next
+    // step doesn't clock length (`extra`) clocks it once right away,
+    // returning true if that ran it out
+    fn set_enabled(&mut self, enabled: bool, extra: bool) -> bool {
+        let was_enabled = self.enabled;
+        self.enabled = enabled;
+        !was_enabled && enabled && extra && self.clock()
+    }
+
+    fn trigger(&mut self, extra: bool) {
+        if self.counter == 0 {
+            self.counter = self.max;
+            if self.enabled && extra {
+                self.counter -= 1;
+            }
+        }
+    }
+}
+
+// NRx2: initial volume, direction and period of the volume envelope
+pub struct Envelope {
+    reg: u8,
+    volume: u8,
+    timer: u8,
+}
+
+impl Envelope {
+    fn new() -> Self {
+        Self {
+            reg: 0,
+            volume: 0,
+            timer: 0,
+        }
+    }
+
+    fn period(&self) -> u8 {
+        self.reg & 0x07
+    }
+
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    // the DAC is off when the top five bits are clear
+    fn dac_on(&self) -> bool {
+        self.reg & 0xF8 != 0
+    }
+
+    fn trigger(&mut self) {
+        self.volume = self.reg >> 4;
+        self.timer = self.period();
+    }
+
+    fn clock(&mut self) {
+        if self.period() == 0 {
+            return;
+        }
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            if self.reg & 0x08 != 0 && self.volume < 15 {
+                self.volume += 1;
+            }
+            else if self.reg & 0x08 == 0 && self.volume > 0 {
+                self.volume -= 1;
+            }
+        }
+    }
+}
+
+// NR10: channel 1's frequency sweep, which works on a shadow copy of the
+// frequency and turns the channel off when it would overflow
+pub struct Sweep {
+    reg: u8,
+    shadow: u16,
+    timer: u8,
+    enabled: bool,
+    // a subtraction ran since the last trigger
+    negated: bool,
+}
+
+impl Sweep {
+    pub fn new() -> Self {
+        Self {
+            reg: 0,
+            shadow: 0,
+            timer: 0,
+            enabled: false,
+            negated: false,
+        }
+    }
+
+    fn period(&self) -> u8 {
+        (self.reg >> 4) & 0x07
+    }
+
+    fn negate(&self) -> bool {
+        self.reg & 0x08 != 0
+    }
+
+    fn shift(&self) -> u8 {
+        self.reg & 0x07
+    }
+
+    fn reload(&mut self) {
+        self.timer = if self.period() == 0 { 8 } else { self.period() };
+    }
+
+    fn next_freq(&mut self) -> u16 {
+        let delta = self.shadow >> self.shift();
+        if self.negate() {
+            self.negated = true;
+            self.shadow - delta
+        }
+        else {
+            self.shadow + delta
+        }
+    }
+
+    // returns false if the write turns channel 1 off: clearing negate after
+    // a subtraction has been used
+    pub fn write(&mut self, val: u8) -> bool {
+        let cleared = self.negate() && val & 0x08 == 0;
+        self.reg = val;
+        !(cleared && self.negated)
+    }
+
+    // returns false if the overflow check turns the channel off
+    fn trigger(&mut self, freq: u16) -> bool {
+        self.shadow = freq;
+        self.negated = false;
+        self.reload();
+        self.enabled = self.period() != 0 || self.shift() != 0;
+        self.shift() == 0 || self.next_freq() <= 0x7FF
+    }
+
+    // frame sequencer clock, returning the channel's new frequency, or Err
+    // when it overflowed and the channel stops
+    fn clock(&mut self) -> Result<Option<u16>, ()> {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer > 0 {
+            return Ok(None);
+        }
+        self.reload();
+        if !self.enabled || self.period() == 0 {
+            return Ok(None);
+        }
+        let freq = self.next_freq();
+        if freq > 0x7FF {
+            return Err(());
+        }
+        if self.shift() == 0 {
+            return Ok(None);
+        }
+        self.shadow = freq;
+        if self.next_freq() > 0x7FF {
+            return Err(());
+        }
+        Ok(Some(freq))
+    }
+}
+
+// channels 1 and 2
+pub struct Pulse {
+    pub enabled: bool,
+    duty: u8,
+    pos: u8,
+    freq: u16,
+    timer: u16,
+    pub length: Length,
+    pub envelope: Envelope,
+    pub sweep: Sweep,
+}
+
+impl Pulse {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            duty: 0,
+            pos: 0,
+            freq: 0,
+            timer: 0,
+            length: Length::new(64),
+            envelope: Envelope::new(),
+            sweep: Sweep::new(),
+        }
+    }
+
+    fn period(&self) -> u16 {
+        (2048 - self.freq) * 4
+    }
+
+    pub fn freq(&self) -> u16 {
+        self.freq
+    }
+
+    pub fn dac_on(&self) -> bool {
+        self.envelope.dac_on()
+    }
+
+    pub fn output(&self) -> u8 {
+        let high = DUTY_PATTERNS[self.duty as usize] >> (7 - self.pos) & 1 != 0;
+        if self.enabled && high { self.envelope.volume } else { 0 }
+    }
+
+    pub fn step(&mut self) {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            self.pos = (self.pos + 1) & 7;
+        }
+    }
+
+    pub fn write_nrx1(&mut self, val: u8) {
+        self.duty = val >> 6;
+        self.length.load(val & 0x3F);
+    }
+
+    pub fn write_nrx2(&mut self, val: u8) {
+        self.envelope.reg = val;
+        if !self.dac_on() {
+            self.enabled = false;
+        }
+    }
+
+    pub fn write_nrx3(&mut self, val: u8) {
+        self.freq = (self.freq & 0x700) | val as u16;
+    }
+
+    // `sweep` is set for channel 1
+    pub fn write_nrx4(&mut self, val: u8, extra: bool, sweep: bool) {
+        self.freq = (self.freq & 0xFF) | ((val as u16 & 0x07) << 8);
+        let expired = self.length.set_enabled(val & 0x40 != 0, extra);
+        if val & 0x80 != 0 {
+            self.enabled = self.dac_on();
+            self.length.trigger(extra);
+            self.envelope.trigger();
+            self.timer = self.period();
+            if sweep && !self.sweep.trigger(self.freq) {
+                self.enabled = false;
+            }
+        }
+        else if expired {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_sweep(&mut self) {
+        match self.sweep.clock() {
+            Ok(Some(freq)) => self.freq = freq,
+            Ok(None) => (),
+            Err(()) => self.enabled = false,
+        }
+    }
+
+    pub fn write_sweep(&mut self, val: u8) {
+        if !self.sweep.write(val) {
+            self.enabled = false;
+        }
+    }
+
+    // back to the duty step the frame sequencer reset leaves it at
+    pub fn reset_duty(&mut self) {
+        self.pos = 0;
+    }
+}
+
+// channel 3, playing 32 4-bit samples from wave RAM
+pub struct Wave {
+    pub enabled: bool,
+    dac: bool,
+    volume: u8,
+    freq: u16,
+    timer: u16,
+    pos: u8,
+    // the sample last read from wave RAM
+    sample: u8,
+    pub length: Length,
+}
+
+impl Wave {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            dac: false,
+            volume: 0,
+            freq: 0,
+            timer: 0,
+            pos: 0,
+            sample: 0,
+            length: Length::new(256),
+        }
+    }
+
+    fn period(&self) -> u16 {
+        (2048 - self.freq) * 2
+    }
+
+    pub fn freq(&self) -> u16 {
+        self.freq
+    }
+
+    // the NR32 output level as a 0-15 volume
+    pub fn volume(&self) -> u8 {
+        0x0F >> WAVE_SHIFTS[self.volume as usize]
+    }
+
+    pub fn dac_on(&self) -> bool {
+        self.dac
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.enabled { self.sample >> WAVE_SHIFTS[self.volume as usize] } else { 0 }
+    }
+
+    // wave RAM byte the channel is reading
+    pub fn ram_index(&self) -> usize {
+        self.pos as usize / 2
+    }
+
+    pub fn step(&mut self, ram: &[u8; WAVE_RAM_SIZE]) {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            self.pos = (self.pos + 1) & 31;
+            let byte = ram[self.ram_index()];
+            self.sample = if self.pos & 1 == 0 { byte >> 4 } else { byte & 0x0F };
+        }
+    }
+
+    pub fn write_nr30(&mut self, val: u8) {
+        self.dac = val & 0x80 != 0;
+        if !self.dac {
+            self.enabled = false;
+        }
+    }
+
+    pub fn write_nr31(&mut self, val: u8) {
+        self.length.load(val);
+    }
+
+    pub fn write_nr32(&mut self, val: u8) {
+        self.volume = (val >> 5) & 0x03;
+    }
+
+    pub fn write_nr33(&mut self, val: u8) {
+        self.freq = (self.freq & 0x700) | val as u16;
+    }
+
+    pub fn write_nr34(&mut self, val: u8, extra: bool) {
+        self.freq = (self.freq & 0xFF) | ((val as u16 & 0x07) << 8);
+        let expired = self.length.set_enabled(val & 0x40 != 0, extra);
+        if val & 0x80 != 0 {
+            // the sample buffer isn't refilled, so the old sample plays
+            // until the first step
+            self.enabled = self.dac;
+            self.length.trigger(extra);
+            self.timer = self.period();
+            self.pos = 0;
+        }
+        else if expired {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+}
+
+// channel 4, clocking a 15 bit (or 7 bit) linear feedback shift register
+pub struct Noise {
+    pub enabled: bool,
+    poly: u8,
+    lfsr: u16,
+    timer: u32,
+    pub length: Length,
+    pub envelope: Envelope,
+}
+
+impl Noise {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            poly: 0,
+            lfsr: 0,
+            timer: 0,
+            length: Length::new(64),
+            envelope: Envelope::new(),
+        }
+    }
+
+    // shifts 14 and 15 stop the shift register
+    fn period(&self) -> Option<u32> {
+        let shift = self.poly >> 4;
+        if shift >= 14 {
+            return None;
+        }
+        Some(NOISE_DIVISORS[(self.poly & 0x07) as usize] << shift)
+    }
+
+    pub fn dac_on(&self) -> bool {
+        self.envelope.dac_on()
+    }
+
+    // NR43
+    pub fn poly(&self) -> u8 {
+        self.poly
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.enabled && self.lfsr & 1 == 0 { self.envelope.volume } else { 0 }
+    }
+
+    pub fn step(&mut self) {
+        let Some(period) = self.period() else {
+            return;
+        };
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = period;
+            let bit = (self.lfsr ^ (self.lfsr >> 1)) & 1;
+            self.lfsr = (self.lfsr >> 1) | (bit << 14);
+            if self.poly & 0x08 != 0 {
+                self.lfsr = (self.lfsr & !0x40) | (bit << 6);
+            }
+        }
+    }
+
+    pub fn write_nr41(&mut self, val: u8) {
+        self.length.load(val & 0x3F);
+    }
+
+    pub fn write_nr42(&mut self, val: u8) {
+        self.envelope.reg = val;
+        if !self.dac_on() {
+            self.enabled = false;
+        }
+    }
+
+    pub fn write_nr43(&mut self, val: u8) {
+        self.poly = val;
+    }
+
+    pub fn write_nr44(&mut self, val: u8, extra: bool) {
+        let expired = self.length.set_enabled(val & 0x40 != 0, extra);
+        if val & 0x80 != 0 {
+            self.enabled = self.dac_on();
+            self.length.trigger(extra);
+            self.envelope.trigger();
+            self.lfsr = 0x7FFF;
+            self.timer = self.period().unwrap_or(0);
+        }
+        else if expired {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Pulse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
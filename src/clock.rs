@@ -0,0 +1,142 @@
+// CGB double speed, and the clock split it brings. The cpu, timer, serial
+// port and OAM DMA run off the cpu clock, which doubles in double speed,
+// while the ppu and apu stay on the fixed 4 MiHz dot clock. The step loop
+// feeds the cycles of each instruction through here so every component gets
+// ticks on its own clock. KEY1 (0xFF4D) arms the switch, which happens on
+// the next STOP.
+
+pub const KEY1: u16 = 0xFF4D;
+
+const KEY1_PREPARE: u8 = 0x01;
+const KEY1_SPEED: u8 = 0x80;
+const KEY1_UNUSED: u8 = 0x7E;
+
+// m-cycles the cpu stays stopped for while the speed switches
+pub const SWITCH_DELAY: u32 = 2050;
+
+// one instruction's worth of time on each clock
+pub struct Ticks {
+    // T-cycles of the cpu clock
+    pub cpu: u8,
+    // dots of the 4 MiHz clock
+    pub dots: u8,
+}
+
+pub struct Clock {
+    double_speed: bool,
+    prepare: bool,
+    // a half dot left over from an odd cycle count in double speed
+    half_dot: bool,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self {
+            double_speed: false,
+            prepare: false,
+            half_dot: false,
+        }
+    }
+
+    pub fn double_speed(&self) -> bool {
+        self.double_speed
+    }
+
+    // splits `cycles` cpu T-cycles into ticks for each clock
+    pub fn advance(&mut self, cycles: u8) -> Ticks {
+        let dots = if self.double_speed {
+            let total = cycles as u16 + self.half_dot as u16;
+            self.half_dot = total % 2 == 1;
+            (total / 2) as u8
+        }
+        else {
+            cycles
+        };
+        Ticks {
+            cpu: cycles,
+            dots,
+        }
+    }
+
+    // cpu T-cycles that `dots` dots take
+    pub fn cpu_cycles(&self, dots: u32) -> u32 {
+        if self.double_speed { dots * 2 } else { dots }
+    }
+
+    // switches speed if KEY1 armed it, returning true if it did
+    pub fn stop(&mut self) -> bool {
+        if !self.prepare {
+            return false;
+        }
+        self.prepare = false;
+        self.double_speed = !self.double_speed;
+        self.half_dot = false;
+        true
+    }
+
+    pub fn read(&self) -> u8 {
+        let speed = if self.double_speed { KEY1_SPEED } else { 0 };
+        let prepare = if self.prepare { KEY1_PREPARE } else { 0 };
+        KEY1_UNUSED | speed | prepare
+    }
+
+    pub fn write(&mut self, val: u8) {
+        self.prepare = val & KEY1_PREPARE != 0;
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key1() {
+        let mut clock = Clock::new();
+        assert_eq!(clock.read(), 0x7E);
+        clock.write(0xFF);
+        assert_eq!(clock.read(), 0x7F);
+        assert!(clock.stop());
+        assert_eq!(clock.read(), 0xFE);
+        // STOP without arming it again changes nothing
+        assert!(!clock.stop());
+        assert!(clock.double_speed());
+        clock.write(KEY1_PREPARE);
+        clock.stop();
+        assert_eq!(clock.read(), 0x7E);
+    }
+
+    #[test]
+    fn double_speed_halves_the_dots() {
+        let mut clock = Clock::new();
+        let ticks = clock.advance(12);
+        assert_eq!((ticks.cpu, ticks.dots), (12, 12));
+        assert_eq!(clock.cpu_cycles(456), 456);
+        clock.write(KEY1_PREPARE);
+        clock.stop();
+        let ticks = clock.advance(12);
+        assert_eq!((ticks.cpu, ticks.dots), (12, 6));
+        assert_eq!(clock.cpu_cycles(456), 912);
+    }
+
+    #[test]
+    fn odd_cycles_carry_half_a_dot() {
+        let mut clock = Clock::new();
+        clock.write(KEY1_PREPARE);
+        clock.stop();
+        assert_eq!(clock.advance(3).dots, 1);
+        assert_eq!(clock.advance(3).dots, 2);
+        assert_eq!(clock.advance(1).dots, 0);
+        // switching drops the half
+        clock.write(KEY1_PREPARE);
+        clock.stop();
+        clock.write(KEY1_PREPARE);
+        clock.stop();
+        assert_eq!(clock.advance(1).dots, 0);
+    }
+}
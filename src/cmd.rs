@@ -1,6 +1,7 @@
 use crate::common::{RegBytes, ByteSize};
 use crate::cpu::{Flag, Reg};
 use crate::motherboard::Motherboard;
+use crate::oam_bug::Corruption;
 
 #[derive(Clone, Copy)]
 pub enum RegExt {
@@ -27,6 +28,7 @@ impl RegExt {
             RegExt::NFlag(_) => ByteSize::Single,
             RegExt::Flag(_) => ByteSize::Single,
             RegExt::B(_) => ByteSize::Single,
+            RegExt::N => ByteSize::Single,
             _ => ByteSize::Double
         }
     }
@@ -71,24 +73,33 @@ const CMD_INP_PC: CmdInp = CmdInp {
 };
 
 fn get_reg_ext_val(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
     arg: &CmdInp,
 ) -> RegBytes {
-    let val;
-    
+    if arg.mem {
+        let addr = get_reg_ext_addr(mother, arg);
+        return RegBytes::new_single(mother.get_mem_at(addr));
+    }
+
     match &arg.re {
-        RegExt::Reg(reg) => val = mother.cpu.read_reg(*reg),
-        RegExt::N => val = mother.get_immediate_val(true),
-        RegExt::NN => val = mother.get_immediate_val(false),
+        RegExt::Reg(reg) => mother.cpu.read_reg(*reg),
+        RegExt::N => mother.get_immediate_val(true),
+        RegExt::NN => mother.get_immediate_val(false),
         _ => panic!("Get value of Flag or bit position")
     }
+}
 
-    if arg.mem {
-        RegBytes::new_single(mother.get_mem_at(val.get_double() + arg.change))
-    }
-    else {
-        val
-    }
+// address a memory arg refers to. 8 bit values (n, C) are offsets from change
+fn get_reg_ext_addr(
+    mother: &mut Motherboard,
+    arg: &CmdInp,
+) -> u16 {
+    let val = get_reg_ext_val(mother, &CmdInp::new(arg.re, false, 0));
+    let base = match (arg.re, arg.re.size()) {
+        (RegExt::N, _) | (_, ByteSize::Single) => val.get_single() as u16,
+        _ => val.get_double(),
+    };
+    base.wrapping_add(arg.change)
 }
 
 fn put_reg_ext_val(
@@ -97,9 +108,8 @@ fn put_reg_ext_val(
     val: RegBytes,
 ) {
     if arg.mem {
-        let new_arg = CmdInp::new(arg.re, false, 0);
-        let loc = get_reg_ext_val(mother, &new_arg);
-        mother.put_mem_at(loc.get_double() + arg.change, val.get_single())
+        let loc = get_reg_ext_addr(mother, arg);
+        mother.put_mem_at(loc, val.get_single())
     }
     else {
         match arg.re {
@@ -121,7 +131,7 @@ fn get_reg_ext_flag_val(
 }
 
 fn get_reg_ext_byte_val(
-    mother: &mut Motherboard,
+    _mother: &mut Motherboard,
     arg: &CmdInp,
 ) -> u8 {
     match &arg.re {
@@ -138,6 +148,19 @@ fn get_flag_val(
     mother.cpu.check_flag(flag) as u8
 }
 
+// sets all four flags at once
+fn set_flags(
+    mother: &mut Motherboard,
+    z: bool,
+    n: bool,
+    h: bool,
+    c: bool,
+) {
+    let flags = [(Flag::Z, z), (Flag::N, n), (Flag::H, h), (Flag::C, c)];
+    let f = flags.iter().filter(|(_, on)| *on).fold(0, |f, (flag, _)| f | *flag as u8);
+    mother.cpu.write_reg(Reg::F, RegBytes::new_single(f));
+}
+
 // booleans to indicate references to memory
 pub fn ld(
     mother: &mut Motherboard,
@@ -145,6 +168,14 @@ pub fn ld(
     src: CmdInp,
 ) {
     let src_val = get_reg_ext_val(mother, &src);
+    // ld (nn),sp stores both bytes, low first
+    if let (true, ByteSize::Double) = (dst.mem, src.size()) {
+        let loc = get_reg_ext_addr(mother, &dst);
+        let [low, high] = src_val.get_double().to_le_bytes();
+        mother.put_mem_at(loc, low);
+        mother.put_mem_at(loc.wrapping_add(1), high);
+        return;
+    }
     put_reg_ext_val(mother, &dst, src_val);
 }
 
@@ -154,16 +185,16 @@ fn change_hl(mother: &mut Motherboard, reg_ext: RegExt, inc: bool) {
         RegExt::Reg(Reg::HL) => {
             let mut val: u16 = mother.cpu.read_reg(Reg::HL).get_double();
             if inc {
-                val += 1;
+                val = val.wrapping_add(1);
             }
             else {
-                val -= 1;
+                val = val.wrapping_sub(1);
             }
             mother.cpu.write_reg(Reg::HL, RegBytes::new_double(val));
         }
         _ => panic!("unexpected reg_ext in ldd"),
     }
-} 
+}
 
 fn ld_change(
     mother: &mut Motherboard,
@@ -171,6 +202,10 @@ fn ld_change(
     src: CmdInp,
     inc: bool,
 ) {
+    // hl goes through the increment unit on the access cycle
+    let hl = mother.cpu.read_reg(Reg::HL).get_double();
+    let kind = if dst.mem { Corruption::Write } else { Corruption::ReadIncrease };
+    mother.oam_bug(hl, kind, 4);
     ld(mother, dst.clone(), src.clone());
     if dst.mem {
         change_hl(mother, dst.re, inc);
@@ -199,15 +234,27 @@ pub fn ldi(
     ld_change(mother, dst, src, true);
 }
 
+// sp plus the signed immediate, with H and C from adding the immediate to
+// the low byte of sp unsigned, as add sp,e and ld hl,sp+e both set them
+fn sp_offset(
+    mother: &mut Motherboard,
+    e: u8,
+) -> u16 {
+    let sp = mother.cpu.sp;
+    let h = (sp & 0x0F) + (e as u16 & 0x0F) > 0x0F;
+    let c = (sp & 0xFF) + e as u16 > 0xFF;
+    set_flags(mother, false, false, h, c);
+    sp.wrapping_add(e as i8 as u16)
+}
+
 pub fn ldhl(
     mother: &mut Motherboard,
-    arg1: CmdInp,
+    _arg1: CmdInp,
     arg2: CmdInp,
 ) {
-    let v = get_reg_ext_val(mother, &arg2).get_single() as u16;
-    let new_arg1 = CmdInp::new(RegExt::Reg(Reg::HL), false, 0);
-    let new_arg2 = CmdInp::new(arg1.re, arg1.mem, arg1.change + v);
-    ld(mother, new_arg1, new_arg2);
+    let e = get_reg_ext_val(mother, &arg2).get_single();
+    let val = sp_offset(mother, e);
+    mother.cpu.write_reg(Reg::HL, RegBytes::new_double(val));
 }
 
 pub fn push(
@@ -215,6 +262,11 @@ pub fn push(
     arg: CmdInp,
 ) {
     let val = get_reg_ext_val(mother, &arg).get_double();
+    // sp is decremented once on its own, then along with each write
+    let sp = mother.cpu.sp;
+    mother.oam_bug(sp, Corruption::Write, 4);
+    mother.oam_bug(sp.wrapping_sub(1), Corruption::Write, 8);
+    mother.oam_bug(sp.wrapping_sub(2), Corruption::Write, 12);
     mother.push(val);
 }
 
@@ -222,73 +274,96 @@ pub fn pop(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
+    let sp = mother.cpu.sp;
+    mother.oam_bug(sp, Corruption::ReadIncrease, 4);
+    mother.oam_bug(sp.wrapping_add(1), Corruption::ReadIncrease, 8);
     let val = RegBytes::new_double(mother.pop());
     put_reg_ext_val(mother, &arg, val);
 }
 
+// a + b + carry into A, flagging the carries out of bits 3 and 7
+fn add_a(
+    mother: &mut Motherboard,
+    val: u8,
+    carry: u8,
+) {
+    let a = get_reg_ext_val(mother, &CMD_INP_A).get_single();
+    let sum = a as u16 + val as u16 + carry as u16;
+    let h = (a & 0x0F) + (val & 0x0F) + carry > 0x0F;
+    set_flags(mother, sum as u8 == 0, false, h, sum > 0xFF);
+    put_reg_ext_val(mother, &CMD_INP_A, RegBytes::new_single(sum as u8));
+}
+
+// a - b - carry, flagging the borrows from bits 4 and 8. The result only
+// goes into A if `store`, for cp
+fn sub_a(
+    mother: &mut Motherboard,
+    val: u8,
+    carry: u8,
+    store: bool,
+) {
+    let a = get_reg_ext_val(mother, &CMD_INP_A).get_single();
+    let diff = a.wrapping_sub(val).wrapping_sub(carry);
+    let h = (a & 0x0F) < (val & 0x0F) + carry;
+    let c = (a as u16) < val as u16 + carry as u16;
+    set_flags(mother, diff == 0, true, h, c);
+    if store {
+        put_reg_ext_val(mother, &CMD_INP_A, RegBytes::new_single(diff));
+    }
+}
+
 pub fn add(
     mother: &mut Motherboard,
     arg1: CmdInp,
     arg2: CmdInp,
 ) {
-    let size1 = arg1.size();
-    let v1 = get_reg_ext_val(mother, &arg1);
     let v2 = get_reg_ext_val(mother, &arg2);
-    let mut val;
-    match size1 {
-        ByteSize::Single => {
-            val = RegBytes::new_single(v1.get_single() + v2.get_single());
+    match (arg1.size(), arg2.re) {
+        (ByteSize::Single, _) => add_a(mother, v2.get_single(), 0),
+        // add sp,e
+        (ByteSize::Double, RegExt::N) => {
+            let val = sp_offset(mother, v2.get_single());
+            put_reg_ext_val(mother, &arg1, RegBytes::new_double(val));
         },
-        ByteSize::Double => {
-            let size2 = arg2.size();
-            let val1 = v1.get_double();
-            let mut val2;
-            match size2 {
-                ByteSize::Single => {
-                    val2 = v2.get_single() as u16;
-                },
-                ByteSize::Double => {
-                    val2 = v2.get_double();
-                }
-            }
-            val = RegBytes::new_double(val1 + val2);
+        // add hl,rr leaves Z alone and carries out of bits 11 and 15
+        (ByteSize::Double, _) => {
+            let v1 = get_reg_ext_val(mother, &arg1).get_double();
+            let v2 = v2.get_double();
+            let (sum, c) = v1.overflowing_add(v2);
+            let h = (v1 & 0x0FFF) + (v2 & 0x0FFF) > 0x0FFF;
+            let z = mother.cpu.check_flag(Flag::Z);
+            set_flags(mother, z, false, h, c);
+            put_reg_ext_val(mother, &arg1, RegBytes::new_double(sum));
         },
     }
-    put_reg_ext_val(mother, &arg1, val);
 }
 
 pub fn adc(
     mother: &mut Motherboard,
-    arg1: CmdInp,
+    _arg1: CmdInp,
     arg2: CmdInp,
 ) {
-    let v1 = get_reg_ext_val(mother, &arg1).get_single();
-    let v2 = get_reg_ext_val(mother, &arg2).get_single();
+    let val = get_reg_ext_val(mother, &arg2).get_single();
     let carry = get_flag_val(mother, Flag::C);
-    let new_val = RegBytes::new_single(v1 + v2 + carry);
-    put_reg_ext_val(mother, &arg1, new_val);
+    add_a(mother, val, carry);
 }
 
 pub fn sub(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let a_val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = RegBytes::new_single(a_val - val);
-    put_reg_ext_val(mother, &CMD_INP_A, new_val);
+    sub_a(mother, val, 0, true);
 }
 
 pub fn sbc(
     mother: &mut Motherboard,
-    arg1: CmdInp,
+    _arg1: CmdInp,
     arg2: CmdInp,
 ) {
-    let v1 = get_reg_ext_val(mother, &arg1).get_single();
-    let v2 = get_reg_ext_val(mother, &arg2).get_single();
+    let val = get_reg_ext_val(mother, &arg2).get_single();
     let carry = get_flag_val(mother, Flag::C);
-    let new_val = RegBytes::new_single(v1 - v2 - carry);
-    put_reg_ext_val(mother, &arg1, new_val);
+    sub_a(mother, val, carry, true);
 }
 
 pub fn and(
@@ -297,8 +372,9 @@ pub fn and(
 ) {
     let a_val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let v = get_reg_ext_val(mother, &arg).get_single();
-    let new_v = RegBytes::new_single(a_val & v);
-    put_reg_ext_val(mother, &CMD_INP_A, new_v);
+    let new_v = a_val & v;
+    set_flags(mother, new_v == 0, false, true, false);
+    put_reg_ext_val(mother, &CMD_INP_A, RegBytes::new_single(new_v));
 }
 
 pub fn or(
@@ -307,8 +383,9 @@ pub fn or(
 ) {
     let a_val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let v = get_reg_ext_val(mother, &arg).get_single();
-    let new_v = RegBytes::new_single(a_val | v);
-    put_reg_ext_val(mother, &CMD_INP_A, new_v);
+    let new_v = a_val | v;
+    set_flags(mother, new_v == 0, false, false, false);
+    put_reg_ext_val(mother, &CMD_INP_A, RegBytes::new_single(new_v));
 }
 
 pub fn xor(
@@ -317,19 +394,20 @@ pub fn xor(
 ) {
     let a_val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let v = get_reg_ext_val(mother, &arg).get_single();
-    let new_v = RegBytes::new_single(a_val ^ v);
-    put_reg_ext_val(mother, &CMD_INP_A, new_v);
+    let new_v = a_val ^ v;
+    set_flags(mother, new_v == 0, false, false, false);
+    put_reg_ext_val(mother, &CMD_INP_A, RegBytes::new_single(new_v));
 }
 
 pub fn cp(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let a_val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let v = get_reg_ext_val(mother, &arg).get_single();
-    let out = a_val - v;
+    sub_a(mother, v, 0, false);
 }
 
+// 8 bit inc and dec leave C alone, 16 bit ones touch no flags
 pub fn inc(
     mother: &mut Motherboard,
     arg: CmdInp,
@@ -337,12 +415,15 @@ pub fn inc(
     match arg.size() {
         ByteSize::Single => {
             let v = get_reg_ext_val(mother, &arg).get_single();
-            let new_v = RegBytes::new_single(v + 1);
-            put_reg_ext_val(mother, &arg, new_v);
+            let new_v = v.wrapping_add(1);
+            let c = mother.cpu.check_flag(Flag::C);
+            set_flags(mother, new_v == 0, false, v & 0x0F == 0x0F, c);
+            put_reg_ext_val(mother, &arg, RegBytes::new_single(new_v));
         }
         ByteSize::Double => {
             let v = get_reg_ext_val(mother, &arg).get_double();
-            let new_v = RegBytes::new_double(v + 1);
+            mother.oam_bug(v, Corruption::Write, 4);
+            let new_v = RegBytes::new_double(v.wrapping_add(1));
             put_reg_ext_val(mother, &arg, new_v);
         }
     }
@@ -355,12 +436,15 @@ pub fn dec(
     match arg.size() {
         ByteSize::Single => {
             let v = get_reg_ext_val(mother, &arg).get_single();
-            let new_v = RegBytes::new_single(v - 1);
-            put_reg_ext_val(mother, &arg, new_v);
+            let new_v = v.wrapping_sub(1);
+            let c = mother.cpu.check_flag(Flag::C);
+            set_flags(mother, new_v == 0, true, v & 0x0F == 0, c);
+            put_reg_ext_val(mother, &arg, RegBytes::new_single(new_v));
         }
         ByteSize::Double => {
             let v = get_reg_ext_val(mother, &arg).get_double();
-            let new_v = RegBytes::new_double(v - 1);
+            mother.oam_bug(v, Corruption::Write, 4);
+            let new_v = RegBytes::new_double(v.wrapping_sub(1));
             put_reg_ext_val(mother, &arg, new_v);
         }
     }
@@ -371,35 +455,39 @@ pub fn swap(
     arg: CmdInp,
 ) {
     let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_v = (val >> 4) + (val << 4);
+    let new_v = val.rotate_left(4);
+    set_flags(mother, new_v == 0, false, false, false);
     let bytes = RegBytes::new_single(new_v);
     put_reg_ext_val(mother, &arg, bytes);
 }
 
+// adjusts A back to BCD after an add or sub of two BCD values
 pub fn daa(
     mother: &mut Motherboard,
 ) {
-    let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
-    let new_val: u8;
-    let mut corr: u8 = 0;
-    if mother.cpu.check_flag(Flag::H) {
-        corr += 0x6;
-    }
-    if mother.cpu.check_flag(Flag::C) {
-        corr += 0x60;
+    let mut val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
+    let n = mother.cpu.check_flag(Flag::N);
+    let h = mother.cpu.check_flag(Flag::H);
+    let mut c = mother.cpu.check_flag(Flag::C);
+    if n {
+        if c {
+            val = val.wrapping_sub(0x60);
+        }
+        if h {
+            val = val.wrapping_sub(0x06);
+        }
     }
-    if mother.cpu.check_flag(Flag::N) {
-        new_val = val - corr;
-    } else {
-        if (val & 0xf) > 0x9 {
-            corr |= 0x6;
+    else {
+        if c || val > 0x99 {
+            val = val.wrapping_add(0x60);
+            c = true;
         }
-        if val > 0x99 {
-            corr |= 0x60;
+        if h || (val & 0x0F) > 0x09 {
+            val = val.wrapping_add(0x06);
         }
-        new_val = val + corr;
     }
-    let bytes = RegBytes::new_single(new_val);
+    set_flags(mother, val == 0, n, false, c);
+    let bytes = RegBytes::new_single(val);
     put_reg_ext_val(mother, &CMD_INP_A, bytes);
 }
 
@@ -408,160 +496,161 @@ pub fn cpl(
 ) {
     let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
     let new_v = !val;
+    mother.cpu.set_flag(Flag::N);
+    mother.cpu.set_flag(Flag::H);
     let bytes = RegBytes::new_single(new_v);
     put_reg_ext_val(mother, &CMD_INP_A, bytes);
 }
 
 pub fn ccf(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    let z = mother.cpu.check_flag(Flag::Z);
+    let c = mother.cpu.check_flag(Flag::C);
+    set_flags(mother, z, false, false, !c);
 }
 
 pub fn scf(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    let z = mother.cpu.check_flag(Flag::Z);
+    set_flags(mother, z, false, false, true);
 }
 
 pub fn nop(
-    mother: &Motherboard,
+    _mother: &Motherboard,
 ) {
-    ()
 }
 
 pub fn halt(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    mother.cpu.halted = true;
 }
 
 pub fn stop(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    mother.stop();
 }
 
 pub fn di(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    mother.cpu.ime = false;
+    mother.cpu.ime_pending = false;
 }
 
+// takes effect after the next instruction
 pub fn ei(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    mother.cpu.ime_pending = true;
+}
+
+// runs a rotate or shift on `arg`: `op` takes the value and the carry flag
+// and gives the result and the bit shifted out, which goes into C. The
+// rotates of A outside the CB prefix always clear Z
+fn shift(
+    mother: &mut Motherboard,
+    arg: &CmdInp,
+    op: fn(u8, bool) -> (u8, bool),
+    zero: bool,
+) {
+    let val = get_reg_ext_val(mother, arg).get_single();
+    let carry = mother.cpu.check_flag(Flag::C);
+    let (new_val, c) = op(val, carry);
+    set_flags(mother, zero && new_val == 0, false, false, c);
+    put_reg_ext_val(mother, arg, RegBytes::new_single(new_val));
+}
+
+fn rotate_left(val: u8, _carry: bool) -> (u8, bool) {
+    (val.rotate_left(1), val & 0x80 != 0)
+}
+
+fn rotate_left_carry(val: u8, carry: bool) -> (u8, bool) {
+    ((val << 1) | carry as u8, val & 0x80 != 0)
+}
+
+fn rotate_right(val: u8, _carry: bool) -> (u8, bool) {
+    (val.rotate_right(1), val & 0x01 != 0)
+}
+
+fn rotate_right_carry(val: u8, carry: bool) -> (u8, bool) {
+    ((val >> 1) | ((carry as u8) << 7), val & 0x01 != 0)
 }
 
 pub fn rlca(
     mother: &mut Motherboard,
 ) {
-    let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
-    let new_val = (val << 1) + (val >> 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &CMD_INP_A, bytes);
+    shift(mother, &CMD_INP_A, rotate_left, false);
 }
 
 pub fn rla(
     mother: &mut Motherboard,
 ) {
-    let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
-    let flag_val = get_flag_val(mother, Flag::C);
-    let new_val = flag_val + val;
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &CMD_INP_A, bytes);
+    shift(mother, &CMD_INP_A, rotate_left_carry, false);
 }
 
 pub fn rrca(
     mother: &mut Motherboard,
 ) {
-    let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
-    let new_val = (val >> 1) + (val << 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &CMD_INP_A, bytes);
+    shift(mother, &CMD_INP_A, rotate_right, false);
 }
 
 pub fn rra(
     mother: &mut Motherboard,
 ) {
-    let val = get_reg_ext_val(mother, &CMD_INP_A).get_single();
-    let flag = get_flag_val(mother, Flag::C);
-    let new_val = (val >> 1) + (flag << 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &CMD_INP_A, bytes);
+    shift(mother, &CMD_INP_A, rotate_right_carry, false);
 }
 
 pub fn rlc(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = (val << 1) + (val >> 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, rotate_left, true);
 }
 
 pub fn rl(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let flag = get_flag_val(mother, Flag::C);
-    let new_val = (val << 1) + flag;
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, rotate_left_carry, true);
 }
 
 pub fn rrc(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = (val >> 1) + (val << 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, rotate_right, true);
 }
 
 pub fn rr(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let flag = get_flag_val(mother, Flag::C);
-    let new_val = (val >> 1) + (flag << 7);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, rotate_right_carry, true);
 }
 
 pub fn sla(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = val << 1;
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, |val, _| (val << 1, val & 0x80 != 0), true);
 }
 
 pub fn sra(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = (val >> 1) | (val & 0x80);
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, |val, _| ((val >> 1) | (val & 0x80), val & 0x01 != 0), true);
 }
 
 pub fn srl(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
-    let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = val >> 1;
-    let bytes = RegBytes::new_single(new_val);
-    put_reg_ext_val(mother, &arg, bytes);
+    shift(mother, &arg, |val, _| (val >> 1, val & 0x01 != 0), true);
 }
 
 pub fn bit(
@@ -571,7 +660,8 @@ pub fn bit(
 ) {
     let pos = get_reg_ext_byte_val(mother, &arg1);
     let val = get_reg_ext_val(mother, &arg2).get_single();
-    let test = (val & (1 << pos)) == 0;
+    let c = mother.cpu.check_flag(Flag::C);
+    set_flags(mother, val & (1 << pos) == 0, false, true, c);
 }
 
 pub fn set(
@@ -598,6 +688,8 @@ pub fn res(
     put_reg_ext_val(mother, &arg2, bytes);
 }
 
+// the conditional branches return the extra cycles they take when taken
+
 pub fn jp(
     mother: &mut Motherboard,
     arg: CmdInp,
@@ -610,20 +702,23 @@ pub fn jp_flag(
     mother: &mut Motherboard,
     arg1: CmdInp,
     arg2: CmdInp,
-) {
+) -> u8 {
     let test = get_reg_ext_flag_val(mother, &arg1);
     if test {
         jp(mother, arg2);
+        return 4;
     }
+    0
 }
 
+// the offset is signed, from the end of the 2 byte instruction
 pub fn jr(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
     let curr = get_reg_ext_val(mother, &CMD_INP_PC).get_double();
     let val = get_reg_ext_val(mother, &arg).get_single();
-    let new_val = curr + (val as u16);
+    let new_val = curr.wrapping_add(2).wrapping_add(val as i8 as u16);
     let bytes = RegBytes::new_double(new_val);
     put_reg_ext_val(mother, &CMD_INP_PC, bytes);
 }
@@ -632,19 +727,22 @@ pub fn jr_flag(
     mother: &mut Motherboard,
     arg1: CmdInp,
     arg2: CmdInp,
-) {
+) -> u8 {
     let test = get_reg_ext_flag_val(mother, &arg1);
     if test {
         jr(mother, arg2);
+        return 4;
     }
+    0
 }
 
 pub fn call(
     mother: &mut Motherboard,
     arg: CmdInp,
 ) {
+    // returns to the instruction after the 3 byte call
     let cur = get_reg_ext_val(mother, &CMD_INP_PC).get_double();
-    mother.push(cur + 3);
+    mother.push(cur.wrapping_add(3));
     let val = get_reg_ext_val(mother, &arg);
     put_reg_ext_val(mother, &CMD_INP_PC, val);
 }
@@ -653,11 +751,13 @@ pub fn call_flag(
     mother: &mut Motherboard,
     arg1: CmdInp,
     arg2: CmdInp,
-) {
+) -> u8 {
     let test = get_reg_ext_flag_val(mother, &arg1);
     if test {
         call(mother, arg2);
+        return 12;
     }
+    0
 }
 
 pub fn rst(
@@ -665,7 +765,7 @@ pub fn rst(
     arg: CmdInp,
 ) {
     let cur = get_reg_ext_val(mother, &CMD_INP_PC).get_double();
-    mother.push(cur);
+    mother.push(cur.wrapping_add(1));
     let new_addr = get_reg_ext_byte_val(mother, &arg) as u16;
     let bytes = RegBytes::new_double(new_addr);
     put_reg_ext_val(mother, &CMD_INP_PC, bytes);
@@ -682,15 +782,18 @@ pub fn ret(
 pub fn ret_flag(
     mother: &mut Motherboard,
     arg: CmdInp,
-) {
+) -> u8 {
     let test = get_reg_ext_flag_val(mother, &arg);
     if test {
         ret(mother);
+        return 12;
     }
+    0
 }
 
 pub fn reti(
-    mother: &Motherboard,
+    mother: &mut Motherboard,
 ) {
-    ()
+    ret(mother);
+    mother.cpu.ime = true;
 }
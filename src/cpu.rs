@@ -59,12 +59,13 @@ impl RegPair {
         }
     }
 
+    // the first register of the pair is the high byte
     fn write_16(&mut self, bytes: RegBytes) {
-        self.pair = bytes.get_double().to_le_bytes();
+        self.pair = bytes.get_double().to_be_bytes();
     }
 
     fn read_16(&self) -> RegBytes {
-        RegBytes::new_double(u16::from_le_bytes(self.pair))
+        RegBytes::new_double(u16::from_be_bytes(self.pair))
     }
 }
 
@@ -97,6 +98,14 @@ fn check_flag(flag: Flag, byte: &RegBytes) -> bool {
 pub struct CPU {
     pub sp: u16,
     pub pc: u16,
+    // set whenever an instruction writes pc, so the step loop doesn't advance it
+    pub jumped: bool,
+    // the interrupt master enable, and EI's request to set it once the next
+    // instruction has run
+    pub ime: bool,
+    pub ime_pending: bool,
+    // in HALT, until an interrupt is pending
+    pub halted: bool,
 
     reg_map: HashMap<Reg, (Rc<RefCell<RegPair>>, RegOrder)>,
 }
@@ -126,6 +135,10 @@ impl CPU {
 
             sp: 0,
             pc: 0,
+            jumped: false,
+            ime: false,
+            ime_pending: false,
+            halted: false,
         }
     }
 
@@ -149,7 +162,10 @@ impl CPU {
     pub fn write_reg(&mut self, reg: Reg, bytes: RegBytes) {
         match reg {
             Reg::SP => self.sp = bytes.get_double(),
-            Reg::PC => self.pc = bytes.get_double(),
+            Reg::PC => {
+                self.pc = bytes.get_double();
+                self.jumped = true;
+            },
             _ => {
                 let (pair_ref, order) = self.reg_map.get(&reg).unwrap();
                 let mut pair = pair_ref.borrow_mut();
@@ -159,17 +175,21 @@ impl CPU {
                     RegOrder::Second => pair.write_8(false, bytes),
                     RegOrder::Both => pair.write_16(bytes),
                 }
+                // the low nibble of F doesn't exist
+                if let Reg::F | Reg::AF = reg {
+                    pair.pair[1] &= 0xF0;
+                }
             }
         }
     }
 
-    fn set_flag(&mut self, flag: Flag) {
+    pub fn set_flag(&mut self, flag: Flag) {
         let mut byte = self.read_reg(Reg::F);
         set_flag(flag, &mut byte);
         self.write_reg(Reg::F, byte);
     }
 
-    fn unset_flag(&mut self, flag: Flag) {
+    pub fn unset_flag(&mut self, flag: Flag) {
         let mut byte = self.read_reg(Reg::F);
         unset_flag(flag, &mut byte);
         self.write_reg(Reg::F, byte);
@@ -180,3 +200,9 @@ impl CPU {
         check_flag(flag, &byte)
     }
 }
+
+impl Default for CPU {
+    fn default() -> Self {
+        Self::new()
+    }
+}
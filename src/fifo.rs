@@ -0,0 +1,336 @@
+// Pixel FIFO renderer for mode 3. The background fetcher and the pixel
+// shifter run one dot at a time against the live registers, so writes made
+// during pixel transfer land mid-line, and the length of mode 3 comes out of
+// the fine scroll, window and object fetches instead of a fixed count.
+
+use crate::ppu::Ppu;
+use crate::render::{self, Object, WIDTH};
+
+use std::collections::VecDeque;
+
+const LCDC_OBJ_ENABLE: u8 = 0x02;
+
+// dots spent on the throwaway fetch at the start of every line
+const LINE_START_DOTS: u8 = 8;
+// dots an object fetch stalls the shifter for, before waiting on the
+// background fetcher
+const OBJ_FETCH_DOTS: u8 = 6;
+
+#[derive(Clone, Copy)]
+struct ObjPixel {
+    color: u8,
+    obj: Object,
+}
+
+#[derive(Default)]
+pub struct Fifo {
+    line: u8,
+    // color index and map attributes
+    bg: VecDeque<(u8, u8)>,
+    obj: VecDeque<ObjPixel>,
+    // objects found by the OAM scan not fetched yet
+    objs: Vec<Object>,
+
+    // dot within the current tile fetch, and the fetched tile, attributes
+    // and planes
+    fetch_dot: u8,
+    fetch_x: u8,
+    tile: u8,
+    attr: u8,
+    planes: (u8, u8),
+    window: bool,
+
+    // pixels of fine scroll still to throw away
+    discard: u8,
+    // dots the shifter is held for
+    stall: u8,
+    // pixels sent to the lcd so far
+    lx: u8,
+    out: Vec<u16>,
+    done: bool,
+}
+
+impl Fifo {
+    pub fn new() -> Self {
+        Self {
+            out: vec![0; WIDTH],
+            done: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    // the pixels of the line just finished
+    pub fn output(&self) -> &[u16] {
+        &self.out
+    }
+
+    // whether the window was drawn on the line
+    pub fn drew_window(&self) -> bool {
+        self.window
+    }
+
+    // begins pixel transfer for `line`, with the objects the OAM scan found
+    pub fn start_line(&mut self, ppu: &Ppu, line: u8) {
+        // fetched left to right whatever the priority order
+        let mut objs = render::scan_oam(ppu.oam(), ppu.lcdc, line);
+        objs.sort_by_key(|obj| obj.x);
+        *self = Self {
+            line,
+            objs,
+            discard: ppu.scx & 7,
+            stall: LINE_START_DOTS,
+            out: std::mem::take(&mut self.out),
+            ..Default::default()
+        };
+    }
+
+    // row of the current tile the fetcher reads, and the map entry holding it
+    fn tile_addr(&self, ppu: &Ppu, window_line: u8) -> (usize, u8) {
+        if self.window {
+            let map = render::bg_map(ppu.lcdc, true);
+            let y = window_line;
+            (map + (y as usize / 8) * 32 + self.fetch_x as usize, y % 8)
+        }
+        else {
+            let map = render::bg_map(ppu.lcdc, false);
+            let y = ppu.scy.wrapping_add(self.line);
+            let x = ((ppu.scx / 8).wrapping_add(self.fetch_x) & 31) as usize;
+            (map + (y as usize / 8) * 32 + x, y % 8)
+        }
+    }
+
+    // one dot of the background fetcher: tile number, low plane and high
+    // plane take two dots each, then it waits for the fifo to empty
+    fn fetch(&mut self, ppu: &Ppu, window_line: u8) {
+        let (map_addr, row) = self.tile_addr(ppu, window_line);
+        let row = render::attr_row(self.attr, row);
+        let vram = render::bank(ppu.vram(), self.attr);
+        match self.fetch_dot {
+            1 => {
+                self.tile = ppu.vram()[map_addr];
+                self.attr = render::map_attr(ppu, map_addr);
+            },
+            3 => self.planes.0 = render::tile_row(vram, ppu.lcdc, self.tile, row).0,
+            5 => self.planes.1 = render::tile_row(vram, ppu.lcdc, self.tile, row).1,
+            _ => (),
+        }
+        if self.fetch_dot < 6 {
+            self.fetch_dot += 1;
+        }
+        if self.fetch_dot == 6 && self.bg.is_empty() {
+            for x in 0..8 {
+                self.bg.push_back((render::bg_row_pixel(self.planes, self.attr, x), self.attr));
+            }
+            self.fetch_x = self.fetch_x.wrapping_add(1);
+            self.fetch_dot = 0;
+        }
+    }
+
+    // fetches `obj` into the object fifo. Opaque pixels already there stay in
+    // front, unless CGB priority puts a lower OAM index ahead of them
+    fn fetch_obj(&mut self, ppu: &Ppu, obj: Object) {
+        let oam_priority = render::oam_priority(ppu);
+        let left = obj.x as i16 - 8;
+        for col in 0..8i16 {
+            let x = left + col;
+            if x < self.lx as i16 {
+                continue;
+            }
+            let color = render::obj_pixel(ppu, &obj, self.line, x as u8).unwrap_or(0);
+            let idx = (x - self.lx as i16) as usize;
+            let pixel = ObjPixel {
+                color,
+                obj,
+            };
+            match self.obj.get_mut(idx) {
+                Some(old) if old.color == 0 || (oam_priority && color != 0 && obj.index < old.obj.index) => {
+                    *old = pixel
+                },
+                Some(_) => (),
+                None => self.obj.push_back(pixel),
+            }
+        }
+        // the shifter also waits for the background fetch in progress. This
+        // dot is the first of the fetch
+        self.stall = OBJ_FETCH_DOTS - 1 + 5u8.saturating_sub(self.fetch_dot);
+    }
+
+    // runs one dot of pixel transfer
+    pub fn step(&mut self, ppu: &Ppu, window_line: u8) {
+        if self.done {
+            return;
+        }
+        if self.stall > 0 {
+            self.stall -= 1;
+            return;
+        }
+
+        // the window restarts the fetcher on an empty fifo once it is reached
+        if !self.window && render::window_visible(ppu, self.line) && self.lx as i16 + 7 >= ppu.wx as i16 {
+            self.window = true;
+            self.bg.clear();
+            // a window left of the screen edge is cut off
+            self.discard = 7u8.saturating_sub(ppu.wx);
+            self.fetch_x = 0;
+            self.fetch_dot = 0;
+        }
+
+        self.fetch(ppu, window_line);
+
+        // an object starting at the next pixel holds the shifter while it is
+        // fetched
+        if ppu.lcdc & LCDC_OBJ_ENABLE != 0 && !self.bg.is_empty() && self.discard == 0 {
+            let hit = self.objs.iter().position(|obj| obj.x > 0 && obj.x as u16 <= self.lx as u16 + 8);
+            if let Some(idx) = hit {
+                let obj = self.objs.remove(idx);
+                self.fetch_obj(ppu, obj);
+                return;
+            }
+        }
+
+        if let Some((bg, attr)) = self.bg.pop_front() {
+            if self.discard > 0 {
+                self.discard -= 1;
+                return;
+            }
+            let obj = self.obj.pop_front();
+            self.out[self.lx as usize] = render::mix(ppu, bg, attr, obj.as_ref().map(|pixel| (&pixel.obj, pixel.color)));
+            self.lx += 1;
+            if self.lx as usize == WIDTH {
+                self.done = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppu::{OAM_START, VRAM_START};
+
+    fn ppu() -> Ppu {
+        let mut ppu = Ppu::new();
+        ppu.lcdc = 0x93;
+        ppu.bgp = 0xE4;
+        ppu.obp0 = 0xE4;
+        // a striped tile 1 across the map, and tile 2 solid for objects
+        for row in 0..8 {
+            ppu.write_vram(VRAM_START + 16 + row * 2, 0xF0);
+            ppu.write_vram(VRAM_START + 16 + row * 2 + 1, 0x3C);
+            ppu.write_vram(VRAM_START + 32 + row * 2, 0xFF);
+        }
+        for idx in 0..0x400 {
+            ppu.write_vram(VRAM_START + 0x1800 + idx, if idx % 3 == 0 { 1 } else { 0 });
+        }
+        ppu
+    }
+
+    fn set_obj(ppu: &mut Ppu, idx: u16, x: u8) {
+        for (i, val) in [16, x, 2, 0].iter().enumerate() {
+            ppu.write_oam(OAM_START + idx * 4 + i as u16, *val);
+        }
+    }
+
+    // draws line 0 through the fifo, returning the dots mode 3 took
+    fn run(ppu: &Ppu, fifo: &mut Fifo) -> u16 {
+        fifo.start_line(ppu, 0);
+        let mut dots = 0;
+        while !fifo.done() {
+            fifo.step(ppu, 0);
+            dots += 1;
+        }
+        dots
+    }
+
+    fn scanline(ppu: &Ppu) -> Vec<u16> {
+        let mut out = vec![0; WIDTH];
+        render::render_line(ppu, 0, &mut 0, &mut out);
+        out
+    }
+
+    fn dots(ppu: &Ppu) -> u16 {
+        run(ppu, &mut Fifo::new())
+    }
+
+    #[test]
+    fn matches_the_scanline_renderer() {
+        let mut ppu = ppu();
+        let mut fifo = Fifo::new();
+        run(&ppu, &mut fifo);
+        assert_eq!(fifo.output(), scanline(&ppu).as_slice());
+
+        ppu.scx = 13;
+        ppu.lcdc |= 0x20;
+        ppu.wx = 60;
+        set_obj(&mut ppu, 0, 30);
+        set_obj(&mut ppu, 1, 100);
+        run(&ppu, &mut fifo);
+        assert_eq!(fifo.output(), scanline(&ppu).as_slice());
+        assert!(fifo.drew_window());
+    }
+
+    #[test]
+    fn fine_scroll_lengthens_mode_3() {
+        let mut ppu = ppu();
+        let base = dots(&ppu);
+        for scx in 1..8 {
+            ppu.scx = scx;
+            assert_eq!(dots(&ppu), base + scx as u16);
+        }
+        ppu.scx = 8;
+        assert_eq!(dots(&ppu), base);
+    }
+
+    #[test]
+    fn objects_lengthen_mode_3() {
+        let mut ppu = ppu();
+        let base = dots(&ppu);
+        set_obj(&mut ppu, 0, 8);
+        let one = dots(&ppu) - base;
+        assert_eq!(one, 11);
+        // 6 dots, plus what is left of the background fetch for the tile
+        // under the object's left edge
+        for (x, penalty) in [(9, 10), (13, 6), (15, 6), (16, 11)] {
+            set_obj(&mut ppu, 0, x);
+            assert_eq!(dots(&ppu) - base, penalty);
+        }
+        set_obj(&mut ppu, 1, 50);
+        assert!(dots(&ppu) - base > one);
+        // not fetched with objects off
+        ppu.lcdc &= !LCDC_OBJ_ENABLE;
+        assert_eq!(dots(&ppu), base);
+    }
+
+    #[test]
+    fn window_lengthens_mode_3() {
+        let mut ppu = ppu();
+        let base = dots(&ppu);
+        ppu.lcdc |= 0x20;
+        ppu.wx = 87;
+        assert!(dots(&ppu) > base);
+    }
+
+    #[test]
+    fn registers_change_mid_line() {
+        let ppu = ppu();
+        let mut fifo = Fifo::new();
+        fifo.start_line(&ppu, 0);
+        let mut ppu = ppu;
+        let mut dots = 0;
+        while !fifo.done() {
+            // every pixel from the 80th dot on is shaded through BGP 0x00
+            if dots == 80 {
+                ppu.bgp = 0;
+            }
+            fifo.step(&ppu, 0);
+            dots += 1;
+        }
+        let out = fifo.output();
+        assert!(out[..20].iter().any(|pixel| *pixel != 0));
+        assert!(out[80..].iter().all(|pixel| *pixel == 0));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::motherboard::Motherboard;
+use crate::op_cmds::OpCmds;
+use crate::opcodes::{OpTable, CB_PREFIX};
+use crate::ppu;
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+const INTERRUPT_CYCLES: u8 = 20;
+
+// op_cmds key of LD B,B, which test roms use as a software breakpoint
+pub const BREAKPOINT_OP: u16 = 0x40;
+
+pub struct GameBoy {
+    pub mother: Motherboard,
+    // total cycles executed
+    pub cycles: u64,
+    // set once an LD B,B runs, until cleared
+    pub breakpoint: bool,
+    ops: OpCmds,
+    table: OpTable,
+}
+
+impl GameBoy {
+    pub fn new(mother: Motherboard) -> Self {
+        Self {
+            mother,
+            cycles: 0,
+            breakpoint: false,
+            ops: OpCmds::new(),
+            table: OpTable::new(),
+        }
+    }
+
+    // op_cmds key of the instruction at pc. the byte after is only fetched
+    // behind a CB prefix
+    pub fn current_op(&mut self) -> u16 {
+        let pc = self.mother.cpu.pc;
+        let first = self.mother.get_mem_at(pc);
+        if first != CB_PREFIX {
+            return first as u16;
+        }
+        let second = self.mother.get_mem_at(pc.wrapping_add(1));
+        OpTable::key_of(first, second)
+    }
+
+    pub fn has_op(&self, key: u16) -> bool {
+        self.table.get(key).is_some()
+    }
+
+    // executes the instruction at pc, returning the cycles it took
+    pub fn step(&mut self) -> u8 {
+        // in STOP nothing runs until a button is pressed, but time goes on
+        if self.mother.stopped() {
+            self.mother.tick(4);
+            self.cycles += 4;
+            return 4;
+        }
+        // HALT waits for any pending interrupt, whether or not IME is set
+        if self.mother.cpu.halted {
+            if self.mother.pending_interrupts() == 0 {
+                self.mother.tick(4);
+                self.cycles += 4;
+                return 4;
+            }
+            self.mother.cpu.halted = false;
+        }
+        if self.mother.cpu.ime && self.mother.pending_interrupts() != 0 {
+            return self.interrupt();
+        }
+        let enable_ime = self.mother.cpu.ime_pending;
+
+        let key = self.current_op();
+        let (length, table_cycles) = self.table.get(key).map_or((1, 4), |info| (info.length, info.cycles));
+
+        // handlers see pc at the start of the instruction
+        self.mother.cpu.jumped = false;
+        self.mother.begin_instr(table_cycles);
+        let cycles = self.ops.exe_op(&mut self.mother, key);
+        if key == BREAKPOINT_OP {
+            self.breakpoint = true;
+        }
+        self.mother.end_instr(cycles);
+        self.cycles += self.mother.run_stall() as u64;
+        if !self.mother.cpu.jumped {
+            self.mother.cpu.pc = self.mother.cpu.pc.wrapping_add(length as u16);
+        }
+        // an EI before this instruction, unless it was a DI
+        if enable_ime && self.mother.cpu.ime_pending {
+            self.mother.cpu.ime_pending = false;
+            self.mother.cpu.ime = true;
+        }
+        self.cycles += cycles as u64;
+        cycles
+    }
+
+    // dispatches the highest priority pending interrupt: IME is cleared and
+    // pc pushed before jumping to its vector, over 5 m-cycles
+    fn interrupt(&mut self) -> u8 {
+        self.mother.cpu.ime = false;
+        self.mother.begin_instr(INTERRUPT_CYCLES);
+        let pc = self.mother.cpu.pc;
+        self.mother.push(pc);
+        // the high byte of pc landing on IE can cancel the interrupt, which
+        // sends the cpu to 0x0000 instead
+        self.mother.cpu.pc = self.mother.take_interrupt().unwrap_or(0);
+        self.mother.end_instr(INTERRUPT_CYCLES);
+        self.cycles += INTERRUPT_CYCLES as u64;
+        INTERRUPT_CYCLES
+    }
+
+    // runs until the ppu finishes a frame, or for as long as one would take
+    // while the lcd is off, returning the cycles taken
+    pub fn run_frame(&mut self) -> u64 {
+        let start = self.cycles;
+        let frame = self.mother.clock.cpu_cycles(ppu::FRAME_DOTS as u32) as u64;
+        loop {
+            self.step();
+            if self.mother.ppu.frame_ready() || self.cycles - start >= frame {
+                return self.cycles - start;
+            }
+        }
+    }
+
+    // like step, but reports a handler panic as an error instead of unwinding
+    pub fn try_step(&mut self) -> Result<u8, String> {
+        panic::catch_unwind(AssertUnwindSafe(|| self.step())).map_err(panic_msg)
+    }
+}
+
+pub fn panic_msg(err: Box<dyn Any + Send>) -> String {
+    match err.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(err) => err.downcast_ref::<&str>().map_or("unknown panic", |msg| *msg).to_string(),
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// puts the thread's previous setting back, even if `f` unwinds
+struct QuietGuard(bool);
+
+impl Drop for QuietGuard {
+    fn drop(&mut self) {
+        QUIET.with(|quiet| quiet.set(self.0));
+    }
+}
+
+// runs `f` with the panic messages of this thread suppressed, for runners
+// that report handler panics through try_step themselves. One hook is
+// installed for good, handing every other panic to the hook it replaced
+pub fn quiet_panics<T, F: FnOnce() -> T>(f: F) -> T {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(|quiet| quiet.get()) {
+                hook(info);
+            }
+        }));
+    });
+    let _guard = QuietGuard(QUIET.with(|quiet| quiet.replace(true)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_panics_restores_after_unwinding() {
+        let err = panic::catch_unwind(|| quiet_panics(|| panic!("handler"))).unwrap_err();
+        assert_eq!(panic_msg(err), "handler");
+        assert!(!QUIET.with(|quiet| quiet.get()));
+        // nesting keeps the outer setting
+        quiet_panics(|| {
+            quiet_panics(|| ());
+            assert!(QUIET.with(|quiet| quiet.get()));
+        });
+    }
+}
@@ -0,0 +1,394 @@
+// Game Boy Sound System (.gbs) files: a music driver and its data ripped out
+// of a game, behind a 0x70 byte header giving where to load it and the
+// addresses of its INIT and PLAY routines. The data goes into a synthetic
+// rom at its load address, with the RST vectors pointed into it as the
+// format asks, the VBlank and timer interrupt vectors jumping to `play`, and
+// a small driver at 0x0100:
+//
+//     init: call INIT     ; a = song
+//           ld a, IE      ; VBlank, or the timer if the header sets it up
+//           ldh [$FF], a
+//           ei
+//     idle: halt
+//           jr idle
+//     play: call PLAY
+//           reti
+//
+// Bank switches take the whole byte written to 0x2000-0x3FFF. TAC bit 7
+// asks for CGB double speed, which the driver switches to before INIT.
+
+use crate::asm;
+use crate::capture::{self, Recording};
+use crate::common::RegBytes;
+use crate::cpu::Reg;
+use crate::gameboy::GameBoy;
+use crate::model::Model;
+use crate::motherboard::{BankSelect, Interrupt, Motherboard, IF};
+use crate::timer;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8] = b"GBS";
+const VERSION: u8 = 1;
+pub const HEADER_SIZE: usize = 0x70;
+
+// the lowest load address, leaving room for the vectors and the driver
+const MIN_LOAD: u16 = 0x0400;
+// INIT and PLAY run from the fixed and switchable banks below here
+const ROM_END: u16 = 0x8000;
+// the most an 8 bit bank number reaches
+const MAX_ROM_SIZE: usize = 0x100 * ROM_BANK_SIZE;
+
+const DRIVER: u16 = 0x0100;
+const RST_VECTORS: [u16; 8] = [0x00, 0x08, 0x10, 0x18, 0x20, 0x28, 0x30, 0x38];
+const VBLANK_VECTOR: u16 = 0x40;
+const TIMER_VECTOR: u16 = 0x50;
+const ROM_BANK_SIZE: usize = 0x4000;
+const HEADER_CGB_FLAG: usize = 0x143;
+const CGB_ONLY: u8 = 0xC0;
+
+// TAC bit saying PLAY runs off the timer instead of VBlank
+const TAC_ENABLE: u8 = 0x04;
+// and the one asking for double speed, which the timer doesn't have
+const TAC_DOUBLE_SPEED: u8 = 0x80;
+
+#[derive(Debug)]
+pub enum GbsError {
+    Io(String),
+    BadMagic,
+    BadVersion(u8),
+    TooShort(usize),
+    BadLoadAddress(u16),
+    BadEntry(u16),
+    TooLarge(usize),
+    BadSong(u8),
+}
+
+impl fmt::Display for GbsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GbsError::Io(msg) => write!(f, "failed to read gbs file: {}", msg),
+            GbsError::BadMagic => write!(f, "not a gbs file"),
+            GbsError::BadVersion(version) => write!(f, "unsupported gbs version {}", version),
+            GbsError::TooShort(size) => write!(f, "gbs file is {} bytes, shorter than its header", size),
+            GbsError::BadLoadAddress(addr) => {
+                write!(f, "gbs load address {:04x} is outside {:04x}-{:04x}", addr, MIN_LOAD, ROM_END - 1)
+            }
+            GbsError::BadEntry(addr) => write!(f, "gbs init or play address {:04x} is outside the loaded code", addr),
+            GbsError::TooLarge(size) => write!(f, "gbs data ends at {:x}, past the last bank", size),
+            GbsError::BadSong(song) => write!(f, "no song {}", song),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GbsHeader {
+    pub version: u8,
+    pub songs: u8,
+    // 1 based
+    pub first_song: u8,
+    pub load: u16,
+    pub init: u16,
+    pub play: u16,
+    pub sp: u16,
+    pub tma: u8,
+    pub tac: u8,
+    pub title: String,
+    pub author: String,
+    pub copyright: String,
+}
+
+fn word(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+// a 32 byte, zero padded string field
+fn text(data: &[u8], at: usize) -> String {
+    let field = &data[at..at + 32];
+    let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+impl GbsHeader {
+    pub fn parse(data: &[u8]) -> Result<Self, GbsError> {
+        if data.len() < HEADER_SIZE {
+            return Err(GbsError::TooShort(data.len()));
+        }
+        if &data[..3] != MAGIC {
+            return Err(GbsError::BadMagic);
+        }
+        if data[0x03] != VERSION {
+            return Err(GbsError::BadVersion(data[0x03]));
+        }
+        let header = Self {
+            version: data[0x03],
+            songs: data[0x04],
+            first_song: data[0x05],
+            load: word(data, 0x06),
+            init: word(data, 0x08),
+            play: word(data, 0x0A),
+            sp: word(data, 0x0C),
+            tma: data[0x0E],
+            tac: data[0x0F],
+            title: text(data, 0x10),
+            author: text(data, 0x30),
+            copyright: text(data, 0x50),
+        };
+        if !(MIN_LOAD..ROM_END).contains(&header.load) {
+            return Err(GbsError::BadLoadAddress(header.load));
+        }
+        let end = header.load as usize + data.len() - HEADER_SIZE;
+        if end > MAX_ROM_SIZE {
+            return Err(GbsError::TooLarge(end));
+        }
+        // both have to land in the data while bank 1 is selected
+        for entry in [header.init, header.play] {
+            if entry < header.load || entry as usize >= end.min(ROM_END as usize) {
+                return Err(GbsError::BadEntry(entry));
+            }
+        }
+        Ok(header)
+    }
+
+    // PLAY runs on timer overflows rather than VBlank
+    pub fn timer_driven(&self) -> bool {
+        self.tac & TAC_ENABLE != 0
+    }
+
+    pub fn double_speed(&self) -> bool {
+        self.tac & TAC_DOUBLE_SPEED != 0
+    }
+}
+
+pub struct Gbs {
+    pub header: GbsHeader,
+    data: Vec<u8>,
+}
+
+impl Gbs {
+    pub fn new(data: &[u8]) -> Result<Self, GbsError> {
+        Ok(Self {
+            header: GbsHeader::parse(data)?,
+            data: data[HEADER_SIZE..].to_vec(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, GbsError> {
+        let data = fs::read(path).map_err(|err| GbsError::Io(err.to_string()))?;
+        Self::new(&data)
+    }
+
+    // the synthetic rom: the data at its load address, in whole banks, with
+    // the vectors and the driver below it. Double speed needs a CGB rom
+    pub fn rom(&self) -> Vec<u8> {
+        let load = self.header.load as usize;
+        let size = (load + self.data.len()).div_ceil(ROM_BANK_SIZE).max(2) * ROM_BANK_SIZE;
+        let mut rom = vec![0xFF; size];
+        rom[load..load + self.data.len()].copy_from_slice(&self.data);
+
+        for vector in RST_VECTORS {
+            let [low, high] = (self.header.load + vector).to_le_bytes();
+            rom[vector as usize..vector as usize + 3].copy_from_slice(&[0xC3, low, high]);
+        }
+        let (driver, play) = driver(&self.header);
+        rom[DRIVER as usize..DRIVER as usize + driver.len()].copy_from_slice(&driver);
+        let [low, high] = play.to_le_bytes();
+        for vector in [VBLANK_VECTOR, TIMER_VECTOR] {
+            rom[vector as usize..vector as usize + 3].copy_from_slice(&[0xC3, low, high]);
+        }
+        if self.header.double_speed() {
+            rom[HEADER_CGB_FLAG] = CGB_ONLY;
+        }
+        rom
+    }
+}
+
+// the driver's code, and the address of `play` in it
+fn driver(header: &GbsHeader) -> (Vec<u8>, u16) {
+    let interrupt = if header.timer_driven() { Interrupt::Timer } else { Interrupt::VBlank };
+    let speed = if header.double_speed() { "ld a, $01\nldh [$4D], a\nstop\n" } else { "" };
+    let src = format!(
+        "{}call ${:04X}\nld a, ${:02X}\nldh [$FF], a\nei\nidle: halt\njr idle\n",
+        speed, header.init, interrupt as u8,
+    );
+    let mut code = asm::assemble(&src, DRIVER).expect("gbs driver assembles");
+    let play = DRIVER + code.len() as u16;
+    let src = format!("call ${:04X}\nreti\n", header.play);
+    code.extend(asm::assemble(&src, play).expect("gbs driver assembles"));
+    (code, play)
+}
+
+pub struct GbsPlayer {
+    pub gb: GameBoy,
+    pub header: GbsHeader,
+}
+
+impl GbsPlayer {
+    // starts `song` (1 based) by calling INIT with it in a
+    pub fn new(gbs: &Gbs, song: u8) -> Result<Self, GbsError> {
+        if song == 0 || song > gbs.header.songs {
+            return Err(GbsError::BadSong(song));
+        }
+        let model = if gbs.header.double_speed() { Model::CGB } else { Model::DMG };
+        let mut mother = Motherboard::new();
+        mother.load_rom(&gbs.rom());
+        mother.bank_select = BankSelect::Byte;
+        mother.skip_boot(model);
+        mother.cpu.write_reg(Reg::A, RegBytes::new_single(song - 1));
+        mother.cpu.sp = gbs.header.sp;
+        mother.cpu.pc = DRIVER;
+        mother.put_mem_at(IF, 0);
+        if gbs.header.timer_driven() {
+            mother.put_mem_at(timer::TMA, gbs.header.tma);
+            mother.put_mem_at(timer::TAC, gbs.header.tac & !TAC_DOUBLE_SPEED);
+        }
+
+        Ok(Self {
+            gb: GameBoy::new(mother),
+            header: gbs.header.clone(),
+        })
+    }
+
+    pub fn step(&mut self) {
+        self.gb.step();
+    }
+
+    // plays for `cycles` T-cycles, recording at `sample_rate`
+    pub fn record(&mut self, cycles: u64, sample_rate: u32, stems: bool) -> Recording {
+        capture::record(&mut self.gb, cycles, sample_rate, stems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOAD: u16 = 0x0400;
+    const SONG: u16 = 0xC000;
+    const PLAYS: u16 = 0xC001;
+
+    // a gbs whose INIT stores the song at SONG and whose PLAY counts its
+    // calls at PLAYS, padded to `size` bytes of data
+    fn gbs_data(tac: u8, size: usize) -> Vec<u8> {
+        let code = asm!("ld [$C000], a\nret\nld hl, $C001\ninc [hl]\nret", LOAD);
+        let mut data = vec![0; HEADER_SIZE];
+        data[..3].copy_from_slice(MAGIC);
+        data[0x03] = VERSION;
+        data[0x04] = 2;
+        data[0x05] = 1;
+        data[0x06..0x08].copy_from_slice(&LOAD.to_le_bytes());
+        data[0x08..0x0A].copy_from_slice(&LOAD.to_le_bytes());
+        data[0x0A..0x0C].copy_from_slice(&(LOAD + 4).to_le_bytes());
+        data[0x0C..0x0E].copy_from_slice(&0xDFFFu16.to_le_bytes());
+        data[0x0F] = tac;
+        data.extend(&code);
+        data.resize(HEADER_SIZE + size.max(code.len()), 0);
+        data
+    }
+
+    fn player(tac: u8, song: u8) -> GbsPlayer {
+        let gbs = Gbs::new(&gbs_data(tac, 0)).unwrap();
+        GbsPlayer::new(&gbs, song).unwrap()
+    }
+
+    fn run(player: &mut GbsPlayer, cycles: u64) {
+        while player.gb.cycles < cycles {
+            player.step();
+        }
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut data = gbs_data(0, 0);
+        data[0x03] = 2;
+        assert!(matches!(GbsHeader::parse(&data), Err(GbsError::BadVersion(2))));
+    }
+
+    #[test]
+    fn rejects_bad_load_addresses() {
+        for load in [0x0200u16, 0x8000, 0xFFF0] {
+            let mut data = gbs_data(0, 0);
+            data[0x06..0x08].copy_from_slice(&load.to_le_bytes());
+            assert!(matches!(GbsHeader::parse(&data), Err(GbsError::BadLoadAddress(addr)) if addr == load));
+        }
+    }
+
+    // INIT and PLAY have to point into the data, below 0x8000. The test
+    // data is 9 bytes long
+    #[test]
+    fn rejects_bad_entries() {
+        for (field, addr) in [(0x08, 0x03FFu16), (0x0A, LOAD + 9), (0x0A, 0x8000)] {
+            let mut data = gbs_data(0, 0);
+            data[field..field + 2].copy_from_slice(&addr.to_le_bytes());
+            assert!(matches!(GbsHeader::parse(&data), Err(GbsError::BadEntry(entry)) if entry == addr));
+        }
+        let mut data = gbs_data(0, 0x8000);
+        data[0x0A..0x0C].copy_from_slice(&0x8000u16.to_le_bytes());
+        assert!(matches!(GbsHeader::parse(&data), Err(GbsError::BadEntry(0x8000))));
+    }
+
+    #[test]
+    fn rejects_data_past_the_last_bank() {
+        let size = MAX_ROM_SIZE - LOAD as usize;
+        assert!(GbsHeader::parse(&gbs_data(0, size)).is_ok());
+        let data = gbs_data(0, size + 1);
+        assert!(matches!(GbsHeader::parse(&data), Err(GbsError::TooLarge(end)) if end == MAX_ROM_SIZE + 1));
+    }
+
+    #[test]
+    fn init_gets_the_song() {
+        let mut player = player(0, 2);
+        run(&mut player, 100);
+        assert_eq!(player.gb.mother.get_mem_at(SONG), 1);
+        assert_eq!(player.gb.mother.get_mem_at(PLAYS), 0);
+    }
+
+    // PLAY runs from the VBlank interrupt, once a frame
+    #[test]
+    fn vblank_drives_play() {
+        let mut player = player(0, 1);
+        run(&mut player, 70224 * 10);
+        let plays = player.gb.mother.get_mem_at(PLAYS);
+        assert!((9..=10).contains(&plays), "{} plays", plays);
+        assert!(player.gb.mother.cpu.halted);
+    }
+
+    // at 262144 Hz from TMA 0, the timer overflows every 4096 cycles
+    #[test]
+    fn timer_drives_play() {
+        let mut player = player(0x05, 1);
+        run(&mut player, 4096 * 20);
+        let plays = player.gb.mother.get_mem_at(PLAYS);
+        assert!((19..=20).contains(&plays), "{} plays", plays);
+    }
+
+    // double speed runs the timer, and PLAY, twice as often in real time,
+    // less the time the switch takes
+    #[test]
+    fn double_speed() {
+        let mut player = player(0x85, 1);
+        assert_eq!(player.gb.mother.model, Model::CGB);
+        while player.gb.mother.apu.dots() < 4096 * 20 {
+            player.step();
+        }
+        assert!(player.gb.mother.clock.double_speed());
+        assert_eq!(player.gb.mother.timer.read(timer::TAC) & 0x07, 0x05);
+        let plays = player.gb.mother.get_mem_at(PLAYS);
+        assert!((36..=40).contains(&plays), "{} plays", plays);
+    }
+
+    // the whole byte selects the bank, so ones above 0x1F can be reached
+    #[test]
+    fn eight_bit_banks() {
+        let size = 0x22 * ROM_BANK_SIZE - LOAD as usize;
+        let mut data = gbs_data(0, size);
+        let marker = HEADER_SIZE + 0x21 * ROM_BANK_SIZE - LOAD as usize;
+        data[marker] = 0x21;
+        let gbs = Gbs::new(&data).unwrap();
+        let mut player = GbsPlayer::new(&gbs, 1).unwrap();
+        player.gb.mother.put_mem_at(0x2000, 0x21);
+        assert_eq!(player.gb.mother.get_mem_at(0x4000), 0x21);
+    }
+}
@@ -0,0 +1,157 @@
+// CGB VRAM DMA (HDMA1-HDMA5). Writing HDMA5 starts either a general purpose
+// transfer, which copies everything at once while the cpu is halted, or an
+// HBlank transfer copying one 16 byte block at the start of each HBlank.
+// Clearing bit 7 in a write while an HBlank transfer runs cancels it.
+
+pub const HDMA1: u16 = 0xFF51;
+pub const HDMA2: u16 = 0xFF52;
+pub const HDMA3: u16 = 0xFF53;
+pub const HDMA4: u16 = 0xFF54;
+pub const HDMA5: u16 = 0xFF55;
+
+pub const BLOCK_SIZE: u16 = 0x10;
+
+const HDMA5_HBLANK: u8 = 0x80;
+const HDMA5_LENGTH: u8 = 0x7F;
+
+pub struct Hdma {
+    src: u16,
+    // offset into VRAM
+    dst: u16,
+    // blocks left, so HDMA5 reads them back minus one
+    blocks: u8,
+    hblank: bool,
+}
+
+impl Hdma {
+    pub fn new() -> Self {
+        Self {
+            src: 0,
+            dst: 0,
+            blocks: 0,
+            hblank: false,
+        }
+    }
+
+    // an HBlank transfer is waiting for the next HBlank
+    pub fn hblank_active(&self) -> bool {
+        self.hblank
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            // bit 7 reads 0 while an HBlank transfer runs, and all ones once
+            // a transfer has completed
+            HDMA5 => {
+                let active = if self.hblank { 0 } else { HDMA5_HBLANK };
+                active | self.blocks.wrapping_sub(1) & HDMA5_LENGTH
+            },
+            _ => 0xFF,
+        }
+    }
+
+    // returns the blocks of a general purpose transfer to copy right away
+    pub fn write(&mut self, addr: u16, val: u8) -> u8 {
+        match addr {
+            HDMA1 => self.src = (self.src & 0x00FF) | (val as u16) << 8,
+            HDMA2 => self.src = (self.src & 0xFF00) | (val & 0xF0) as u16,
+            HDMA3 => self.dst = (self.dst & 0x00FF) | ((val & 0x1F) as u16) << 8,
+            HDMA4 => self.dst = (self.dst & 0xFF00) | (val & 0xF0) as u16,
+            _ => {
+                if self.hblank && val & HDMA5_HBLANK == 0 {
+                    self.hblank = false;
+                    return 0;
+                }
+                self.blocks = (val & HDMA5_LENGTH) + 1;
+                if val & HDMA5_HBLANK != 0 {
+                    self.hblank = true;
+                }
+                else {
+                    return self.blocks;
+                }
+            },
+        }
+        0
+    }
+
+    // source and VRAM destination of the next block, moving both past it
+    pub fn next_block(&mut self) -> (u16, u16) {
+        let block = (self.src, 0x8000 | (self.dst & 0x1FF0));
+        self.src = self.src.wrapping_add(BLOCK_SIZE);
+        self.dst = (self.dst + BLOCK_SIZE) & 0x1FF0;
+        self.blocks -= 1;
+        if self.blocks == 0 {
+            self.hblank = false;
+        }
+        block
+    }
+}
+
+impl Default for Hdma {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(src: u16, dst: u16) -> Hdma {
+        let mut hdma = Hdma::new();
+        hdma.write(HDMA1, (src >> 8) as u8);
+        hdma.write(HDMA2, src as u8);
+        hdma.write(HDMA3, (dst >> 8) as u8);
+        hdma.write(HDMA4, dst as u8);
+        hdma
+    }
+
+    #[test]
+    fn addresses_are_masked() {
+        let mut hdma = setup(0xC0FF, 0xFFFF);
+        hdma.write(HDMA5, 0x01);
+        assert_eq!(hdma.next_block(), (0xC0F0, 0x9FF0));
+        // the destination wraps round VRAM
+        assert_eq!(hdma.next_block(), (0xC100, 0x8000));
+        assert_eq!(hdma.read(HDMA1), 0xFF);
+    }
+
+    #[test]
+    fn general_purpose() {
+        let mut hdma = setup(0xC000, 0x8000);
+        assert_eq!(hdma.write(HDMA5, 0x03), 4);
+        assert!(!hdma.hblank_active());
+        for _ in 0..4 {
+            hdma.next_block();
+        }
+        assert_eq!(hdma.read(HDMA5), 0xFF);
+    }
+
+    #[test]
+    fn hblank_readback() {
+        let mut hdma = setup(0xC000, 0x8000);
+        assert_eq!(hdma.write(HDMA5, 0x82), 0);
+        assert!(hdma.hblank_active());
+        assert_eq!(hdma.read(HDMA5), 0x02);
+        hdma.next_block();
+        assert_eq!(hdma.read(HDMA5), 0x01);
+        hdma.next_block();
+        hdma.next_block();
+        assert!(!hdma.hblank_active());
+        assert_eq!(hdma.read(HDMA5), 0xFF);
+    }
+
+    #[test]
+    fn hblank_cancel() {
+        let mut hdma = setup(0xC000, 0x8000);
+        hdma.write(HDMA5, 0x85);
+        hdma.next_block();
+        assert_eq!(hdma.write(HDMA5, 0x00), 0);
+        assert!(!hdma.hblank_active());
+        // bit 7 set, with the blocks that were left
+        assert_eq!(hdma.read(HDMA5), 0x84);
+        // and it picks up where it stopped
+        hdma.write(HDMA5, 0x80);
+        assert_eq!(hdma.next_block(), (0xC010, 0x8010));
+    }
+}
@@ -0,0 +1,173 @@
+// P1/JOYP (0xFF00). The eight buttons sit in a 2x4 matrix: writing 0 to bit
+// 4 selects the d-pad and 0 to bit 5 the other buttons, and the low nibble
+// reads the selected lines, each low while one of its buttons is held. A
+// line going from high to low, from a press or from selecting a group with
+// a button already held, requests the joypad interrupt and wakes the cpu
+// from STOP.
+
+pub const P1: u16 = 0xFF00;
+
+const SELECT_DPAD: u8 = 0x10;
+const SELECT_BUTTONS: u8 = 0x20;
+const SELECT_MASK: u8 = SELECT_DPAD | SELECT_BUTTONS;
+const P1_UNUSED: u8 = 0xC0;
+pub const LINES: u8 = 0x0F;
+
+// d-pad then buttons, each group in the order of the lines it pulls low
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Button {
+    Right,
+    Left,
+    Up,
+    Down,
+    A,
+    B,
+    Select,
+    Start,
+}
+
+// the buttons held, a bit each in Button order
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ButtonState(u8);
+
+impl ButtonState {
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    pub fn press(&mut self, button: Button) {
+        self.0 |= 1 << button as u8;
+    }
+
+    pub fn release(&mut self, button: Button) {
+        self.0 &= !(1 << button as u8);
+    }
+
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.0 & (1 << button as u8) != 0
+    }
+
+    fn dpad(&self) -> u8 {
+        self.0 & 0x0F
+    }
+
+    fn buttons(&self) -> u8 {
+        self.0 >> 4
+    }
+}
+
+pub struct Joypad {
+    // bits 4 and 5 as last written
+    select: u8,
+    buttons: ButtonState,
+}
+
+impl Joypad {
+    pub fn new() -> Self {
+        Self {
+            select: SELECT_MASK,
+            buttons: ButtonState::new(),
+        }
+    }
+
+    pub fn buttons(&self) -> ButtonState {
+        self.buttons
+    }
+
+    // the low nibble of P1, 0 for lines pulled low
+    pub fn lines(&self) -> u8 {
+        let mut pressed = 0;
+        if self.select & SELECT_DPAD == 0 {
+            pressed |= self.buttons.dpad();
+        }
+        if self.select & SELECT_BUTTONS == 0 {
+            pressed |= self.buttons.buttons();
+        }
+        LINES & !pressed
+    }
+
+    pub fn read(&self) -> u8 {
+        P1_UNUSED | self.select | self.lines()
+    }
+
+    // these return whether a line fell
+    pub fn write(&mut self, val: u8) -> bool {
+        let old = self.lines();
+        self.select = val & SELECT_MASK;
+        fell(old, self.lines())
+    }
+
+    pub fn set_buttons(&mut self, buttons: ButtonState) -> bool {
+        let old = self.lines();
+        self.buttons = buttons;
+        fell(old, self.lines())
+    }
+}
+
+impl Default for Joypad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fell(old: u8, new: u8) -> bool {
+    old & !new != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(buttons: &[Button]) -> ButtonState {
+        let mut state = ButtonState::new();
+        for button in buttons {
+            state.press(*button);
+        }
+        state
+    }
+
+    #[test]
+    fn button_state() {
+        let mut state = held(&[Button::A, Button::Down]);
+        assert!(state.is_pressed(Button::A));
+        assert!(!state.is_pressed(Button::B));
+        assert_eq!((state.dpad(), state.buttons()), (0x08, 0x01));
+        state.release(Button::A);
+        assert!(!state.is_pressed(Button::A));
+        assert_eq!(state, held(&[Button::Down]));
+    }
+
+    #[test]
+    fn lines() {
+        let mut joypad = Joypad::new();
+        joypad.set_buttons(held(&[Button::Left, Button::Start]));
+        assert_eq!(joypad.read(), 0xFF);
+        joypad.write(SELECT_BUTTONS);
+        assert_eq!(joypad.read(), 0xED);
+        joypad.write(SELECT_DPAD);
+        assert_eq!(joypad.read(), 0xD7);
+        joypad.write(0);
+        assert_eq!(joypad.read(), 0xC5);
+    }
+
+    #[test]
+    fn falling_lines() {
+        let mut joypad = Joypad::new();
+        assert!(!joypad.set_buttons(held(&[Button::Up])));
+        assert!(joypad.write(SELECT_BUTTONS));
+        assert!(!joypad.write(SELECT_BUTTONS));
+        assert!(!joypad.write(SELECT_MASK));
+        assert!(!joypad.write(SELECT_DPAD));
+        assert!(joypad.set_buttons(held(&[Button::Up, Button::B])));
+        assert!(!joypad.set_buttons(held(&[Button::Up, Button::B, Button::Left])));
+        // selecting the d-pad as well drops Up's line 2
+        assert!(joypad.write(0));
+        assert_eq!(joypad.lines(), 0x09);
+        // without Up, all it adds is Left on line 1, which B already holds low
+        assert!(!joypad.write(SELECT_DPAD));
+        assert!(!joypad.set_buttons(held(&[Button::B, Button::Left])));
+        assert!(!joypad.write(0));
+        assert_eq!(joypad.lines(), 0x0D);
+        assert!(!joypad.set_buttons(ButtonState::new()));
+    }
+}
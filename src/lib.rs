@@ -0,0 +1,35 @@
+pub mod analyzer;
+pub mod apu;
+pub mod apu_log;
+pub mod asm;
+pub mod blargg;
+pub mod blip;
+pub mod boot_rom;
+pub mod capture;
+pub mod channel;
+pub mod clock;
+pub mod cmd;
+pub mod common;
+pub mod cpu;
+pub mod fifo;
+pub mod gameboy;
+pub mod gbs;
+pub mod hdma;
+pub mod joypad;
+pub mod model;
+pub mod midi;
+pub mod mooneye;
+pub mod motherboard;
+pub mod notes;
+pub mod oam_bug;
+pub mod oam_dma;
+pub mod op_cmds;
+pub mod opcodes;
+pub mod ppu;
+pub mod render;
+pub mod runner;
+pub mod serial;
+pub mod single_step;
+pub mod timer;
+pub mod vgm;
+pub mod wav;
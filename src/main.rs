@@ -1,9 +1,238 @@
-mod cmd;
-mod common;
-mod cpu;
-mod motherboard;
-//mod op_cmds;
+use rustgb::analyzer::Analysis;
+use rustgb::apu;
+use rustgb::blargg;
+use rustgb::boot_rom::BootRom;
+use rustgb::capture::{self, Recording};
+use rustgb::gbs::{Gbs, GbsPlayer};
+use rustgb::midi;
+use rustgb::mooneye;
+use rustgb::motherboard::Motherboard;
+use rustgb::runner::{self, Outcome};
+use rustgb::single_step;
+use rustgb::vgm;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+const DEFAULT_RECORD_SECONDS: f64 = 10.0;
+
+fn usage() -> ! {
+    eprintln!("usage: RustGB analyze <rom> [--sym <out.sym>] [--map <out.map>]");
+    eprintln!("       RustGB sm83 <test dir>");
+    eprintln!("       RustGB blargg <rom dir> [--cycles <budget>] [--boot <boot rom>]");
+    eprintln!("       RustGB mooneye <rom dir> [--cycles <budget>] [--boot <boot rom>]");
+    eprintln!("       RustGB record <rom> <out.wav> [--seconds <secs>] [--rate <hz>] [--stems] [--vgm <out.vgm> [--loop <secs>]] [--midi <out.mid>] [--boot <boot rom>]");
+    eprintln!("       RustGB gbs <file.gbs> <out.wav> [--song <n>] [--seconds <secs>] [--rate <hz>] [--stems] [--vgm <out.vgm> [--loop <secs>]] [--midi <out.mid>]");
+    process::exit(1);
+}
+
+// returns the value following `flag` in `args`, if present
+fn flag_val<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|idx| args.get(idx + 1))
+        .map(|val| val.as_str())
+}
+
+fn read_file(path: &str) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| {
+        eprintln!("failed to read {}: {}", path, err);
+        process::exit(1);
+    })
+}
+
+fn write_file(path: &str, contents: &str) {
+    if let Err(err) = fs::write(path, contents) {
+        eprintln!("failed to write {}: {}", path, err);
+        process::exit(1);
+    }
+}
+
+fn boot_rom(args: &[String]) -> Option<BootRom> {
+    flag_val(args, "--boot").map(|path| {
+        BootRom::load(Path::new(path)).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        })
+    })
+}
+
+fn analyze(args: &[String]) {
+    let rom_path = args.first().unwrap_or_else(|| usage());
+    let analysis = Analysis::new(&read_file(rom_path));
+
+    match flag_val(args, "--sym") {
+        Some(path) => write_file(path, &analysis.to_sym()),
+        None => print!("{}", analysis.to_sym()),
+    }
+    if let Some(path) = flag_val(args, "--map") {
+        write_file(path, &analysis.to_map());
+    }
+}
+
+fn sm83(args: &[String]) {
+    let dir = args.first().unwrap_or_else(|| usage());
+    let report = single_step::run_dir(Path::new(dir)).unwrap_or_else(|err| {
+        eprintln!("failed to run {}: {}", dir, err);
+        process::exit(1);
+    });
+    print!("{}", report.summary());
+    if !report.all_passed() {
+        process::exit(1);
+    }
+}
+
+fn run_blargg(args: &[String]) {
+    let dir = args.first().unwrap_or_else(|| usage());
+    let budget = match flag_val(args, "--cycles") {
+        Some(cycles) => cycles.parse().unwrap_or_else(|_| usage()),
+        None => blargg::DEFAULT_CYCLE_BUDGET,
+    };
+    let results = blargg::run_dir(Path::new(dir), boot_rom(args).as_ref(), budget).unwrap_or_else(|err| {
+        eprintln!("failed to run {}: {}", dir, err);
+        process::exit(1);
+    });
+    for (path, result) in &results {
+        println!("{:?} {} ({} cycles)", result.outcome, path.display(), result.cycles);
+    }
+    if results.iter().any(|(_, result)| result.outcome != Outcome::Passed) {
+        process::exit(1);
+    }
+}
+
+fn run_mooneye(args: &[String]) {
+    let dir = args.first().unwrap_or_else(|| usage());
+    let budget = match flag_val(args, "--cycles") {
+        Some(cycles) => cycles.parse().unwrap_or_else(|_| usage()),
+        None => mooneye::DEFAULT_CYCLE_BUDGET,
+    };
+    let results = mooneye::run_dir(Path::new(dir), boot_rom(args).as_ref(), budget).unwrap_or_else(|err| {
+        eprintln!("failed to run {}: {}", dir, err);
+        process::exit(1);
+    });
+    for (path, result) in &results {
+        match result.regs {
+            Some(regs) if result.outcome != Outcome::Passed => {
+                println!("{:?} {} (regs {:02x?})", result.outcome, path.display(), regs)
+            },
+            _ => println!("{:?} {} ({} cycles)", result.outcome, path.display(), result.cycles),
+        }
+    }
+    let passed = results.iter().filter(|(_, result)| result.outcome == Outcome::Passed).count();
+    println!("{}/{} passed", passed, results.len());
+    if passed != results.len() {
+        process::exit(1);
+    }
+}
+
+// the length in cycles, sample rate and whether to write stems asked for
+fn record_opts(args: &[String]) -> (u64, u32, bool) {
+    let seconds: f64 = match flag_val(args, "--seconds") {
+        Some(secs) => secs.parse().unwrap_or_else(|_| usage()),
+        None => DEFAULT_RECORD_SECONDS,
+    };
+    let rate = match flag_val(args, "--rate") {
+        Some(rate) => rate.parse().unwrap_or_else(|_| usage()),
+        None => apu::DEFAULT_SAMPLE_RATE,
+    };
+    let stems = args.iter().any(|arg| arg == "--stems");
+    ((seconds * apu::CLOCK_RATE as f64) as u64, rate, stems)
+}
+
+fn save_recording(recording: &Recording, out: &str) {
+    if let Err(err) = recording.save(Path::new(out)) {
+        eprintln!("failed to write {}: {}", out, err);
+        process::exit(1);
+    }
+    println!("wrote {} samples at {} Hz to {}", recording.len(), recording.sample_rate, out);
+}
+
+// starts the apu log for --vgm, looping back to --loop seconds in, and the
+// note tracker for --midi
+fn start_exports(args: &[String], mother: &mut Motherboard) {
+    if flag_val(args, "--vgm").is_some() {
+        mother.start_apu_log();
+        if let (Some(secs), Some(log)) = (flag_val(args, "--loop"), &mut mother.apu_log) {
+            let secs: f64 = secs.parse().unwrap_or_else(|_| usage());
+            let at = (secs * apu::CLOCK_RATE as f64) as u64;
+            if at >= record_opts(args).0 {
+                eprintln!("--loop has to start before the end of the recording");
+                process::exit(1);
+            }
+            log.loop_at(at);
+        }
+    }
+    if flag_val(args, "--midi").is_some() {
+        mother.start_notes();
+    }
+}
+
+// writes what start_exports started
+fn save_exports(args: &[String], mother: &Motherboard) {
+    if let (Some(path), Some(log)) = (flag_val(args, "--vgm"), &mother.apu_log) {
+        if let Err(err) = vgm::write(Path::new(path), log) {
+            eprintln!("failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("wrote {} apu writes to {}", log.writes().len(), path);
+    }
+    if let (Some(path), Some(notes)) = (flag_val(args, "--midi"), &mother.notes) {
+        if let Err(err) = midi::write(Path::new(path), notes) {
+            eprintln!("failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("wrote the notes to {}", path);
+    }
+}
+
+fn record(args: &[String]) {
+    let (rom_path, out) = match args {
+        [rom_path, out, ..] => (rom_path, out),
+        _ => usage(),
+    };
+    let (cycles, rate, stems) = record_opts(args);
+    let mut gb = runner::load(&read_file(rom_path), boot_rom(args).as_ref());
+    start_exports(args, &mut gb.mother);
+    save_recording(&capture::record(&mut gb, cycles, rate, stems), out);
+    save_exports(args, &gb.mother);
+}
+
+fn play_gbs(args: &[String]) {
+    let (gbs_path, out) = match args {
+        [gbs_path, out, ..] => (gbs_path, out),
+        _ => usage(),
+    };
+    let gbs = Gbs::load(Path::new(gbs_path)).unwrap_or_else(|err| {
+        eprintln!("{}: {}", gbs_path, err);
+        process::exit(1);
+    });
+    let song = match flag_val(args, "--song") {
+        Some(song) => song.parse().unwrap_or_else(|_| usage()),
+        None => gbs.header.first_song,
+    };
+    let mut player = GbsPlayer::new(&gbs, song).unwrap_or_else(|err| {
+        eprintln!("{}: {}", gbs_path, err);
+        process::exit(1);
+    });
+    println!("{} - {} ({}), song {}/{}", gbs.header.title, gbs.header.author, gbs.header.copyright, song, gbs.header.songs);
+
+    let (cycles, rate, stems) = record_opts(args);
+    start_exports(args, &mut player.gb.mother);
+    save_recording(&player.record(cycles, rate, stems), out);
+    save_exports(args, &player.gb.mother);
+}
 
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|cmd| cmd.as_str()) {
+        Some("analyze") => analyze(&args[2..]),
+        Some("sm83") => sm83(&args[2..]),
+        Some("blargg") => run_blargg(&args[2..]),
+        Some("mooneye") => run_mooneye(&args[2..]),
+        Some("record") => record(&args[2..]),
+        Some("gbs") => play_gbs(&args[2..]),
+        _ => usage(),
+    }
 }
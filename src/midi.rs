@@ -0,0 +1,246 @@
+// Export of the notes a NoteTracker followed as a Standard MIDI File. Each
+// APU channel gets a track of its own in a format 1 file: the pulse and wave
+// channels on MIDI channels 1-3 and the noise channel on 10, the General
+// MIDI percussion channel. The file runs at 120 beats per minute with 480
+// ticks a beat, so a second of emulated time is 960 ticks.
+
+use crate::apu;
+use crate::notes::{NoteEvent, NoteTracker, CHANNELS};
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const FORMAT: u16 = 1;
+const TICKS_PER_BEAT: u16 = 480;
+// microseconds a beat
+const TEMPO: u32 = 500_000;
+const TICKS_PER_SECOND: u64 = 960;
+
+const MIDI_CHANNELS: [u8; CHANNELS] = [0, 1, 2, 9];
+const NAMES: [&str; CHANNELS] = ["Pulse 1", "Pulse 2", "Wave", "Noise"];
+// General MIDI square and sawtooth leads. Percussion has no program
+const PROGRAMS: [Option<u8>; CHANNELS] = [Some(80), Some(80), Some(81), None];
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const CONTROL: u8 = 0xB0;
+const PROGRAM: u8 = 0xC0;
+const PITCH_BEND: u8 = 0xE0;
+const CC_EXPRESSION: u8 = 11;
+const OFF_VELOCITY: u8 = 0x40;
+
+const META: u8 = 0xFF;
+const META_NAME: u8 = 0x03;
+const META_TEMPO: u8 = 0x51;
+const META_END: u8 = 0x2F;
+
+fn ticks(dots: u64) -> u64 {
+    apu::scale_dots(dots, TICKS_PER_SECOND)
+}
+
+// a variable length quantity: 7 bits a byte, most significant first, with
+// the top bit set on all but the last
+fn put_var(out: &mut Vec<u8>, val: u64) {
+    let mut bytes = vec![(val & 0x7F) as u8];
+    let mut rest = val >> 7;
+    while rest > 0 {
+        bytes.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
+
+// a track's events, each after the time since the one before
+struct Track {
+    data: Vec<u8>,
+    now: u64,
+}
+
+impl Track {
+    fn put(&mut self, ticks: u64, event: &[u8]) {
+        put_var(&mut self.data, ticks - self.now);
+        self.data.extend_from_slice(event);
+        self.now = ticks;
+    }
+
+    fn put_meta(&mut self, ticks: u64, kind: u8, data: &[u8]) {
+        let mut event = vec![META, kind];
+        put_var(&mut event, data.len() as u64);
+        event.extend_from_slice(data);
+        self.put(ticks, &event);
+    }
+}
+
+fn track(tracker: &NoteTracker, idx: usize) -> Vec<u8> {
+    let mut track = Track {
+        data: Vec::new(),
+        now: 0,
+    };
+    let channel = MIDI_CHANNELS[idx];
+    track.put_meta(0, META_NAME, NAMES[idx].as_bytes());
+    if idx == 0 {
+        track.put_meta(0, META_TEMPO, &TEMPO.to_be_bytes()[1..]);
+    }
+    if let Some(program) = PROGRAMS[idx] {
+        track.put(0, &[PROGRAM | channel, program]);
+    }
+    for (time, event) in tracker.events(idx) {
+        let event = match *event {
+            NoteEvent::On {
+                note,
+                velocity,
+            } => vec![NOTE_ON | channel, note, velocity],
+            NoteEvent::Off {
+                note,
+            } => vec![NOTE_OFF | channel, note, OFF_VELOCITY],
+            NoteEvent::Bend(bend) => vec![PITCH_BEND | channel, (bend & 0x7F) as u8, (bend >> 7) as u8],
+            NoteEvent::Expression(expression) => vec![CONTROL | channel, CC_EXPRESSION, expression],
+        };
+        track.put(ticks(*time), &event);
+    }
+    let end = ticks(tracker.time());
+    if let Some(note) = tracker.sounding(idx) {
+        track.put(end, &[NOTE_OFF | channel, note, OFF_VELOCITY]);
+    }
+    track.put_meta(end, META_END, &[]);
+
+    let mut chunk = b"MTrk".to_vec();
+    chunk.extend_from_slice(&(track.data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(&track.data);
+    chunk
+}
+
+// the tracked notes as a MIDI file, playing up to the tracker's current time
+pub fn encode(tracker: &NoteTracker) -> Vec<u8> {
+    let mut out = b"MThd".to_vec();
+    out.extend_from_slice(&6u32.to_be_bytes());
+    out.extend_from_slice(&FORMAT.to_be_bytes());
+    out.extend_from_slice(&(CHANNELS as u16).to_be_bytes());
+    out.extend_from_slice(&TICKS_PER_BEAT.to_be_bytes());
+    for idx in 0..CHANNELS {
+        out.extend(track(tracker, idx));
+    }
+    out
+}
+
+pub fn write(path: &Path, tracker: &NoteTracker) -> io::Result<()> {
+    fs::write(path, encode(tracker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apu::{Apu, CLOCK_RATE, NR12, NR13, NR14, NR42, NR43, NR44, NR52};
+
+    fn var(val: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        put_var(&mut out, val);
+        out
+    }
+
+    // each track chunk's events
+    fn tracks(midi: &[u8]) -> Vec<&[u8]> {
+        let mut tracks = Vec::new();
+        let mut rest = &midi[14..];
+        while !rest.is_empty() {
+            assert_eq!(&rest[..4], b"MTrk");
+            let len = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            tracks.push(&rest[8..8 + len]);
+            rest = &rest[8 + len..];
+        }
+        tracks
+    }
+
+    fn contains(track: &[u8], event: &[u8]) -> bool {
+        track.windows(event.len()).any(|window| window == event)
+    }
+
+    // a second of A4 on pulse 1, bent up a little halfway, and a hi-hat
+    fn tracker() -> NoteTracker {
+        let mut apu = Apu::new();
+        let mut tracker = NoteTracker::new();
+        let write = |apu: &mut Apu, tracker: &mut NoteTracker, addr, val| {
+            apu.write(addr, val);
+            tracker.observe(addr, val, apu);
+        };
+        write(&mut apu, &mut tracker, NR52, 0x80);
+        write(&mut apu, &mut tracker, NR12, 0xF0);
+        write(&mut apu, &mut tracker, NR13, 0xD6);
+        write(&mut apu, &mut tracker, NR14, 0x86);
+        write(&mut apu, &mut tracker, NR42, 0xF0);
+        write(&mut apu, &mut tracker, NR43, 0x00);
+        write(&mut apu, &mut tracker, NR44, 0x80);
+        for _ in 0..CLOCK_RATE / 128 {
+            tracker.advance(128, &apu);
+        }
+        write(&mut apu, &mut tracker, NR13, 0xE0);
+        for _ in 0..CLOCK_RATE / 128 {
+            tracker.advance(128, &apu);
+        }
+        tracker
+    }
+
+    #[test]
+    fn variable_length() {
+        assert_eq!(var(0), [0x00]);
+        assert_eq!(var(0x7F), [0x7F]);
+        assert_eq!(var(0x80), [0x81, 0x00]);
+        assert_eq!(var(0x2000), [0xC0, 0x00]);
+        assert_eq!(var(0x3FFF), [0xFF, 0x7F]);
+        assert_eq!(var(0x0020_0000), [0x81, 0x80, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn header() {
+        let midi = encode(&NoteTracker::new());
+        assert_eq!(&midi[..14], &[b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 1, 0, 4, 0x01, 0xE0]);
+        let tracks = tracks(&midi);
+        assert_eq!(tracks.len(), CHANNELS);
+        // the tempo goes in the first track
+        assert!(contains(tracks[0], &[META, META_TEMPO, 3, 0x07, 0xA1, 0x20]));
+        assert!(contains(tracks[0], &[META, META_NAME, 7, b'P', b'u', b'l', b's', b'e', b' ', b'1']));
+        for track in &tracks {
+            assert!(track.ends_with(&[0x00, META, META_END, 0x00]));
+        }
+    }
+
+    #[test]
+    fn notes() {
+        let midi = encode(&tracker());
+        let tracks = tracks(&midi);
+        assert!(contains(tracks[0], &[PROGRAM, 80]));
+        assert!(contains(tracks[0], &[NOTE_ON, 69, 127]));
+        // the note ends with the file, a second after the bend
+        assert!(contains(tracks[0], &[0x87, 0x40, NOTE_OFF, 69, OFF_VELOCITY]));
+        assert!(!contains(tracks[1], &[NOTE_ON | 1]));
+    }
+
+    #[test]
+    fn pitch_bend() {
+        let midi = encode(&tracker());
+        let tracks = tracks(&midi);
+        let bend = tracker()
+            .events(0)
+            .iter()
+            .rev()
+            .find_map(|(_, event)| match event {
+                NoteEvent::Bend(bend) => Some(*bend),
+                _ => None,
+            })
+            .unwrap();
+        assert!(bend > 0x2000);
+        // one second in, least significant 7 bits first
+        assert!(contains(tracks[0], &[0x87, 0x40, PITCH_BEND, (bend & 0x7F) as u8, (bend >> 7) as u8]));
+    }
+
+    #[test]
+    fn noise_goes_to_percussion() {
+        let midi = encode(&tracker());
+        let tracks = tracks(&midi);
+        assert!(contains(tracks[3], &[NOTE_ON | 9, 42, 127]));
+        assert!(contains(tracks[3], &[NOTE_OFF | 9, 42, OFF_VELOCITY]));
+        assert!(!contains(tracks[3], &[PROGRAM | 9]));
+        assert!(!contains(tracks[3], &[PITCH_BEND | 9]));
+    }
+}
@@ -0,0 +1,338 @@
+// Game Boy hardware revisions, and the state each one's boot rom leaves the
+// machine in when it hands over to the cartridge at 0x0100. Some of it
+// depends on the cartridge header, so it is computed from the rom.
+
+use crate::cpu::Reg;
+
+const HEADER_TITLE: usize = 0x134;
+const HEADER_CGB_FLAG: usize = 0x143;
+const HEADER_NEW_LICENSEE: usize = 0x144;
+const HEADER_OLD_LICENSEE: usize = 0x14B;
+const HEADER_CHECKSUM: usize = 0x14D;
+
+// the colors a dmg cartridge gets on cgb without a boot rom. The boot rom
+// picks a palette by title; without one the shades are left plain grey
+pub const COMPAT_GREYS: [u16; 4] = [0x7FFF, 0x5294, 0x294A, 0x0000];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Model {
+    DMG0,
+    DMG,
+    MGB,
+    SGB,
+    SGB2,
+    CGB,
+    AGB,
+}
+
+pub struct PostBoot {
+    // A, F, B, C, D, E, H, L
+    pub regs: [(Reg, u8); 8],
+    pub sp: u16,
+    pub pc: u16,
+    // running the cartridge with CGB features, rather than in DMG mode
+    pub cgb_mode: bool,
+    // full 16 bit internal divider, DIV is its upper byte
+    pub div: u16,
+    // scanline and dot within it the ppu has reached
+    pub line: u8,
+    pub dot: u16,
+    // io register contents, 0xFF00-0xFF7F and IE
+    pub io: Vec<(u16, u8)>,
+}
+
+fn header(rom: &[u8], addr: usize) -> u8 {
+    *rom.get(addr).unwrap_or(&0)
+}
+
+// the cgb boot rom picks a compatibility palette for nintendo published dmg
+// games, keyed on the sum of the title bytes, and leaves that sum in B
+fn title_checksum(rom: &[u8]) -> u8 {
+    let old = header(rom, HEADER_OLD_LICENSEE);
+    let new = [header(rom, HEADER_NEW_LICENSEE), header(rom, HEADER_NEW_LICENSEE + 1)];
+    if old == 0x01 || (old == 0x33 && &new == b"01") {
+        (HEADER_TITLE..HEADER_TITLE + 16).fold(0u8, |sum, addr| sum.wrapping_add(header(rom, addr)))
+    }
+    else {
+        0
+    }
+}
+
+fn cgb_cartridge(rom: &[u8]) -> bool {
+    header(rom, HEADER_CGB_FLAG) & 0x80 != 0
+}
+
+impl Model {
+    pub fn is_cgb(self) -> bool {
+        matches!(self, Model::CGB | Model::AGB)
+    }
+
+    pub fn is_sgb(self) -> bool {
+        matches!(self, Model::SGB | Model::SGB2)
+    }
+
+    pub fn from_name(name: &str) -> Option<Model> {
+        match name.to_ascii_lowercase().as_str() {
+            "dmg0" => Some(Model::DMG0),
+            "dmg" => Some(Model::DMG),
+            "mgb" => Some(Model::MGB),
+            "sgb" => Some(Model::SGB),
+            "sgb2" => Some(Model::SGB2),
+            "cgb" => Some(Model::CGB),
+            "agb" => Some(Model::AGB),
+            _ => None,
+        }
+    }
+
+    // a,f,b,c,d,e,h,l as the boot rom leaves them for `rom`
+    fn cpu_regs(self, rom: &[u8]) -> [u8; 8] {
+        // the dmg boot rom ends comparing the header checksum, setting H and C
+        // unless it is zero
+        let dmg_flags = if header(rom, HEADER_CHECKSUM) == 0 { 0x80 } else { 0xB0 };
+        let cgb_mode = cgb_cartridge(rom);
+
+        match self {
+            Model::DMG0 => [0x01, 0x00, 0xFF, 0x13, 0x00, 0xC1, 0x84, 0x03],
+            Model::DMG => [0x01, dmg_flags, 0x00, 0x13, 0x00, 0xD8, 0x01, 0x4D],
+            Model::MGB => [0xFF, dmg_flags, 0x00, 0x13, 0x00, 0xD8, 0x01, 0x4D],
+            Model::SGB => [0x01, 0x00, 0x00, 0x14, 0x00, 0x00, 0xC0, 0x60],
+            Model::SGB2 => [0xFF, 0x00, 0x00, 0x14, 0x00, 0x00, 0xC0, 0x60],
+            Model::CGB | Model::AGB => {
+                let mut regs = if cgb_mode {
+                    [0x11, 0x80, 0x00, 0x00, 0xFF, 0x56, 0x00, 0x0D]
+                }
+                else {
+                    let b = title_checksum(rom);
+                    let (h, l) = if b == 0x43 || b == 0x58 { (0x99, 0x1A) } else { (0x00, 0x7C) };
+                    [0x11, 0x80, b, 0x00, 0x00, 0x08, h, l]
+                };
+                if self == Model::AGB {
+                    // the agb boot rom finishes with an extra inc b
+                    let b = regs[2].wrapping_add(1);
+                    let z = if b == 0 { 0x80 } else { 0 };
+                    let h = if b & 0x0F == 0 { 0x20 } else { 0 };
+                    regs[1] = z | h;
+                    regs[2] = b;
+                }
+                regs
+            },
+        }
+    }
+
+    // internal divider value when the boot rom hands over
+    fn div(self) -> u16 {
+        match self {
+            Model::DMG0 => 0x1830,
+            Model::DMG | Model::MGB => 0xABCC,
+            Model::SGB | Model::SGB2 => 0xD85C,
+            Model::CGB => 0x1EA0,
+            Model::AGB => 0x1EA4,
+        }
+    }
+
+    // (line, dot) of the ppu when the boot rom hands over
+    fn ppu_position(self) -> (u8, u16) {
+        match self {
+            Model::DMG0 => (145, 60),
+            // late in line 153, where LY already reads 0
+            Model::DMG | Model::MGB | Model::SGB | Model::SGB2 => (153, 400),
+            Model::CGB | Model::AGB => (144, 196),
+        }
+    }
+
+    pub fn post_boot(self, rom: &[u8]) -> PostBoot {
+        let cpu = self.cpu_regs(rom);
+        let div = self.div();
+        let (line, dot) = self.ppu_position();
+        let ly = if line == 153 { 0 } else { line };
+        let cgb = self.is_cgb();
+
+        // vblank, with LY == LYC on the models that hand over with LY at 0
+        let stat = if ly == 0 { 0x85 } else { 0x81 };
+        let mut io = vec![
+            (0xFF00, 0xCF),
+            (0xFF01, 0x00),
+            (0xFF02, if cgb { 0x7F } else { 0x7E }),
+            (0xFF04, (div >> 8) as u8),
+            (0xFF05, 0x00),
+            (0xFF06, 0x00),
+            (0xFF07, 0xF8),
+            (0xFF0F, 0xE1),
+            (0xFF10, 0x80),
+            (0xFF11, 0xBF),
+            (0xFF12, 0xF3),
+            (0xFF13, 0xFF),
+            (0xFF14, 0xBF),
+            (0xFF16, 0x3F),
+            (0xFF17, 0x00),
+            (0xFF18, 0xFF),
+            (0xFF19, 0xBF),
+            (0xFF1A, 0x7F),
+            (0xFF1B, 0xFF),
+            (0xFF1C, 0x9F),
+            (0xFF1D, 0xFF),
+            (0xFF1E, 0xBF),
+            (0xFF20, 0xFF),
+            (0xFF21, 0x00),
+            (0xFF22, 0x00),
+            (0xFF23, 0xBF),
+            (0xFF24, 0x77),
+            (0xFF25, 0xF3),
+            (0xFF26, if self.is_sgb() { 0xF0 } else { 0xF1 }),
+            (0xFF40, 0x91),
+            (0xFF41, stat),
+            (0xFF42, 0x00),
+            (0xFF43, 0x00),
+            (0xFF44, ly),
+            (0xFF45, 0x00),
+            (0xFF46, if cgb { 0x00 } else { 0xFF }),
+            (0xFF47, 0xFC),
+            (0xFF48, 0xFF),
+            (0xFF49, 0xFF),
+            (0xFF4A, 0x00),
+            (0xFF4B, 0x00),
+            (0xFFFF, 0x00),
+        ];
+        if cgb {
+            io.extend_from_slice(&[
+                (0xFF4D, 0x7E),
+                (0xFF4F, 0xFE),
+                (0xFF51, 0xFF),
+                (0xFF52, 0xFF),
+                (0xFF53, 0xFF),
+                (0xFF54, 0xFF),
+                (0xFF55, 0xFF),
+                (0xFF56, 0x3E),
+                (0xFF68, 0xC0),
+                (0xFF6A, 0xC1),
+                (0xFF70, 0xF8),
+            ]);
+        }
+
+        PostBoot {
+            regs: [
+                (Reg::A, cpu[0]),
+                (Reg::F, cpu[1]),
+                (Reg::B, cpu[2]),
+                (Reg::C, cpu[3]),
+                (Reg::D, cpu[4]),
+                (Reg::E, cpu[5]),
+                (Reg::H, cpu[6]),
+                (Reg::L, cpu[7]),
+            ],
+            sp: 0xFFFE,
+            pc: 0x0100,
+            cgb_mode: self.is_cgb() && cgb_cartridge(rom),
+            div,
+            line,
+            dot,
+            io,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a header with `title`, licensee `old` and the cgb flag
+    fn rom(title: &[u8], old: u8, cgb_flag: u8) -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        rom[HEADER_TITLE..HEADER_TITLE + title.len()].copy_from_slice(title);
+        rom[HEADER_CGB_FLAG] = cgb_flag;
+        rom[HEADER_OLD_LICENSEE] = old;
+        rom
+    }
+
+    fn regs(state: &PostBoot) -> [u8; 8] {
+        let mut regs = [0; 8];
+        for (val, (_, reg)) in regs.iter_mut().zip(state.regs.iter()) {
+            *val = *reg;
+        }
+        regs
+    }
+
+    fn io(state: &PostBoot, addr: u16) -> Option<u8> {
+        state.io.iter().find(|(io, _)| *io == addr).map(|(_, val)| *val)
+    }
+
+    #[test]
+    fn dmg_flags_follow_the_header_checksum() {
+        let mut cart = rom(b"TEST", 0, 0);
+        assert_eq!(regs(&Model::DMG.post_boot(&cart)), [0x01, 0x80, 0x00, 0x13, 0x00, 0xD8, 0x01, 0x4D]);
+        cart[HEADER_CHECKSUM] = 0x12;
+        assert_eq!(regs(&Model::DMG.post_boot(&cart))[1], 0xB0);
+        assert_eq!(regs(&Model::MGB.post_boot(&cart))[..2], [0xFF, 0xB0]);
+        // the dmg0 boot rom leaves the same state whatever the header
+        assert_eq!(regs(&Model::DMG0.post_boot(&cart)), [0x01, 0x00, 0xFF, 0x13, 0x00, 0xC1, 0x84, 0x03]);
+    }
+
+    #[test]
+    fn cgb_cartridges_run_in_cgb_mode() {
+        let state = Model::CGB.post_boot(&rom(b"TEST", 0, 0x80));
+        assert!(state.cgb_mode);
+        assert_eq!(regs(&state), [0x11, 0x80, 0x00, 0x00, 0xFF, 0x56, 0x00, 0x0D]);
+        assert_eq!(io(&state, 0xFF4D), Some(0x7E));
+        assert!(!Model::DMG.post_boot(&rom(b"TEST", 0, 0x80)).cgb_mode);
+    }
+
+    #[test]
+    fn dmg_cartridges_on_cgb_keep_the_title_checksum() {
+        // nintendo published: the title sum is left in B
+        let state = Model::CGB.post_boot(&rom(b"AB", 0x01, 0));
+        assert!(!state.cgb_mode);
+        assert_eq!(regs(&state), [0x11, 0x80, 0x83, 0x00, 0x00, 0x08, 0x00, 0x7C]);
+        // two sums leave HL pointing into the boot rom's palette table
+        let state = Model::CGB.post_boot(&rom(b"C", 0x01, 0));
+        assert_eq!(regs(&state)[2..], [0x43, 0x00, 0x00, 0x08, 0x99, 0x1A]);
+        // anyone else's title is ignored
+        let mut cart = rom(b"AB", 0x33, 0);
+        assert_eq!(regs(&Model::CGB.post_boot(&cart))[2], 0);
+        cart[HEADER_NEW_LICENSEE..HEADER_NEW_LICENSEE + 2].copy_from_slice(b"01");
+        assert_eq!(regs(&Model::CGB.post_boot(&cart))[2], 0x83);
+    }
+
+    #[test]
+    fn agb_increments_b() {
+        assert_eq!(regs(&Model::AGB.post_boot(&rom(b"", 0, 0x80)))[..3], [0x11, 0x00, 0x01]);
+        // 0xFF + 1 sets Z and H
+        let mut title = [0; 16];
+        title[0] = 0xFF;
+        assert_eq!(regs(&Model::AGB.post_boot(&rom(&title, 0x01, 0)))[..3], [0x11, 0xA0, 0x00]);
+    }
+
+    #[test]
+    fn timing_state() {
+        let cart = rom(b"", 0, 0);
+        let state = Model::DMG.post_boot(&cart);
+        assert_eq!((state.div, state.line, state.dot), (0xABCC, 153, 400));
+        // LY already reads 0 late in line 153, matching LYC
+        assert_eq!(io(&state, 0xFF44), Some(0));
+        assert_eq!(io(&state, 0xFF41), Some(0x85));
+        assert_eq!(io(&state, 0xFF04), Some(0xAB));
+        let state = Model::CGB.post_boot(&cart);
+        assert_eq!((state.div, state.line, state.dot), (0x1EA0, 144, 196));
+        assert_eq!(io(&state, 0xFF44), Some(144));
+        assert_eq!(io(&state, 0xFF41), Some(0x81));
+    }
+
+    #[test]
+    fn model_io() {
+        let cart = rom(b"", 0, 0);
+        assert_eq!(io(&Model::SGB.post_boot(&cart), 0xFF26), Some(0xF0));
+        assert_eq!(io(&Model::DMG.post_boot(&cart), 0xFF26), Some(0xF1));
+        assert_eq!(io(&Model::DMG.post_boot(&cart), 0xFF02), Some(0x7E));
+        assert_eq!(io(&Model::CGB.post_boot(&cart), 0xFF02), Some(0x7F));
+        assert_eq!(io(&Model::DMG.post_boot(&cart), 0xFF70), None);
+        assert_eq!(io(&Model::CGB.post_boot(&cart), 0xFF70), Some(0xF8));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Model::from_name("CGB"), Some(Model::CGB));
+        assert_eq!(Model::from_name("sgb2"), Some(Model::SGB2));
+        assert_eq!(Model::from_name("gba"), None);
+        assert!(Model::AGB.is_cgb() && !Model::SGB.is_cgb());
+        assert!(Model::SGB2.is_sgb() && !Model::MGB.is_sgb());
+    }
+}
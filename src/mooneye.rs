@@ -0,0 +1,81 @@
+// Runner for the Mooneye test suite. Tests finish by executing LD B,B as a
+// software breakpoint, with B,C,D,E,H,L holding the fibonacci numbers
+// 3,5,8,13,21,34 on success and 0x42 on failure. Any other signature means
+// the rom went wrong before it could report, and counts as a crash.
+
+use crate::cpu::Reg;
+use crate::boot_rom::BootRom;
+use crate::gameboy::{self, GameBoy};
+use crate::runner::{self, Outcome};
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const SIGNATURE_REGS: [Reg; 6] = [Reg::B, Reg::C, Reg::D, Reg::E, Reg::H, Reg::L];
+pub const PASS_SIGNATURE: [u8; 6] = [3, 5, 8, 13, 21, 34];
+pub const FAIL_SIGNATURE: [u8; 6] = [0x42; 6];
+
+// ten seconds of emulated time, far more than any acceptance test needs
+pub const DEFAULT_CYCLE_BUDGET: u64 = 4_194_304 * 10;
+
+pub struct MooneyeResult {
+    pub outcome: Outcome,
+    // B,C,D,E,H,L when the breakpoint was hit
+    pub regs: Option<[u8; 6]>,
+    pub cycles: u64,
+}
+
+fn signature(gb: &GameBoy) -> [u8; 6] {
+    let mut regs = [0; 6];
+    for (val, reg) in regs.iter_mut().zip(SIGNATURE_REGS.iter()) {
+        *val = gb.mother.cpu.read_reg(*reg).get_single();
+    }
+    regs
+}
+
+pub fn run_rom(rom: &[u8], boot_rom: Option<&BootRom>, budget: u64) -> MooneyeResult {
+    run(&mut runner::load(rom, boot_rom), budget)
+}
+
+// runs `gb` until it hits the breakpoint or `budget` cycles pass
+pub fn run(gb: &mut GameBoy, budget: u64) -> MooneyeResult {
+    let mut regs = None;
+    gb.breakpoint = false;
+    let outcome = loop {
+        if let Err(msg) = gb.try_step() {
+            break Outcome::Crashed(msg);
+        }
+        if gb.breakpoint {
+            let sig = signature(gb);
+            regs = Some(sig);
+            break match sig {
+                PASS_SIGNATURE => Outcome::Passed,
+                FAIL_SIGNATURE => Outcome::Failed,
+                _ => Outcome::Crashed(format!("breakpoint with unknown signature {:02x?}", sig)),
+            };
+        }
+        if gb.cycles >= budget {
+            break Outcome::Timeout;
+        }
+    };
+
+    MooneyeResult {
+        outcome,
+        regs,
+        cycles: gb.cycles,
+    }
+}
+
+// runs every rom under `dir`, in path order
+pub fn run_dir(dir: &Path, boot_rom: Option<&BootRom>, budget: u64) -> io::Result<Vec<(PathBuf, MooneyeResult)>> {
+    let roms = runner::find_roms(dir)?;
+    gameboy::quiet_panics(|| {
+        roms.into_iter()
+            .map(|path| {
+                let result = run_rom(&fs::read(&path)?, boot_rom, budget);
+                Ok((path, result))
+            })
+            .collect()
+    })
+}
@@ -1,51 +1,664 @@
+use crate::apu::{self, Apu};
+use crate::apu_log::ApuLog;
+use crate::boot_rom::{self, BootRom, BootRomError};
+use crate::clock::{self, Clock};
 use crate::common::RegBytes;
 use crate::cpu::CPU;
+use crate::hdma::{self, Hdma};
+use crate::joypad::{self, Button, ButtonState, Joypad};
+use crate::model::{self, Model};
+use crate::notes::NoteTracker;
+use crate::oam_bug::{self, Corruption};
+use crate::oam_dma::{self, OamDma};
+use crate::ppu::{self, Mode, Ppu};
+use crate::serial::{self, Serial};
+use crate::timer::{self, Timer};
+
+use std::cell::RefCell;
+
+const ROM_END: u16 = 0x8000;
+const ROM_BANK_SIZE: usize = 0x4000;
+
+pub const IF: u16 = 0xFF0F;
+pub const IE: u16 = 0xFFFF;
+const INTERRUPTS: u8 = 0x1F;
+// VBlank's, with the rest following 8 bytes apart
+const INTERRUPT_VECTORS: u16 = 0x0040;
+
+// IF / IE bits
+#[derive(Clone, Copy)]
+pub enum Interrupt {
+    VBlank = 1 << 0,
+    Stat = 1 << 1,
+    Timer = 1 << 2,
+    Serial = 1 << 3,
+    Joypad = 1 << 4,
+}
+
+// how a write to 0x2000-0x3FFF picks the switchable rom bank
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BankSelect {
+    // MBC1: the low 5 bits
+    Mbc1,
+    // the whole byte, as GBS players do
+    Byte,
+}
+
+// one memory access by the cpu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BusAccess {
+    pub addr: u16,
+    pub val: u8,
+    pub write: bool,
+}
+
+// 0xE000-0xFDFF mirrors work ram at 0xC000
+fn ram_index(addr: u16) -> usize {
+    match addr {
+        0xE000..=0xFDFF => (addr - 0x2000 - ROM_END) as usize,
+        _ => (addr - ROM_END) as usize,
+    }
+}
 
 pub struct Motherboard {
     pub cpu: CPU,
+    pub joypad: Joypad,
+    pub serial: Serial,
+    pub timer: Timer,
+    pub apu: Apu,
+    // apu register writes, while logging them
+    pub apu_log: Option<ApuLog>,
+    // the notes the apu plays, while following them
+    pub notes: Option<NoteTracker>,
+    pub ppu: Ppu,
+    pub hdma: Hdma,
+    pub oam_dma: OamDma,
+    pub model: Model,
+    rom: Vec<u8>,
+    rom_bank: usize,
+    pub bank_select: BankSelect,
+    // overlaid on the low rom until 0xFF50 is written
+    boot_rom: Option<BootRom>,
+    // everything from 0x8000 up, or the whole address space on a flat bus
+    ram: Vec<u8>,
+    flat: bool,
+    // T-cycles of the running instruction, and how many of them the hardware
+    // has been advanced through so far
+    instr_cycles: u8,
+    instr_ticked: u8,
+    // T-cycles the cpu is held for after the running instruction
+    stall: u32,
+    // in STOP, until a joypad line falls
+    stopped: bool,
+    pub clock: Clock,
+    // the cpu's reads and writes, while tracing them
+    trace: RefCell<Option<Vec<BusAccess>>>,
 }
 
 impl Motherboard {
     pub fn new() -> Self {
         Self {
             cpu: CPU::new(),
+            joypad: Joypad::new(),
+            serial: Serial::new(),
+            timer: Timer::new(),
+            apu: Apu::new(),
+            apu_log: None,
+            notes: None,
+            ppu: Ppu::new(),
+            hdma: Hdma::new(),
+            oam_dma: OamDma::new(),
+            model: Model::DMG,
+            rom: Vec::new(),
+            rom_bank: 1,
+            bank_select: BankSelect::Mbc1,
+            boot_rom: None,
+            ram: vec![0; 0x10000 - ROM_END as usize],
+            flat: false,
+            instr_cycles: 0,
+            instr_ticked: 0,
+            stall: 0,
+            stopped: false,
+            clock: Clock::new(),
+            trace: RefCell::new(None),
+        }
+    }
+
+    // plain 64 KiB of ram with no rom or io mapping, for cpu tests
+    pub fn new_flat() -> Self {
+        Self {
+            cpu: CPU::new(),
+            joypad: Joypad::new(),
+            serial: Serial::new(),
+            timer: Timer::new(),
+            apu: Apu::new(),
+            apu_log: None,
+            notes: None,
+            ppu: Ppu::new(),
+            hdma: Hdma::new(),
+            oam_dma: OamDma::new(),
+            model: Model::DMG,
+            rom: Vec::new(),
+            rom_bank: 1,
+            bank_select: BankSelect::Mbc1,
+            boot_rom: None,
+            ram: vec![0; 0x10000],
+            flat: true,
+            instr_cycles: 0,
+            instr_ticked: 0,
+            stall: 0,
+            stopped: false,
+            clock: Clock::new(),
+            trace: RefCell::new(None),
+        }
+    }
+
+    pub fn load_rom(&mut self, rom: &[u8]) {
+        self.rom = rom.to_vec();
+    }
+
+    // starts recording the cpu's memory accesses
+    pub fn start_trace(&mut self) {
+        *self.trace.get_mut() = Some(Vec::new());
+    }
+
+    // stops recording, returning the accesses since start_trace
+    pub fn take_trace(&mut self) -> Vec<BusAccess> {
+        self.trace.get_mut().take().unwrap_or_default()
+    }
+
+    fn record(&self, access: BusAccess) {
+        if let Some(trace) = self.trace.borrow_mut().as_mut() {
+            trace.push(access);
         }
     }
 
-    pub fn get_mem_at(&self, addr: u16) -> u8 {
-        0
+    // starts the loaded cartridge through `boot_rom` from 0x0000, or without
+    // one straight from 0x0100 in the state `model`'s boot rom leaves behind.
+    // A boot rom for another model is refused
+    pub fn power_on(&mut self, boot_rom: Option<BootRom>, model: Model) -> Result<(), BootRomError> {
+        match boot_rom {
+            Some(boot_rom) if boot_rom.model != model => Err(BootRomError::WrongModel(boot_rom.model, model)),
+            Some(boot_rom) => {
+                // a cgb starts in CGB mode, until the boot rom hands a dmg
+                // cartridge over through KEY0
+                self.model = model;
+                self.cpu = CPU::new();
+                self.ppu.cgb = model.is_cgb();
+                self.serial.set_cgb(model.is_cgb());
+                self.apu.set_cgb(model.is_cgb());
+                self.boot_rom = Some(boot_rom);
+                Ok(())
+            },
+            None => {
+                self.skip_boot(model);
+                Ok(())
+            },
+        }
     }
 
-    pub fn put_mem_at(&self, addr: u16, val: u8) {
-        ()
+    // puts the machine in the state `model`'s boot rom leaves it in for the
+    // loaded cartridge, ready to run from 0x0100
+    pub fn skip_boot(&mut self, model: Model) {
+        let state = model.post_boot(&self.rom);
+        self.model = model;
+        for (reg, val) in state.regs {
+            self.cpu.write_reg(reg, RegBytes::new_single(val));
+        }
+        self.cpu.sp = state.sp;
+        self.cpu.pc = state.pc;
+        self.timer.set_counter(state.div);
+        self.ppu.cgb = state.cgb_mode;
+        self.ppu.compat = model.is_cgb() && !state.cgb_mode;
+        if self.ppu.compat {
+            for (obj, palette) in [(false, 0), (true, 0), (true, 1)] {
+                self.ppu.set_palette(obj, palette, model::COMPAT_GREYS);
+            }
+        }
+        self.serial.set_cgb(state.cgb_mode);
+        self.apu.set_cgb(model.is_cgb());
+        for (addr, val) in state.io {
+            self.init_io(addr, val);
+        }
+        self.ppu.set_position(state.line, state.dot);
+    }
+
+    // sets an io register without the side effects of a cpu write
+    fn init_io(&mut self, addr: u16, val: u8) {
+        match addr {
+            joypad::P1 => {
+                self.joypad.write(val);
+            },
+            serial::SB | serial::SC => self.serial.write(addr, val),
+            // DIV comes from the counter set above
+            timer::DIV => (),
+            oam_dma::DMA => self.oam_dma.set_reg(val),
+            clock::KEY1 if self.ppu.cgb => self.clock.write(val),
+            timer::TIMA..=timer::TAC => self.timer.write(addr, val),
+            apu::START..=apu::END => self.apu.init(addr, val),
+            ppu::LCDC..=ppu::LYC | ppu::BGP..=ppu::WX | ppu::VBK | ppu::BCPS..=ppu::OPRI => {
+                self.ppu.write(addr, val);
+            },
+            _ => self.ram[ram_index(addr)] = val,
+        }
+    }
+
+    // starts logging apu register writes, from the apu's current state
+    pub fn start_apu_log(&mut self) {
+        self.apu_log = Some(ApuLog::new(self.apu.snapshot()));
+    }
+
+    // starts following the notes the apu plays
+    pub fn start_notes(&mut self) {
+        self.notes = Some(NoteTracker::new());
+    }
+
+    pub fn request_interrupt(&mut self, interrupt: Interrupt) {
+        self.request_interrupts(interrupt as u8);
+    }
+
+    // ORs a mask of Interrupt bits into IF
+    fn request_interrupts(&mut self, mask: u8) {
+        if mask != 0 {
+            self.ram[ram_index(IF)] |= mask;
+        }
+    }
+
+    // STOP: switches speed if KEY1 is armed in CGB mode, holding the cpu while
+    // it happens. Otherwise, unless a button is already held, the cpu sleeps
+    // until one is pressed. Either way the divider is reset
+    pub fn stop(&mut self) {
+        self.write_timer(timer::DIV, 0);
+        if self.ppu.cgb && self.clock.stop() {
+            self.stall += clock::SWITCH_DELAY * 4;
+        }
+        else if !self.flat && self.joypad.lines() == joypad::LINES {
+            self.stopped = true;
+        }
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    pub fn set_buttons(&mut self, buttons: ButtonState) {
+        let fell = self.joypad.set_buttons(buttons);
+        self.joypad_changed(fell);
+    }
+
+    pub fn press(&mut self, button: Button) {
+        let mut buttons = self.joypad.buttons();
+        buttons.press(button);
+        self.set_buttons(buttons);
+    }
+
+    pub fn release(&mut self, button: Button) {
+        let mut buttons = self.joypad.buttons();
+        buttons.release(button);
+        self.set_buttons(buttons);
+    }
+
+    fn joypad_changed(&mut self, fell: bool) {
+        if fell {
+            self.request_interrupt(Interrupt::Joypad);
+            self.stopped = false;
+        }
+    }
+
+    // resetting DIV can clock the apu's frame sequencer
+    fn write_timer(&mut self, addr: u16, val: u8) {
+        let div = self.timer.counter();
+        self.timer.write(addr, val);
+        self.apu.clock_div(div, self.timer.counter(), self.clock.double_speed());
+    }
+
+    // advances the hardware alongside the cpu by `cycles` T-cycles
+    pub fn tick(&mut self, cycles: u8) {
+        if self.flat {
+            return;
+        }
+        let ticks = self.clock.advance(cycles);
+        // STOP holds the divider, the serial clock taken from it, the ppu and
+        // both DMAs
+        if !self.stopped {
+            for _ in 0..ticks.cpu / 4 {
+                if let Some((src, offset)) = self.oam_dma.tick_m() {
+                    let val = self.bus_read(src);
+                    self.oam_dma.set_last(val);
+                    self.ppu.write_oam(ppu::OAM_START + offset as u16, val);
+                }
+            }
+            let div = self.timer.counter();
+            if self.timer.tick(ticks.cpu) {
+                self.request_interrupt(Interrupt::Timer);
+            }
+            self.apu.clock_div(div, self.timer.counter(), self.clock.double_speed());
+            if self.serial.tick(ticks.cpu, self.clock.double_speed()) {
+                self.request_interrupt(Interrupt::Serial);
+            }
+        }
+        self.apu.tick(ticks.dots);
+        if let Some(log) = &mut self.apu_log {
+            log.advance(ticks.dots);
+        }
+        if let Some(notes) = &mut self.notes {
+            notes.advance(ticks.dots, &self.apu);
+        }
+        if self.stopped {
+            return;
+        }
+        let irq = self.ppu.tick(ticks.dots);
+        self.request_interrupts(irq);
+        if self.ppu.take_hblank() && self.hdma.hblank_active() {
+            self.copy_dma_blocks(1);
+        }
+    }
+
+    // copies `blocks` blocks of VRAM DMA, holding the cpu while it happens
+    fn copy_dma_blocks(&mut self, blocks: u8) {
+        for _ in 0..blocks {
+            let (src, dst) = self.hdma.next_block();
+            for i in 0..hdma::BLOCK_SIZE {
+                let val = self.dma_source(src.wrapping_add(i));
+                self.ppu.write_vram(dst + i, val);
+            }
+            self.stall += self.clock.cpu_cycles(32);
+        }
+    }
+
+    // what VRAM DMA reads from `addr`: VRAM itself can't be a source, and
+    // 0xE000 up reads external ram
+    fn dma_source(&self, addr: u16) -> u8 {
+        match addr {
+            ppu::VRAM_START..=ppu::VRAM_END => 0xFF,
+            0xE000..=0xFFFF => self.bus_read(addr - 0x4000),
+            _ => self.bus_read(addr),
+        }
+    }
+
+    // runs the hardware through cycles the cpu was held for, returning them
+    pub fn run_stall(&mut self) -> u32 {
+        let mut total = 0;
+        while self.stall > 0 {
+            let cycles = self.stall.min(0xFC);
+            self.stall -= cycles;
+            total += cycles;
+            self.tick(cycles as u8);
+        }
+        total
+    }
+
+    // called before an instruction taking `cycles` T-cycles runs
+    pub fn begin_instr(&mut self, cycles: u8) {
+        self.instr_cycles = cycles;
+        self.instr_ticked = 0;
+    }
+
+    // advances the hardware to the last m-cycle of the running instruction,
+    // where its memory access happens, so the ppu and timer are seen at the
+    // dot the cpu reads or writes them
+    fn sync(&mut self) {
+        self.sync_to(self.instr_cycles.saturating_sub(4));
+    }
+
+    // advances the hardware to `target` T-cycles into the running instruction
+    fn sync_to(&mut self, target: u8) {
+        if target > self.instr_ticked {
+            let cycles = target - self.instr_ticked;
+            self.instr_ticked = target;
+            self.tick(cycles);
+        }
+    }
+
+    // the DMG OAM corruption bug, for a 16 bit register holding `addr` going
+    // through the increment/decrement unit `cycle` T-cycles into the running
+    // instruction
+    pub fn oam_bug(&mut self, addr: u16, kind: Corruption, cycle: u8) {
+        if self.flat || self.model.is_cgb() || !(oam_bug::START..=oam_bug::END).contains(&addr) {
+            return;
+        }
+        self.sync_to(cycle);
+        self.ppu.corrupt_oam(kind);
+    }
+
+    // runs the hardware through the rest of an instruction that took `cycles`
+    pub fn end_instr(&mut self, cycles: u8) {
+        if cycles > self.instr_ticked {
+            self.tick(cycles - self.instr_ticked);
+        }
+        self.instr_cycles = 0;
+        self.instr_ticked = 0;
+    }
+
+    // VRAM, OAM and the io registers, whose contents move with the hardware
+    fn synced(addr: u16) -> bool {
+        matches!(addr, 0x8000..=0x9FFF | 0xFE00..=0xFF7F)
+    }
+
+    pub fn get_mem_at(&mut self, addr: u16) -> u8 {
+        if !self.flat && Self::synced(addr) {
+            self.sync();
+        }
+        let val = self.cpu_read(addr);
+        self.record(BusAccess {
+            addr,
+            val,
+            write: false,
+        });
+        val
+    }
+
+    fn cpu_read(&self, addr: u16) -> u8 {
+        if self.flat {
+            return self.ram[addr as usize];
+        }
+        if self.oam_dma.transferring() {
+            // OAM is locked, and the bus the transfer reads from carries its
+            // byte whatever address is asked for
+            if (ppu::OAM_START..=0xFEFF).contains(&addr) {
+                return 0xFF;
+            }
+            let cgb = self.ppu.cgb;
+            if oam_dma::bus(addr, cgb).is_some() && oam_dma::bus(addr, cgb) == self.oam_dma.source_bus(cgb) {
+                return self.oam_dma.last();
+            }
+        }
+        if self.locked(addr) {
+            return 0xFF;
+        }
+        self.bus_read(addr)
+    }
+
+    // VRAM is cut off from the cpu while the ppu draws, and OAM while it
+    // scans or draws; reads give 0xFF and writes are dropped
+    fn locked(&self, addr: u16) -> bool {
+        match addr {
+            ppu::VRAM_START..=ppu::VRAM_END => self.ppu.mode() == Mode::Transfer,
+            ppu::OAM_START..=ppu::OAM_END => matches!(self.ppu.mode(), Mode::OamScan | Mode::Transfer),
+            _ => false,
+        }
+    }
+
+    // reads `addr` for inspection tools, seeing through the ppu locks and
+    // OAM DMA
+    pub fn peek(&self, addr: u16) -> u8 {
+        if self.flat {
+            return self.ram[addr as usize];
+        }
+        self.bus_read(addr)
+    }
+
+    // writes `addr` for inspection tools, ignoring the ppu locks
+    pub fn poke(&mut self, addr: u16, val: u8) {
+        match addr {
+            ppu::VRAM_START..=ppu::VRAM_END if !self.flat => self.ppu.write_vram(addr, val),
+            ppu::OAM_START..=ppu::OAM_END if !self.flat => self.ppu.write_oam(addr, val),
+            _ => self.put_mem_at(addr, val),
+        }
+    }
+
+    // reads `addr` as the memory map has it, without OAM DMA getting in the way
+    fn bus_read(&self, addr: u16) -> u8 {
+        if let Some(val) = self.boot_rom.as_ref().and_then(|boot_rom| boot_rom.read(addr)) {
+            return val;
+        }
+        match addr {
+            0..=0x3FFF => *self.rom.get(addr as usize).unwrap_or(&0xFF),
+            0x4000..=0x7FFF => {
+                let offset = self.rom_bank * ROM_BANK_SIZE + (addr as usize - ROM_BANK_SIZE);
+                *self.rom.get(offset).unwrap_or(&0xFF)
+            },
+            joypad::P1 => self.joypad.read(),
+            serial::SB | serial::SC => self.serial.read(addr),
+            timer::DIV..=timer::TAC => self.timer.read(addr),
+            apu::START..=apu::END => self.apu.read(addr),
+            ppu::VRAM_START..=ppu::VRAM_END => self.ppu.read_vram(addr),
+            ppu::OAM_START..=ppu::OAM_END => self.ppu.read_oam(addr),
+            ppu::LCDC..=ppu::LYC | ppu::BGP..=ppu::WX | ppu::VBK | ppu::BCPS..=ppu::OPRI => self.ppu.read(addr),
+            hdma::HDMA1..=hdma::HDMA5 if self.ppu.cgb => self.hdma.read(addr),
+            // KEY1 is only there in CGB mode
+            clock::KEY1 if self.ppu.cgb => self.clock.read(),
+            clock::KEY1 => 0xFF,
+            oam_dma::DMA => self.oam_dma.read(),
+            _ => self.ram[ram_index(addr)],
+        }
+    }
+
+    pub fn put_mem_at(&mut self, addr: u16, val: u8) {
+        self.record(BusAccess {
+            addr,
+            val,
+            write: true,
+        });
+        if self.flat {
+            self.ram[addr as usize] = val;
+            return;
+        }
+        if Self::synced(addr) {
+            self.sync();
+        }
+        match addr {
+            // rom bank select, wrapped to the size of the rom
+            0x2000..=0x3FFF => {
+                let banks = (self.rom.len() / ROM_BANK_SIZE).max(2);
+                let bank = match self.bank_select {
+                    BankSelect::Mbc1 => val & 0x1F,
+                    BankSelect::Byte => val,
+                };
+                self.rom_bank = (bank as usize).max(1) % banks;
+            },
+            0..=0x7FFF => (),
+            joypad::P1 => {
+                let fell = self.joypad.write(val);
+                self.joypad_changed(fell);
+            },
+            serial::SB | serial::SC => self.serial.write(addr, val),
+            // only the cgb boot rom gets to pick the mode
+            boot_rom::KEY0 => {
+                if self.model.is_cgb() && self.boot_rom.is_some() {
+                    self.ram[ram_index(addr)] = val;
+                }
+            },
+            timer::DIV..=timer::TAC => self.write_timer(addr, val),
+            apu::START..=apu::END => {
+                if let Some(log) = &mut self.apu_log {
+                    log.record(addr, val);
+                }
+                self.apu.write(addr, val);
+                if let Some(notes) = &mut self.notes {
+                    notes.observe(addr, val, &self.apu);
+                }
+            },
+            ppu::VRAM_START..=ppu::VRAM_END => {
+                if !self.locked(addr) {
+                    self.ppu.write_vram(addr, val);
+                }
+            },
+            ppu::OAM_START..=ppu::OAM_END => {
+                if !self.oam_dma.transferring() && !self.locked(addr) {
+                    self.ppu.write_oam(addr, val);
+                }
+            },
+            oam_dma::DMA => self.oam_dma.write(val),
+            ppu::LCDC..=ppu::LYC | ppu::BGP..=ppu::WX | ppu::VBK | ppu::BCPS..=ppu::OPRI => {
+                let irq = self.ppu.write(addr, val);
+                self.request_interrupts(irq);
+            },
+            hdma::HDMA1..=hdma::HDMA5 if self.ppu.cgb => {
+                let blocks = self.hdma.write(addr, val);
+                self.copy_dma_blocks(blocks);
+                // an HBlank transfer started in HBlank, or with the lcd off,
+                // copies its first block straight away
+                if addr == hdma::HDMA5 && self.hdma.hblank_active() && self.ppu.mode() == Mode::HBlank {
+                    self.copy_dma_blocks(1);
+                }
+            },
+            clock::KEY1 if self.ppu.cgb => self.clock.write(val),
+            clock::KEY1 => (),
+            boot_rom::BOOT_OFF => {
+                let handover = val & 1 != 0 && self.boot_rom.take().is_some();
+                let dmg_mode = self.ram[ram_index(boot_rom::KEY0)] & boot_rom::DMG_MODE != 0;
+                if handover && self.model.is_cgb() && dmg_mode {
+                    self.ppu.enter_compat();
+                    self.serial.set_cgb(false);
+                }
+                self.ram[ram_index(addr)] = val;
+            },
+            _ => self.ram[ram_index(addr)] = val,
+        }
+    }
+
+    // interrupts both requested and enabled
+    pub fn pending_interrupts(&self) -> u8 {
+        self.cpu_read(IF) & self.cpu_read(IE) & INTERRUPTS
+    }
+
+    // the vector of the highest priority pending interrupt, clearing its
+    // request
+    pub fn take_interrupt(&mut self) -> Option<u16> {
+        let pending = self.pending_interrupts();
+        if pending == 0 {
+            return None;
+        }
+        let bit = pending.trailing_zeros() as u16;
+        let flags = self.cpu_read(IF);
+        self.put_mem_at(IF, flags & !(1 << bit));
+        Some(INTERRUPT_VECTORS + bit * 8)
     }
 
     // true for byte, false for two bytes
-    pub fn get_immediate_val(&self, single: bool) -> RegBytes {
+    pub fn get_immediate_val(&mut self, single: bool) -> RegBytes {
         if single {
-            let byte = self.get_mem_at(self.cpu.pc + 1);
+            let byte = self.get_mem_at(self.cpu.pc.wrapping_add(1));
             RegBytes::new_single(byte)
         }
         else {
-            let byte1 = self.get_mem_at(self.cpu.pc + 1);
-            let byte2 = self.get_mem_at(self.cpu.pc + 2);
+            let byte1 = self.get_mem_at(self.cpu.pc.wrapping_add(1));
+            let byte2 = self.get_mem_at(self.cpu.pc.wrapping_add(2));
             let bytes = u16::from_le_bytes([byte1, byte2]);
             RegBytes::new_double(bytes)
         }
     }
 
+    // the high byte goes on the stack first, leaving the value little endian
     pub fn push(&mut self, val: u16) {
-        let bytes = val.to_be_bytes();
-        self.put_mem_at(self.cpu.sp - 2, bytes[0]);
-        self.put_mem_at(self.cpu.sp - 1, bytes[1]);
-        self.cpu.sp -= 2;
+        let [low, high] = val.to_le_bytes();
+        self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+        self.put_mem_at(self.cpu.sp, high);
+        self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+        self.put_mem_at(self.cpu.sp, low);
     }
 
     pub fn pop(&mut self) -> u16 {
-        let byte1 = self.get_mem_at(self.cpu.sp);
-        let byte2 = self.get_mem_at(self.cpu.sp + 1);
-        let ret = u16::from_be_bytes([byte2, byte1]);
-        self.cpu.sp += 2;
-        ret
+        let low = self.get_mem_at(self.cpu.sp);
+        let high = self.get_mem_at(self.cpu.sp.wrapping_add(1));
+        self.cpu.sp = self.cpu.sp.wrapping_add(2);
+        u16::from_le_bytes([low, high])
+    }
+}
+
+impl Default for Motherboard {
+    fn default() -> Self {
+        Self::new()
     }
 }
\ No newline at end of file
@@ -0,0 +1,381 @@
+// Follows the notes the APU plays, for exporting them as MIDI (see midi.rs).
+// The motherboard hands it each write to the APU registers and polls it as
+// the hardware advances, and it watches what every channel is doing: a
+// trigger lets the channel start a note once it can be heard, and the
+// channel turning off or its volume reaching 0 ends it. Pitches come from
+// the frequency registers, rounded to the nearest semitone with a pitch bend
+// for the rest, so a sweep or vibrato bends the note until it strays past
+// the bend range and a new note takes over. Volume changes during a note,
+// from the envelope or NR32, become expression changes. The noise channel
+// plays drums, picked by how high its noise is.
+
+use crate::apu::{Apu, Voice, NR14, NR24, NR34, NR44};
+
+pub const CHANNELS: usize = 4;
+const WAVE: usize = 2;
+const NOISE: usize = 3;
+
+// semitones a full pitch bend reaches either way, the General MIDI default
+const BEND_RANGE: f64 = 2.0;
+pub const BEND_CENTER: u16 = 0x2000;
+const BEND_MAX: u16 = 0x3FFF;
+const EXPRESSION_MAX: u8 = 127;
+
+// the noise channel's drums, by the lowest noise frequency that plays them
+const DRUMS: [(f64, u8); 4] = [
+    (16384.0, 42), // closed hi-hat
+    (4096.0, 38),  // snare
+    (1024.0, 45),  // low tom
+    (0.0, 36),     // kick
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NoteEvent {
+    On { note: u8, velocity: u8 },
+    Off { note: u8 },
+    // 14 bits, BEND_CENTER for none
+    Bend(u16),
+    // 0-127, relative to the volume the note started at
+    Expression(u8),
+}
+
+#[derive(Clone, Copy)]
+struct Channel {
+    // triggered and not turned off since, so it may start a note
+    armed: bool,
+    // the sounding note and the volume it started at
+    note: Option<(u8, u8)>,
+    bend: u16,
+    expression: u8,
+}
+
+pub struct NoteTracker {
+    // dots since tracking started
+    time: u64,
+    channels: [Channel; CHANNELS],
+    events: [Vec<(u64, NoteEvent)>; CHANNELS],
+}
+
+impl NoteTracker {
+    pub fn new() -> Self {
+        let channel = Channel {
+            armed: false,
+            note: None,
+            bend: BEND_CENTER,
+            expression: EXPRESSION_MAX,
+        };
+        Self {
+            time: 0,
+            channels: [channel; CHANNELS],
+            events: Default::default(),
+        }
+    }
+
+    pub fn advance(&mut self, dots: u8, apu: &Apu) {
+        self.time += dots as u64;
+        self.poll(apu);
+    }
+
+    // called after the apu has taken the write
+    pub fn observe(&mut self, addr: u16, val: u8, apu: &Apu) {
+        let triggered = match addr {
+            NR14 => Some(0),
+            NR24 => Some(1),
+            NR34 => Some(WAVE),
+            NR44 => Some(NOISE),
+            _ => None,
+        };
+        if let Some(idx) = triggered.filter(|_| val & 0x80 != 0) {
+            self.end(idx);
+            self.channels[idx].armed = true;
+        }
+        self.poll(apu);
+    }
+
+    // dots tracked so far
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    // channel `idx`'s events, each with the dot it happened on
+    pub fn events(&self, idx: usize) -> &[(u64, NoteEvent)] {
+        &self.events[idx]
+    }
+
+    // the note channel `idx` is playing, if any
+    pub fn sounding(&self, idx: usize) -> Option<u8> {
+        self.channels[idx].note.map(|(note, _)| note)
+    }
+
+    fn poll(&mut self, apu: &Apu) {
+        for idx in 0..CHANNELS {
+            self.update(idx, apu.voice(idx));
+        }
+    }
+
+    fn update(&mut self, idx: usize, voice: Voice) {
+        if !voice.on {
+            self.channels[idx].armed = false;
+        }
+        let pitch = if voice.on && voice.volume > 0 { pitch(idx, voice.freq) } else { None };
+        let Channel {
+            armed,
+            note,
+            ..
+        } = self.channels[idx];
+        match (note, pitch) {
+            (Some(_), None) => self.end(idx),
+            (Some((note, _)), Some(pitch)) if idx != NOISE && (pitch - note as f64).abs() > BEND_RANGE => {
+                self.end(idx);
+                self.start(idx, pitch, voice.volume);
+            },
+            (Some((note, start_volume)), Some(pitch)) => {
+                if idx != NOISE {
+                    self.bend(idx, pitch - note as f64);
+                }
+                let expression = voice.volume as u32 * EXPRESSION_MAX as u32 / start_volume as u32;
+                self.express(idx, expression.min(EXPRESSION_MAX as u32) as u8);
+            },
+            (None, Some(pitch)) if armed => self.start(idx, pitch, voice.volume),
+            _ => (),
+        }
+    }
+
+    fn start(&mut self, idx: usize, pitch: f64, volume: u8) {
+        let note = pitch.round() as u8;
+        self.bend(idx, pitch - note as f64);
+        self.express(idx, EXPRESSION_MAX);
+        let velocity = (volume as u16 * 127 / 15).max(1) as u8;
+        self.push(idx, NoteEvent::On {
+            note,
+            velocity,
+        });
+        self.channels[idx].note = Some((note, volume));
+    }
+
+    fn end(&mut self, idx: usize) {
+        if let Some((note, _)) = self.channels[idx].note.take() {
+            self.push(idx, NoteEvent::Off {
+                note,
+            });
+        }
+    }
+
+    // bends the channel `semitones` away from its note
+    fn bend(&mut self, idx: usize, semitones: f64) {
+        let bend = BEND_CENTER as f64 * (1.0 + semitones / BEND_RANGE);
+        let bend = bend.round().clamp(0.0, BEND_MAX as f64) as u16;
+        if bend != self.channels[idx].bend {
+            self.channels[idx].bend = bend;
+            self.push(idx, NoteEvent::Bend(bend));
+        }
+    }
+
+    fn express(&mut self, idx: usize, expression: u8) {
+        if expression != self.channels[idx].expression {
+            self.channels[idx].expression = expression;
+            self.push(idx, NoteEvent::Expression(expression));
+        }
+    }
+
+    fn push(&mut self, idx: usize, event: NoteEvent) {
+        self.events[idx].push((self.time, event));
+    }
+}
+
+impl Default for NoteTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the MIDI note, with a fraction, channel `idx` plays at `freq`: the 11 bit
+// frequency, or NR43 for the noise channel's drum. None if it's out of MIDI's
+// range or the noise is stopped
+fn pitch(idx: usize, freq: u16) -> Option<f64> {
+    if idx == NOISE {
+        return drum(freq as u8).map(|note| note as f64);
+    }
+    let rate = if idx == WAVE { 65536.0 } else { 131072.0 };
+    let hz = rate / (2048 - freq) as f64;
+    let pitch = 69.0 + 12.0 * (hz / 440.0).log2();
+    (0.0..=127.0).contains(&pitch).then_some(pitch)
+}
+
+fn drum(poly: u8) -> Option<u8> {
+    let shift = poly >> 4;
+    // shifts 14 and 15 stop the noise
+    if shift >= 14 {
+        return None;
+    }
+    let divisor = match poly & 0x07 {
+        0 => 0.5,
+        r => r as f64,
+    };
+    let hz = 524288.0 / divisor / (2u32 << shift) as f64;
+    DRUMS.iter().find(|(lowest, _)| hz >= *lowest).map(|(_, note)| *note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apu::{NR12, NR13, NR22, NR23, NR42, NR43, NR52};
+
+    fn setup() -> (Apu, NoteTracker) {
+        let mut apu = Apu::new();
+        apu.write(NR52, 0x80);
+        (apu, NoteTracker::new())
+    }
+
+    fn write(apu: &mut Apu, tracker: &mut NoteTracker, addr: u16, val: u8) {
+        apu.write(addr, val);
+        tracker.observe(addr, val, apu);
+    }
+
+    // channel `idx`'s events without their times
+    fn events(tracker: &NoteTracker, idx: usize) -> Vec<NoteEvent> {
+        tracker.events(idx).iter().map(|(_, event)| *event).collect()
+    }
+
+    fn notes(tracker: &NoteTracker, idx: usize) -> Vec<NoteEvent> {
+        let notes = events(tracker, idx).into_iter();
+        notes.filter(|event| matches!(event, NoteEvent::On { .. } | NoteEvent::Off { .. })).collect()
+    }
+
+    // A4 is 131072 / 298 Hz, a hair under 440
+    const A4: u16 = 1750;
+
+    #[test]
+    fn note_on_and_off() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR12, 0xF0);
+        write(&mut apu, &mut tracker, NR13, A4 as u8);
+        for _ in 0..10 {
+            tracker.advance(4, &apu);
+        }
+        // nothing sounds until the trigger
+        assert!(events(&tracker, 0).is_empty());
+        write(&mut apu, &mut tracker, NR14, 0x80 | (A4 >> 8) as u8);
+        assert_eq!(tracker.sounding(0), Some(69));
+        tracker.advance(100, &apu);
+        write(&mut apu, &mut tracker, NR12, 0x00);
+        assert_eq!(tracker.sounding(0), None);
+        let events = tracker.events(0);
+        assert_eq!(events[0], (40, NoteEvent::Bend(8166)));
+        assert_eq!(events[1], (40, NoteEvent::On {
+            note: 69,
+            velocity: 127,
+        }));
+        assert_eq!(events[2], (140, NoteEvent::Off {
+            note: 69,
+        }));
+    }
+
+    #[test]
+    fn velocity() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR22, 0x70);
+        write(&mut apu, &mut tracker, NR23, A4 as u8);
+        write(&mut apu, &mut tracker, NR24, 0x80 | (A4 >> 8) as u8);
+        assert_eq!(notes(&tracker, 1), vec![NoteEvent::On {
+            note: 69,
+            velocity: 59,
+        }]);
+    }
+
+    #[test]
+    fn retrigger() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR12, 0xF0);
+        write(&mut apu, &mut tracker, NR13, A4 as u8);
+        write(&mut apu, &mut tracker, NR14, 0x80 | (A4 >> 8) as u8);
+        write(&mut apu, &mut tracker, NR14, 0x80 | (A4 >> 8) as u8);
+        assert_eq!(notes(&tracker, 0), vec![
+            NoteEvent::On {
+                note: 69,
+                velocity: 127,
+            },
+            NoteEvent::Off {
+                note: 69,
+            },
+            NoteEvent::On {
+                note: 69,
+                velocity: 127,
+            },
+        ]);
+    }
+
+    #[test]
+    fn pitch_bend() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR12, 0xF0);
+        write(&mut apu, &mut tracker, NR13, A4 as u8);
+        write(&mut apu, &mut tracker, NR14, 0x80 | (A4 >> 8) as u8);
+        // just over half a semitone up bends the same note
+        write(&mut apu, &mut tracker, NR13, (A4 + 10) as u8);
+        assert_eq!(tracker.sounding(0), Some(69));
+        let NoteEvent::Bend(bend) = *events(&tracker, 0).last().unwrap() else {
+            panic!("no bend");
+        };
+        assert!(bend > BEND_CENTER + BEND_CENTER / 4 && bend < BEND_CENTER + BEND_CENTER / 3);
+        // past the bend range a new note takes over
+        write(&mut apu, &mut tracker, NR13, (A4 + 41) as u8);
+        assert_eq!(tracker.sounding(0), Some(72));
+        assert_eq!(notes(&tracker, 0)[1..], [
+            NoteEvent::Off {
+                note: 69,
+            },
+            NoteEvent::On {
+                note: 72,
+                velocity: 127,
+            },
+        ]);
+    }
+
+    #[test]
+    fn expression() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR12, 0xF1);
+        write(&mut apu, &mut tracker, NR13, A4 as u8);
+        write(&mut apu, &mut tracker, NR14, 0x80 | (A4 >> 8) as u8);
+        // the envelope steps once every 8 frame sequencer steps
+        for _ in 0..8 {
+            apu.clock_div(0x1000, 0, false);
+        }
+        tracker.advance(4, &apu);
+        assert_eq!(*events(&tracker, 0).last().unwrap(), NoteEvent::Expression(118));
+        assert_eq!(tracker.sounding(0), Some(69));
+    }
+
+    #[test]
+    fn noise_plays_drums() {
+        let (mut apu, mut tracker) = setup();
+        write(&mut apu, &mut tracker, NR42, 0xF0);
+        for (nr43, drum) in [(0x00, 42), (0x60, 38), (0x80, 45), (0xA0, 36)] {
+            write(&mut apu, &mut tracker, NR43, nr43);
+            write(&mut apu, &mut tracker, NR44, 0x80);
+            assert_eq!(tracker.sounding(NOISE), Some(drum));
+        }
+        // drums don't bend
+        write(&mut apu, &mut tracker, NR43, 0x00);
+        assert_eq!(tracker.sounding(NOISE), Some(36));
+        assert!(!events(&tracker, NOISE).iter().any(|event| matches!(event, NoteEvent::Bend(_))));
+        // stopped noise ends the drum
+        write(&mut apu, &mut tracker, NR43, 0xE0);
+        assert_eq!(tracker.sounding(NOISE), None);
+        for idx in 0..NOISE {
+            assert!(events(&tracker, idx).is_empty());
+        }
+    }
+
+    #[test]
+    fn pitches() {
+        assert_eq!(pitch(0, 1750).map(f64::round), Some(69.0));
+        // the wave channel plays an octave lower
+        assert_eq!(pitch(WAVE, 1750).map(f64::round), Some(57.0));
+        assert_eq!(pitch(0, 0).map(f64::round), Some(36.0));
+        // too high for MIDI
+        assert_eq!(pitch(0, 2047), None);
+        assert_eq!(pitch(NOISE, 0xF0), None);
+    }
+}
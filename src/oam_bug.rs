@@ -0,0 +1,144 @@
+// The DMG OAM corruption bug. While the ppu scans OAM in mode 2, a 16 bit
+// register holding an address in 0xFE00-0xFEFF going through the cpu's
+// increment/decrement unit garbles the OAM row the ppu is reading. OAM is
+// seen as 20 rows of four 16 bit words, and each kind of access mixes the
+// current row with the ones before it in its own pattern. The CGB fixed it.
+
+pub const START: u16 = 0xFE00;
+pub const END: u16 = 0xFEFF;
+
+const ROW_SIZE: usize = 8;
+const ROWS: usize = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corruption {
+    // inc rr, dec rr, push and writes through hl+/hl-
+    Write,
+    Read,
+    // reads through hl+/hl- and pop
+    ReadIncrease,
+}
+
+fn word(oam: &[u8], row: usize, idx: usize) -> u16 {
+    let at = row * ROW_SIZE + idx * 2;
+    u16::from_le_bytes([oam[at], oam[at + 1]])
+}
+
+fn set_word(oam: &mut [u8], row: usize, idx: usize, val: u16) {
+    let at = row * ROW_SIZE + idx * 2;
+    oam[at..at + 2].copy_from_slice(&val.to_le_bytes());
+}
+
+// copies the last three words of `from` over those of `to`
+fn copy_tail(oam: &mut [u8], from: usize, to: usize) {
+    oam.copy_within(from * ROW_SIZE + 2..(from + 1) * ROW_SIZE, to * ROW_SIZE + 2);
+}
+
+// garbles `row`, the row the ppu is reading, for an access of kind `kind`.
+// The first row is never hit
+pub fn corrupt(oam: &mut [u8], row: usize, kind: Corruption) {
+    if row == 0 || row >= ROWS {
+        return;
+    }
+    match kind {
+        Corruption::Write => {
+            let (a, b, c) = (word(oam, row, 0), word(oam, row - 1, 0), word(oam, row - 1, 2));
+            set_word(oam, row, 0, ((a ^ c) & (b ^ c)) ^ c);
+            copy_tail(oam, row - 1, row);
+        },
+        Corruption::Read => {
+            let (a, b, c) = (word(oam, row, 0), word(oam, row - 1, 0), word(oam, row - 1, 2));
+            set_word(oam, row, 0, b | (a & c));
+            copy_tail(oam, row - 1, row);
+        },
+        Corruption::ReadIncrease => {
+            // rows near either end only get the plain read pattern
+            if (4..ROWS - 1).contains(&row) {
+                let a = word(oam, row - 2, 0);
+                let b = word(oam, row - 1, 0);
+                let c = word(oam, row, 0);
+                let d = word(oam, row - 1, 2);
+                set_word(oam, row - 1, 0, (b & (a | c | d)) | (a & c & d));
+                let prev = (row - 1) * ROW_SIZE;
+                oam.copy_within(prev..prev + ROW_SIZE, (row - 2) * ROW_SIZE);
+                oam.copy_within(prev..prev + ROW_SIZE, row * ROW_SIZE);
+            }
+            corrupt(oam, row, Corruption::Read);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // each word holds its row in the high byte and its index in the low
+    fn oam() -> Vec<u8> {
+        let mut oam = vec![0; ROWS * ROW_SIZE];
+        for row in 0..ROWS {
+            for idx in 0..4 {
+                set_word(&mut oam, row, idx, (row as u16) << 8 | idx as u16 | 0x10);
+            }
+        }
+        oam
+    }
+
+    fn row(oam: &[u8], row: usize) -> [u16; 4] {
+        [0, 1, 2, 3].map(|idx| word(oam, row, idx))
+    }
+
+    #[test]
+    fn write() {
+        let mut oam = oam();
+        corrupt(&mut oam, 5, Corruption::Write);
+        // ((a ^ c) & (b ^ c)) ^ c, then the rest of the row before
+        let (a, b, c) = (0x0510, 0x0410, 0x0412);
+        assert_eq!(row(&oam, 5), [((a ^ c) & (b ^ c)) ^ c, 0x0411, 0x0412, 0x0413]);
+        assert_eq!(row(&oam, 4), [0x0410, 0x0411, 0x0412, 0x0413]);
+        assert_eq!(row(&oam, 6), [0x0610, 0x0611, 0x0612, 0x0613]);
+    }
+
+    #[test]
+    fn read() {
+        let mut oam = oam();
+        corrupt(&mut oam, 5, Corruption::Read);
+        assert_eq!(row(&oam, 5), [0x0410 | (0x0510 & 0x0412), 0x0411, 0x0412, 0x0413]);
+    }
+
+    #[test]
+    fn read_increase() {
+        let mut oam = oam();
+        corrupt(&mut oam, 8, Corruption::ReadIncrease);
+        let (a, b, c, d) = (0x0610u16, 0x0710u16, 0x0810u16, 0x0712u16);
+        let mixed = (b & (a | c | d)) | (a & c & d);
+        // the row before is mixed and copied over its neighbours, then read
+        // corrupts the current row from it
+        assert_eq!(row(&oam, 7), [mixed, 0x0711, 0x0712, 0x0713]);
+        assert_eq!(row(&oam, 6), row(&oam, 7));
+        assert_eq!(row(&oam, 8), [mixed | (mixed & 0x0712), 0x0711, 0x0712, 0x0713]);
+        assert_eq!(row(&oam, 5)[0], 0x0510);
+    }
+
+    #[test]
+    fn read_increase_near_the_ends() {
+        let mut plain = oam();
+        corrupt(&mut plain, 2, Corruption::Read);
+        let mut oam = oam();
+        corrupt(&mut oam, 2, Corruption::ReadIncrease);
+        assert_eq!(oam, plain);
+        let mut plain = oam.clone();
+        corrupt(&mut plain, ROWS - 1, Corruption::Read);
+        corrupt(&mut oam, ROWS - 1, Corruption::ReadIncrease);
+        assert_eq!(oam, plain);
+    }
+
+    #[test]
+    fn first_row_is_safe() {
+        let mut oam = oam();
+        for kind in [Corruption::Write, Corruption::Read, Corruption::ReadIncrease] {
+            corrupt(&mut oam, 0, kind);
+            corrupt(&mut oam, ROWS, kind);
+        }
+        assert_eq!(oam, self::oam());
+    }
+}
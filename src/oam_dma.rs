@@ -0,0 +1,185 @@
+// OAM DMA (0xFF46). One m-cycle after the write that starts it, the transfer
+// copies a byte per m-cycle from XX00-XX9F into OAM for 160 m-cycles. While
+// it runs OAM is locked, and the cpu reading anything on the bus the DMA is
+// reading from gets the byte being transferred instead. Writing 0xFF46 again
+// starts a new transfer after the same delay, the old one running until then.
+
+pub const DMA: u16 = 0xFF46;
+
+pub const OAM_BYTES: u8 = 0xA0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bus {
+    // cartridge rom and ram, plus work ram on DMG
+    External,
+    Vram,
+    // work ram has its own bus on CGB
+    Wram,
+}
+
+// the bus `addr` is on, or None for OAM, io and HRAM inside the cpu
+pub fn bus(addr: u16, cgb: bool) -> Option<Bus> {
+    match addr {
+        0x8000..=0x9FFF => Some(Bus::Vram),
+        0xC000..=0xFDFF if cgb => Some(Bus::Wram),
+        0xFE00..=0xFFFF => None,
+        _ => Some(Bus::External),
+    }
+}
+
+pub struct OamDma {
+    // last value written to 0xFF46
+    reg: u8,
+    // source page of a write waiting out its start delay, and the m-cycles
+    // left of it
+    pending: Option<(u8, u8)>,
+    source: u16,
+    index: u8,
+    transferring: bool,
+    // the byte on the bus this m-cycle
+    last: u8,
+}
+
+impl OamDma {
+    pub fn new() -> Self {
+        Self {
+            reg: 0xFF,
+            pending: None,
+            source: 0,
+            index: 0,
+            transferring: false,
+            last: 0xFF,
+        }
+    }
+
+    pub fn read(&self) -> u8 {
+        self.reg
+    }
+
+    // sets the register without starting a transfer
+    pub fn set_reg(&mut self, val: u8) {
+        self.reg = val;
+    }
+
+    pub fn write(&mut self, val: u8) {
+        self.reg = val;
+        // the m-cycle of the write, then one to set the transfer up
+        self.pending = Some((val, 2));
+    }
+
+    pub fn transferring(&self) -> bool {
+        self.transferring
+    }
+
+    pub fn last(&self) -> u8 {
+        self.last
+    }
+
+    pub fn set_last(&mut self, val: u8) {
+        self.last = val;
+    }
+
+    // the bus the transfer is reading from
+    pub fn source_bus(&self, cgb: bool) -> Option<Bus> {
+        bus(self.source, cgb)
+    }
+
+    // advances one m-cycle, returning the source address and OAM offset of
+    // the byte to copy in it
+    pub fn tick_m(&mut self) -> Option<(u16, u8)> {
+        let copy = if self.transferring {
+            let copy = (self.source + self.index as u16, self.index);
+            self.index += 1;
+            if self.index == OAM_BYTES {
+                self.transferring = false;
+            }
+            Some(copy)
+        }
+        else {
+            None
+        };
+        match self.pending {
+            Some((page, 1)) => {
+                // 0xE000 up reads work ram like the echo area does
+                let page = if page >= 0xE0 { page - 0x20 } else { page };
+                self.source = (page as u16) << 8;
+                self.index = 0;
+                self.transferring = true;
+                self.pending = None;
+            },
+            Some((page, delay)) => self.pending = Some((page, delay - 1)),
+            None => (),
+        }
+        copy
+    }
+}
+
+impl Default for OamDma {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buses() {
+        assert_eq!(bus(0x0000, false), Some(Bus::External));
+        assert_eq!(bus(0xA000, true), Some(Bus::External));
+        assert_eq!(bus(0x9FFF, false), Some(Bus::Vram));
+        assert_eq!(bus(0xC000, false), Some(Bus::External));
+        assert_eq!(bus(0xC000, true), Some(Bus::Wram));
+        assert_eq!(bus(0xFE00, true), None);
+        assert_eq!(bus(0xFF80, false), None);
+    }
+
+    #[test]
+    fn starts_after_a_cycle() {
+        let mut dma = OamDma::new();
+        dma.write(0xC1);
+        assert_eq!(dma.read(), 0xC1);
+        assert_eq!(dma.tick_m(), None);
+        assert!(!dma.transferring());
+        assert_eq!(dma.tick_m(), None);
+        assert!(dma.transferring());
+        assert_eq!(dma.tick_m(), Some((0xC100, 0)));
+        assert_eq!(dma.tick_m(), Some((0xC101, 1)));
+    }
+
+    #[test]
+    fn runs_160_cycles() {
+        let mut dma = OamDma::new();
+        dma.write(0x80);
+        dma.tick_m();
+        dma.tick_m();
+        let copies: Vec<_> = std::iter::from_fn(|| dma.tick_m()).collect();
+        assert_eq!(copies.len(), OAM_BYTES as usize);
+        assert_eq!(copies.last(), Some(&(0x809F, 0x9F)));
+        assert!(!dma.transferring());
+    }
+
+    #[test]
+    fn restart_keeps_the_old_transfer_going_until_it_starts() {
+        let mut dma = OamDma::new();
+        dma.write(0xC0);
+        for _ in 0..12 {
+            dma.tick_m();
+        }
+        dma.write(0xD0);
+        assert_eq!(dma.tick_m(), Some((0xC00A, 10)));
+        assert_eq!(dma.tick_m(), Some((0xC00B, 11)));
+        assert_eq!(dma.tick_m(), Some((0xD000, 0)));
+    }
+
+    #[test]
+    fn echo_pages_read_work_ram() {
+        let mut dma = OamDma::new();
+        dma.write(0xE3);
+        dma.tick_m();
+        dma.tick_m();
+        assert_eq!(dma.tick_m(), Some((0xC300, 0)));
+        assert_eq!(dma.source_bus(true), Some(Bus::Wram));
+    }
+}
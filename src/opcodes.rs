@@ -0,0 +1,235 @@
+// Decoding table parsed from raw_commands/opcodes.txt, the same listing
+// op_cmds.rs is generated from. Keys match the op_cmds keys: the opcode byte,
+// or 0x100 + the second byte for CB prefixed ops.
+
+use std::collections::HashMap;
+
+const OPCODES_TXT: &str = include_str!("raw_commands/opcodes.txt");
+
+pub const CB_PREFIX: u8 = 0xCB;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BranchKind {
+    None,
+    Jump,
+    CondJump,
+    JumpIndirect,
+    Call,
+    CondCall,
+    Ret,
+    CondRet,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpInfo {
+    pub key: u16,
+    pub cmd: String,
+    pub args: Vec<String>,
+    pub cycles: u8,
+    pub length: u8,
+}
+
+impl OpInfo {
+    fn new(key: u16, cmd: &str, args: Vec<String>, cycles: u8) -> Self {
+        let mut info = Self {
+            key,
+            cmd: cmd.to_string(),
+            args,
+            cycles,
+            length: 0,
+        };
+        info.length = info.opcode_bytes().len() as u8 + info.imm_size();
+        info
+    }
+
+    pub fn opcode_bytes(&self) -> Vec<u8> {
+        if self.key > 0xFF {
+            vec![CB_PREFIX, self.key as u8]
+        }
+        else if self.cmd == "STOP" {
+            // STOP is always followed by a padding byte
+            vec![self.key as u8, 0]
+        }
+        else {
+            vec![self.key as u8]
+        }
+    }
+
+    // number of immediate bytes following the opcode
+    pub fn imm_size(&self) -> u8 {
+        self.args.iter().map(|arg| arg_imm_size(arg)).max().unwrap_or(0)
+    }
+
+    pub fn branch_kind(&self) -> BranchKind {
+        match self.cmd.as_str() {
+            "JP" if self.args[0] == "(HL)" => BranchKind::JumpIndirect,
+            "JP" | "JR" => BranchKind::Jump,
+            "JP_FLAG" | "JR_FLAG" => BranchKind::CondJump,
+            "CALL" | "RST" => BranchKind::Call,
+            "CALL_FLAG" => BranchKind::CondCall,
+            "RET" | "RETI" => BranchKind::Ret,
+            "RET_FLAG" => BranchKind::CondRet,
+            _ => BranchKind::None,
+        }
+    }
+
+    // static branch target of the instruction at `pc` with immediate `imm`
+    pub fn target(&self, pc: u16, imm: u16) -> Option<u16> {
+        match self.cmd.as_str() {
+            "JR" | "JR_FLAG" => {
+                let offset = imm as u8 as i8;
+                Some(pc.wrapping_add(self.length as u16).wrapping_add(offset as u16))
+            },
+            "JP" | "JP_FLAG" | "CALL" | "CALL_FLAG" if self.imm_size() == 2 => Some(imm),
+            "RST" => rst_vector(&self.args[0]),
+            _ => None,
+        }
+    }
+}
+
+fn arg_imm_size(arg: &str) -> u8 {
+    let inner = arg.trim_start_matches('(').trim_end_matches(')');
+    match inner.rsplit('+').next() {
+        Some("n") => 1,
+        Some("nn") => 2,
+        _ => 0,
+    }
+}
+
+fn rst_vector(arg: &str) -> Option<u16> {
+    u16::from_str_radix(arg.trim_end_matches('H'), 16).ok()
+}
+
+// mirrors the fixups cmd_gen.py applies before generating op_cmds.rs
+fn fix_args(section: &str, subsection: &str, args: &mut [String]) {
+    if section == "3.3.1." && subsection == "5." {
+        let arg = &args[1];
+        args[1] = if let Some(stripped) = arg.strip_prefix('(') {
+            format!("($FF00+{}", stripped)
+        }
+        else {
+            format!("$FF00{}", arg)
+        };
+    }
+}
+
+pub struct OpTable {
+    ops: HashMap<u16, OpInfo>,
+}
+
+impl OpTable {
+    pub fn new() -> Self {
+        let mut raw: Vec<(u16, String, String, String, Vec<String>, u8)> = Vec::new();
+        let mut index: HashMap<u16, usize> = HashMap::new();
+        let mut section = String::new();
+        let mut subsection = String::new();
+
+        for line in OPCODES_TXT.lines() {
+            let line = line.trim();
+            let fields: Vec<&str> = line.split('|').collect();
+            if fields.len() != 4 {
+                if line.matches('.').count() > 1 {
+                    section = line.to_string();
+                }
+                else if !line.is_empty() {
+                    subsection = line.to_string();
+                }
+                continue;
+            }
+
+            let cycles = match fields[3].parse::<u8>() {
+                Ok(cycles) => cycles,
+                Err(_) => continue,
+            };
+            let opcode: Vec<&str> = fields[2].split(',').collect();
+            let key = if opcode.len() > 1 && opcode[0] == "CB" {
+                0x100 + u16::from_str_radix(opcode[1], 16).unwrap()
+            }
+            else {
+                u16::from_str_radix(opcode[0], 16).unwrap()
+            };
+            let args = fields[1]
+                .split(',')
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| arg.to_string())
+                .collect();
+
+            // later listings of the same opcode win, as in the generator
+            let entry = (key, fields[0].to_string(), section.clone(), subsection.clone(), args, cycles);
+            match index.get(&key) {
+                Some(&idx) => raw[idx] = entry,
+                None => {
+                    index.insert(key, raw.len());
+                    raw.push(entry);
+                },
+            }
+        }
+
+        let mut ops = HashMap::new();
+        let mut bit_groups: HashMap<String, Vec<(u16, Vec<String>, u8)>> = HashMap::new();
+        for (key, cmd, section, subsection, mut args, cycles) in raw {
+            fix_args(&section, &subsection, &mut args);
+            if args.iter().any(|arg| arg == "b") {
+                bit_groups.entry(cmd).or_default().push((key, args, cycles));
+                continue;
+            }
+            ops.insert(key, OpInfo::new(key, &cmd, args, cycles));
+        }
+
+        for (cmd, group) in bit_groups {
+            let stride = group.len() as u16;
+            for (key, args, cycles) in &group {
+                for i in 0..8 {
+                    let bit_args = args.iter().map(|arg| arg.replace('b', &format!("b{}", i))).collect();
+                    let bit_key = key + i * stride;
+                    ops.insert(bit_key, OpInfo::new(bit_key, &cmd, bit_args, *cycles));
+                }
+            }
+        }
+
+        Self {
+            ops,
+        }
+    }
+
+    pub fn get(&self, key: u16) -> Option<&OpInfo> {
+        self.ops.get(&key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OpInfo> {
+        self.ops.values()
+    }
+
+    // op_cmds key of the instruction starting with `first`, `second`
+    pub fn key_of(first: u8, second: u8) -> u16 {
+        if first == CB_PREFIX {
+            0x100 + second as u16
+        }
+        else {
+            first as u16
+        }
+    }
+
+    // decodes the instruction at `pc`, returning its info and immediate value
+    pub fn decode<F>(&self, pc: u16, read: F) -> Option<(&OpInfo, u16)>
+    where
+        F: Fn(u16) -> Option<u8>,
+    {
+        let first = read(pc)?;
+        let second = if first == CB_PREFIX { read(pc.wrapping_add(1))? } else { 0 };
+        let info = self.get(Self::key_of(first, second))?;
+        let imm_start = pc.wrapping_add(info.opcode_bytes().len() as u16);
+        let imm = match info.imm_size() {
+            1 => read(imm_start)? as u16,
+            2 => u16::from_le_bytes([read(imm_start)?, read(imm_start.wrapping_add(1))?]),
+            _ => 0,
+        };
+        Some((info, imm))
+    }
+}
+
+impl Default for OpTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,685 @@
+// LCD controller timing. Every line is 456 dots: OAM scan (mode 2) for 80,
+// pixel transfer (mode 3) and HBlank (mode 0) for the rest, and lines
+// 144-153 are VBlank (mode 1). The STAT interrupt fires on the rising edge of
+// one internal line ORed from all the enabled sources, so a source becoming
+// true while another already holds the line high raises nothing.
+//
+// In CGB mode VRAM has two banks selected by VBK, the second holding the
+// background map attributes, and colors come from 64 bytes each of
+// background and object palette RAM as 15 bit RGB.
+
+use crate::fifo::Fifo;
+use crate::motherboard::Interrupt;
+use crate::oam_bug::{self, Corruption};
+use crate::render::{self, HEIGHT, SHADES_RGBA, VRAM_BANK_SIZE, WIDTH};
+
+pub const LCDC: u16 = 0xFF40;
+pub const STAT: u16 = 0xFF41;
+pub const SCY: u16 = 0xFF42;
+pub const SCX: u16 = 0xFF43;
+pub const LY: u16 = 0xFF44;
+pub const LYC: u16 = 0xFF45;
+pub const BGP: u16 = 0xFF47;
+pub const OBP0: u16 = 0xFF48;
+pub const OBP1: u16 = 0xFF49;
+pub const WY: u16 = 0xFF4A;
+pub const WX: u16 = 0xFF4B;
+pub const VBK: u16 = 0xFF4F;
+pub const BCPS: u16 = 0xFF68;
+pub const BCPD: u16 = 0xFF69;
+pub const OCPS: u16 = 0xFF6A;
+pub const OCPD: u16 = 0xFF6B;
+pub const OPRI: u16 = 0xFF6C;
+
+pub const VRAM_START: u16 = 0x8000;
+pub const VRAM_END: u16 = 0x9FFF;
+pub const OAM_START: u16 = 0xFE00;
+pub const OAM_END: u16 = 0xFE9F;
+
+pub const DOTS_PER_LINE: u16 = 456;
+pub const LINES: u8 = 154;
+pub const VBLANK_LINE: u8 = 144;
+pub const FRAME_DOTS: u64 = DOTS_PER_LINE as u64 * LINES as u64;
+
+const OAM_SCAN_DOTS: u16 = 80;
+const TRANSFER_DOTS: u16 = 172;
+
+const LCDC_ENABLE: u8 = 0x80;
+
+const STAT_UNUSED: u8 = 0x80;
+const STAT_LYC_EQ: u8 = 0x04;
+const STAT_HBLANK_INT: u8 = 0x08;
+const STAT_VBLANK_INT: u8 = 0x10;
+const STAT_OAM_INT: u8 = 0x20;
+const STAT_LYC_INT: u8 = 0x40;
+const STAT_WRITABLE: u8 = 0x78;
+
+const PALETTE_RAM_SIZE: usize = 64;
+const PALETTE_AUTO_INC: u8 = 0x80;
+const PALETTE_INDEX: u8 = 0x3F;
+
+// how pixel transfer is drawn: whole lines at the end of mode 3 with a
+// fixed length, or dot by dot through the pixel fifos
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Renderer {
+    Scanline,
+    Fifo,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    HBlank = 0,
+    VBlank = 1,
+    OamScan = 2,
+    Transfer = 3,
+}
+
+pub struct Ppu {
+    pub lcdc: u8,
+    // only the interrupt enable bits, the rest is computed on read
+    stat: u8,
+    pub scy: u8,
+    pub scx: u8,
+    pub lyc: u8,
+    pub bgp: u8,
+    pub obp0: u8,
+    pub obp1: u8,
+    pub wy: u8,
+    pub wx: u8,
+    // CGB mode, as opposed to DMG or CGB running a DMG cartridge
+    pub cgb: bool,
+    // CGB running a DMG cartridge: drawn as on DMG, with the shades colored
+    // by background palette 0 and object palettes 0 and 1
+    pub compat: bool,
+    pub opri: u8,
+    vbk: u8,
+    bcps: u8,
+    ocps: u8,
+    bg_palettes: [u8; PALETTE_RAM_SIZE],
+    obj_palettes: [u8; PALETTE_RAM_SIZE],
+
+    line: u8,
+    dot: u16,
+    // the first line after the lcd is switched on skips OAM scan
+    first_line: bool,
+    stat_line: bool,
+    frame_ready: bool,
+    // set on entering HBlank on a visible line, for HBlank DMA
+    hblank_started: bool,
+
+    vram: Vec<u8>,
+    oam: Vec<u8>,
+    // WIDTH x HEIGHT shades 0-3, or 15 bit colors in CGB mode
+    framebuffer: Vec<u8>,
+    colors: Vec<u16>,
+    // lines of the window drawn so far this frame
+    window_line: u8,
+    pub renderer: Renderer,
+    fifo: Fifo,
+}
+
+impl Ppu {
+    pub fn new() -> Self {
+        Self {
+            lcdc: 0,
+            stat: 0,
+            scy: 0,
+            scx: 0,
+            lyc: 0,
+            bgp: 0,
+            obp0: 0,
+            obp1: 0,
+            wy: 0,
+            wx: 0,
+            cgb: false,
+            compat: false,
+            opri: 0,
+            vbk: 0,
+            bcps: 0,
+            ocps: 0,
+            bg_palettes: [0; PALETTE_RAM_SIZE],
+            obj_palettes: [0; PALETTE_RAM_SIZE],
+            line: 0,
+            dot: 0,
+            first_line: false,
+            stat_line: false,
+            frame_ready: false,
+            hblank_started: false,
+            vram: vec![0; VRAM_BANK_SIZE * 2],
+            oam: vec![0; (OAM_END - OAM_START) as usize + 1],
+            framebuffer: vec![0; WIDTH * HEIGHT],
+            colors: vec![0; WIDTH * HEIGHT],
+            window_line: 0,
+            renderer: Renderer::Scanline,
+            fifo: Fifo::new(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.lcdc & LCDC_ENABLE != 0
+    }
+
+    // moves to `line`, `dot`, for starting without a boot rom
+    pub fn set_position(&mut self, line: u8, dot: u16) {
+        self.line = line;
+        self.dot = dot;
+        self.first_line = false;
+        self.stat_line = self.stat_sources();
+    }
+
+    pub fn line(&self) -> u8 {
+        self.line
+    }
+
+    pub fn dot(&self) -> u16 {
+        self.dot
+    }
+
+    // true once after each frame is finished, on entering VBlank
+    pub fn frame_ready(&mut self) -> bool {
+        let ready = self.frame_ready;
+        self.frame_ready = false;
+        ready
+    }
+
+    // true once after pixel transfer ends on each visible line
+    pub fn take_hblank(&mut self) -> bool {
+        let started = self.hblank_started;
+        self.hblank_started = false;
+        started
+    }
+
+    pub fn vram(&self) -> &[u8] {
+        &self.vram
+    }
+
+    pub fn oam(&self) -> &[u8] {
+        &self.oam
+    }
+
+    // both banks, bank 1 following bank 0
+    fn vram_index(&self, addr: u16) -> usize {
+        let bank = if self.cgb { (self.vbk & 1) as usize } else { 0 };
+        bank * VRAM_BANK_SIZE + (addr - VRAM_START) as usize
+    }
+
+    pub fn read_vram(&self, addr: u16) -> u8 {
+        self.vram[self.vram_index(addr)]
+    }
+
+    pub fn write_vram(&mut self, addr: u16, val: u8) {
+        let idx = self.vram_index(addr);
+        self.vram[idx] = val;
+    }
+
+    pub fn read_oam(&self, addr: u16) -> u8 {
+        self.oam[(addr - OAM_START) as usize]
+    }
+
+    pub fn write_oam(&mut self, addr: u16, val: u8) {
+        self.oam[(addr - OAM_START) as usize] = val;
+    }
+
+    // runs the OAM corruption bug on the row mode 2 is reading, four dots
+    // to a row
+    pub fn corrupt_oam(&mut self, kind: Corruption) {
+        if self.mode() == Mode::OamScan {
+            oam_bug::corrupt(&mut self.oam, (self.dot / 4) as usize, kind);
+        }
+    }
+
+    // 15 bit color `color` of palette `palette` in palette RAM
+    fn palette_color(ram: &[u8], palette: u8, color: u8) -> u16 {
+        let idx = palette as usize * 8 + color as usize * 2;
+        u16::from_le_bytes([ram[idx], ram[idx + 1]]) & 0x7FFF
+    }
+
+    pub fn bg_color(&self, palette: u8, color: u8) -> u16 {
+        Self::palette_color(&self.bg_palettes, palette, color)
+    }
+
+    pub fn obj_color(&self, palette: u8, color: u8) -> u16 {
+        Self::palette_color(&self.obj_palettes, palette, color)
+    }
+
+    // fills object or background palette `palette`, as the cgb boot rom does
+    // for dmg cartridges
+    pub fn set_palette(&mut self, obj: bool, palette: u8, colors: [u16; 4]) {
+        let ram = if obj { &mut self.obj_palettes } else { &mut self.bg_palettes };
+        for (idx, color) in colors.iter().enumerate() {
+            let at = palette as usize * 8 + idx * 2;
+            ram[at..at + 2].copy_from_slice(&color.to_le_bytes());
+        }
+    }
+
+    // drops from CGB mode to running a DMG cartridge on CGB
+    pub fn enter_compat(&mut self) {
+        self.cgb = false;
+        self.compat = true;
+    }
+
+    // whether frames come out as colors rather than shades
+    pub fn colored(&self) -> bool {
+        self.cgb || self.compat
+    }
+
+    // the last finished frame as shades 0-3, one byte per pixel. Only drawn
+    // on DMG
+    pub fn framebuffer(&self) -> &[u8] {
+        &self.framebuffer
+    }
+
+    // the last finished frame as 15 bit colors. Only drawn on CGB, in either
+    // mode
+    pub fn rgb555(&self) -> &[u16] {
+        &self.colors
+    }
+
+    // the last finished frame as 8 bit RGBA
+    pub fn rgba(&self) -> Vec<u8> {
+        if self.colored() {
+            self.colors.iter().flat_map(|color| rgb555_to_rgba(*color)).collect()
+        }
+        else {
+            self.framebuffer.iter().flat_map(|shade| SHADES_RGBA[*shade as usize]).collect()
+        }
+    }
+
+    fn store_line(&mut self, out: &[u16]) {
+        let start = self.line as usize * WIDTH;
+        if self.colored() {
+            self.colors[start..start + WIDTH].copy_from_slice(out);
+        }
+        else {
+            for (shade, pixel) in self.framebuffer[start..start + WIDTH].iter_mut().zip(out) {
+                *shade = *pixel as u8;
+            }
+        }
+    }
+
+    fn render_line(&mut self) {
+        let mut out = [0; WIDTH];
+        let mut window_line = self.window_line;
+        render::render_line(self, self.line, &mut window_line, &mut out);
+        self.window_line = window_line;
+        self.store_line(&out);
+    }
+
+    // one dot of fifo rendering, run through mode 3
+    fn step_fifo(&mut self) {
+        let mut fifo = std::mem::take(&mut self.fifo);
+        if self.dot == OAM_SCAN_DOTS {
+            fifo.start_line(self, self.line);
+        }
+        let was_done = fifo.done();
+        fifo.step(self, self.window_line);
+        if fifo.done() && !was_done {
+            self.hblank_started = true;
+            self.store_line(fifo.output());
+            if fifo.drew_window() {
+                self.window_line += 1;
+            }
+        }
+        self.fifo = fifo;
+    }
+
+    // LY as the cpu sees it. It already reads 0 a few dots into line 153
+    pub fn ly(&self) -> u8 {
+        if self.line == LINES - 1 && self.dot >= 4 {
+            0
+        }
+        else {
+            self.line
+        }
+    }
+
+    fn transfer_dots(&self) -> u16 {
+        TRANSFER_DOTS + (self.scx & 7) as u16
+    }
+
+    fn in_transfer(&self) -> bool {
+        match self.renderer {
+            Renderer::Scanline => self.dot < OAM_SCAN_DOTS + self.transfer_dots(),
+            Renderer::Fifo => !self.fifo.done(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        if !self.enabled() || self.line >= VBLANK_LINE {
+            return if self.enabled() { Mode::VBlank } else { Mode::HBlank };
+        }
+        if self.dot < OAM_SCAN_DOTS {
+            if self.first_line { Mode::HBlank } else { Mode::OamScan }
+        }
+        else if self.in_transfer() {
+            Mode::Transfer
+        }
+        else {
+            Mode::HBlank
+        }
+    }
+
+    fn stat_sources(&self) -> bool {
+        if !self.enabled() {
+            return false;
+        }
+        let mode = self.mode();
+        (self.stat & STAT_LYC_INT != 0 && self.ly() == self.lyc)
+            || (self.stat & STAT_HBLANK_INT != 0 && mode == Mode::HBlank)
+            || (self.stat & STAT_VBLANK_INT != 0 && mode == Mode::VBlank)
+            // the OAM source also fires as VBlank starts
+            || (self.stat & STAT_OAM_INT != 0
+                && (mode == Mode::OamScan || (self.line == VBLANK_LINE && self.dot == 0)))
+    }
+
+    // re-evaluates the STAT line, returning true on a rising edge
+    fn update_stat(&mut self) -> bool {
+        let line = self.stat_sources();
+        let rising = line && !self.stat_line;
+        self.stat_line = line;
+        rising
+    }
+
+    // advances `dots` dots, returning the IF bits requested along the way
+    pub fn tick(&mut self, dots: u8) -> u8 {
+        if !self.enabled() {
+            return 0;
+        }
+        let mut irq = 0;
+        for _ in 0..dots {
+            self.dot += 1;
+            if self.dot == DOTS_PER_LINE {
+                self.dot = 0;
+                self.first_line = false;
+                self.line = (self.line + 1) % LINES;
+                if self.line == VBLANK_LINE {
+                    irq |= Interrupt::VBlank as u8;
+                    self.frame_ready = true;
+                    self.window_line = 0;
+                }
+            }
+            if self.line < VBLANK_LINE {
+                match self.renderer {
+                    Renderer::Scanline if self.dot == OAM_SCAN_DOTS + self.transfer_dots() => {
+                        self.hblank_started = true;
+                        self.render_line();
+                    },
+                    Renderer::Fifo if self.dot >= OAM_SCAN_DOTS => self.step_fifo(),
+                    _ => (),
+                }
+            }
+            if self.update_stat() {
+                irq |= Interrupt::Stat as u8;
+            }
+        }
+        irq
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            LCDC => self.lcdc,
+            STAT => {
+                let lyc_eq = if self.ly() == self.lyc { STAT_LYC_EQ } else { 0 };
+                STAT_UNUSED | self.stat | lyc_eq | self.mode() as u8
+            },
+            SCY => self.scy,
+            SCX => self.scx,
+            LY => self.ly(),
+            LYC => self.lyc,
+            BGP => self.bgp,
+            OBP0 => self.obp0,
+            OBP1 => self.obp1,
+            WY => self.wy,
+            WX => self.wx,
+            _ if !self.cgb => 0xFF,
+            VBK => 0xFE | self.vbk,
+            BCPS => self.bcps | 0x40,
+            BCPD => self.bg_palettes[(self.bcps & PALETTE_INDEX) as usize],
+            OCPS => self.ocps | 0x40,
+            OCPD => self.obj_palettes[(self.ocps & PALETTE_INDEX) as usize],
+            _ => 0xFE | self.opri,
+        }
+    }
+
+    // writes palette RAM at the index in `spec`, moving on if it auto
+    // increments
+    fn write_palette(ram: &mut [u8], spec: &mut u8, val: u8) {
+        ram[(*spec & PALETTE_INDEX) as usize] = val;
+        if *spec & PALETTE_AUTO_INC != 0 {
+            *spec = PALETTE_AUTO_INC | ((*spec + 1) & PALETTE_INDEX);
+        }
+    }
+
+    // returns the IF bits the write requests
+    pub fn write(&mut self, addr: u16, val: u8) -> u8 {
+        match addr {
+            LCDC => {
+                let was_enabled = self.enabled();
+                self.lcdc = val;
+                if was_enabled != self.enabled() {
+                    // off parks the ppu at the start of line 0, and on starts
+                    // from there without an OAM scan
+                    self.line = 0;
+                    self.dot = 0;
+                    self.first_line = self.enabled();
+                    self.stat_line = false;
+                }
+            },
+            STAT => self.stat = val & STAT_WRITABLE,
+            SCY => self.scy = val,
+            SCX => self.scx = val,
+            // LY is read only
+            LY => (),
+            LYC => self.lyc = val,
+            BGP => self.bgp = val,
+            OBP0 => self.obp0 = val,
+            OBP1 => self.obp1 = val,
+            WY => self.wy = val,
+            WX => self.wx = val,
+            _ if !self.cgb => (),
+            VBK => self.vbk = val & 1,
+            BCPS => self.bcps = val & (PALETTE_AUTO_INC | PALETTE_INDEX),
+            BCPD => Self::write_palette(&mut self.bg_palettes, &mut self.bcps, val),
+            OCPS => self.ocps = val & (PALETTE_AUTO_INC | PALETTE_INDEX),
+            OCPD => Self::write_palette(&mut self.obj_palettes, &mut self.ocps, val),
+            _ => self.opri = val & 1,
+        }
+        if self.update_stat() { Interrupt::Stat as u8 } else { 0 }
+    }
+}
+
+pub fn rgb555_to_rgba(color: u16) -> [u8; 4] {
+    let channel = |shift: u16| {
+        let c = ((color >> shift) & 0x1F) as u8;
+        c << 3 | c >> 2
+    };
+    [channel(0), channel(5), channel(10), 0xFF]
+}
+
+impl Default for Ppu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::VRAM_BANK_SIZE;
+
+    fn on() -> Ppu {
+        let mut ppu = Ppu::new();
+        ppu.write(LCDC, LCDC_ENABLE);
+        ppu
+    }
+
+    // runs to `line`, `dot`, returning the IF bits raised on the way
+    fn run_to(ppu: &mut Ppu, line: u8, dot: u16) -> u8 {
+        let mut irq = 0;
+        while ppu.line() != line || ppu.dot() != dot {
+            irq |= ppu.tick(1);
+        }
+        irq
+    }
+
+    #[test]
+    fn mode_timing() {
+        let mut ppu = on();
+        // the first line after switching on skips OAM scan
+        assert_eq!(ppu.mode(), Mode::HBlank);
+        run_to(&mut ppu, 1, 0);
+        assert_eq!(ppu.mode(), Mode::OamScan);
+        run_to(&mut ppu, 1, OAM_SCAN_DOTS);
+        assert_eq!(ppu.mode(), Mode::Transfer);
+        run_to(&mut ppu, 1, OAM_SCAN_DOTS + TRANSFER_DOTS);
+        assert_eq!(ppu.mode(), Mode::HBlank);
+        assert!(ppu.take_hblank());
+        assert!(!ppu.take_hblank());
+        // fine scroll lengthens mode 3
+        ppu.write(SCX, 5);
+        run_to(&mut ppu, 2, OAM_SCAN_DOTS + TRANSFER_DOTS);
+        assert_eq!(ppu.mode(), Mode::Transfer);
+        run_to(&mut ppu, 2, OAM_SCAN_DOTS + TRANSFER_DOTS + 5);
+        assert_eq!(ppu.mode(), Mode::HBlank);
+        assert_eq!(ppu.read(STAT), 0x80);
+    }
+
+    #[test]
+    fn vblank() {
+        let mut ppu = on();
+        assert_eq!(run_to(&mut ppu, VBLANK_LINE, 0), Interrupt::VBlank as u8);
+        assert_eq!(ppu.mode(), Mode::VBlank);
+        assert!(ppu.frame_ready());
+        assert!(!ppu.frame_ready());
+        assert_eq!(run_to(&mut ppu, 0, 0), 0);
+    }
+
+    #[test]
+    fn ly_reads_0_early_in_line_153() {
+        let mut ppu = on();
+        run_to(&mut ppu, LINES - 1, 3);
+        assert_eq!(ppu.read(LY), 153);
+        ppu.tick(1);
+        assert_eq!(ppu.read(LY), 0);
+        // and LY can't be written
+        ppu.write(LY, 9);
+        assert_eq!(ppu.read(LY), 0);
+    }
+
+    #[test]
+    fn lyc_compare() {
+        let mut ppu = on();
+        ppu.write(LYC, 3);
+        ppu.write(STAT, STAT_LYC_INT);
+        assert_eq!(run_to(&mut ppu, 3, 0), Interrupt::Stat as u8);
+        assert_eq!(ppu.read(STAT) & STAT_LYC_EQ, STAT_LYC_EQ);
+        run_to(&mut ppu, 4, 0);
+        assert_eq!(ppu.read(STAT) & STAT_LYC_EQ, 0);
+        // writing LYC to match raises it at once
+        assert_eq!(ppu.write(LYC, 4), Interrupt::Stat as u8);
+    }
+
+    #[test]
+    fn stat_blocking() {
+        let mut ppu = on();
+        ppu.write(STAT, STAT_HBLANK_INT | STAT_LYC_INT);
+        ppu.write(LYC, 2);
+        run_to(&mut ppu, 1, 400);
+        // HBlank holds the line high into line 2, so LY == LYC raises nothing
+        // until the line drops in OAM scan
+        let irq = run_to(&mut ppu, 2, 0);
+        assert_eq!(irq, 0);
+        assert_eq!(run_to(&mut ppu, 2, OAM_SCAN_DOTS + TRANSFER_DOTS), 0);
+        // the LYC source holds it through line 2's HBlank too
+        assert_eq!(run_to(&mut ppu, 3, 0), 0);
+        // line 3 OAM scan drops it, HBlank raises it again
+        assert_eq!(run_to(&mut ppu, 3, OAM_SCAN_DOTS + TRANSFER_DOTS), Interrupt::Stat as u8);
+    }
+
+    #[test]
+    fn oam_source_fires_entering_vblank() {
+        let mut ppu = on();
+        ppu.write(STAT, STAT_OAM_INT);
+        run_to(&mut ppu, VBLANK_LINE - 1, 300);
+        assert_eq!(run_to(&mut ppu, VBLANK_LINE, 1), Interrupt::VBlank as u8 | Interrupt::Stat as u8);
+    }
+
+    #[test]
+    fn vram_banks() {
+        let mut ppu = Ppu::new();
+        ppu.cgb = true;
+        ppu.write_vram(0x8000, 1);
+        ppu.write(VBK, 0xFF);
+        assert_eq!(ppu.read(VBK), 0xFF);
+        assert_eq!(ppu.read_vram(0x8000), 0);
+        ppu.write_vram(0x8000, 2);
+        ppu.write(VBK, 0);
+        assert_eq!(ppu.read(VBK), 0xFE);
+        assert_eq!(ppu.read_vram(0x8000), 1);
+        assert_eq!(ppu.vram()[VRAM_BANK_SIZE], 2);
+    }
+
+    #[test]
+    fn palette_ram_auto_increments() {
+        let mut ppu = Ppu::new();
+        ppu.cgb = true;
+        ppu.write(BCPS, PALETTE_AUTO_INC | 0x3E);
+        ppu.write(BCPD, 0x1F);
+        ppu.write(BCPD, 0x7C);
+        // wrapped round to 0
+        ppu.write(BCPD, 0xE0);
+        assert_eq!(ppu.read(BCPS), 0xC1);
+        assert_eq!(ppu.bg_color(7, 3), 0x7C1F);
+        // reading doesn't move the index, and without auto increment
+        // writes stay put
+        ppu.write(OCPS, 0x02);
+        ppu.write(OCPD, 0x11);
+        ppu.write(OCPD, 0x22);
+        assert_eq!(ppu.read(OCPS), 0x42);
+        assert_eq!(ppu.read(OCPD), 0x22);
+        assert_eq!(ppu.obj_color(0, 1), 0x0022);
+        ppu.write(BCPS, 0);
+        assert_eq!(ppu.read(BCPD), 0xE0);
+    }
+
+    #[test]
+    fn cgb_registers_are_gone_in_dmg_mode() {
+        let mut ppu = Ppu::new();
+        ppu.write(VBK, 1);
+        ppu.write(BCPS, 0x80);
+        ppu.write(BCPD, 0x12);
+        for reg in [VBK, BCPS, BCPD, OCPS, OCPD, OPRI] {
+            assert_eq!(ppu.read(reg), 0xFF);
+        }
+        assert_eq!(ppu.bg_color(0, 0), 0);
+        ppu.write_vram(0x8000, 3);
+        assert_eq!(ppu.vram()[0], 3);
+    }
+
+    #[test]
+    fn colored_frames() {
+        let mut ppu = on();
+        assert!(!ppu.colored());
+        ppu.set_palette(false, 0, [0x7FFF, 0x001F, 0x03E0, 0x7C00]);
+        ppu.enter_compat();
+        ppu.bgp = 0xE4;
+        assert!(ppu.colored());
+        run_to(&mut ppu, 1, 0);
+        assert_eq!(ppu.rgb555()[0], 0x7FFF);
+        assert_eq!(ppu.rgba()[..4], [0xFF; 4]);
+        assert_eq!(rgb555_to_rgba(0x001F), [0xFF, 0, 0, 0xFF]);
+        assert_eq!(rgb555_to_rgba(0x0200), [0, 0x84, 0, 0xFF]);
+    }
+
+    #[test]
+    fn lcd_off() {
+        let mut ppu = on();
+        run_to(&mut ppu, 10, 100);
+        ppu.write(LCDC, 0);
+        assert_eq!((ppu.line(), ppu.dot()), (0, 0));
+        assert_eq!(ppu.mode(), Mode::HBlank);
+        assert_eq!(ppu.tick(200), 0);
+        assert_eq!(ppu.dot(), 0);
+        assert_eq!(ppu.read(STAT) & 3, 0);
+    }
+}
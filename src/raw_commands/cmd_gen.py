@@ -114,6 +114,26 @@ def extract_all(line):
         founds.append(found)
     return founds 
 
+# cycle counts the manual gets wrong, by opcode. Conditional branches keep
+# the count for when they aren't taken, and their handlers return the extra
+# cycles a taken branch costs
+CYCLE_FIXES = {
+    'C3': '16',
+    '18': '12',
+    'CD': '24',
+    'C9': '16',
+    'D9': '16',
+    'C7': '16',
+    'CF': '16',
+    'D7': '16',
+    'DF': '16',
+    'E7': '16',
+    'EF': '16',
+    'F7': '16',
+    'FF': '16',
+    'CB,46': '12',
+}
+
 def process_cmd_line(line, num_args, flag):
     cmd, line = extract_front_cmd(line)
     if flag:
@@ -131,6 +151,7 @@ def process_cmd_line(line, num_args, flag):
     op_code = [process_opcode(op) for op in op_code]
     args_str = ','.join(args)
     op_code_str = ','.join(op_code)
+    cycles = CYCLE_FIXES.get(op_code_str, cycles)
     return '|'.join([cmd, args_str, op_code_str, cycles])
 
 def line_to_params(line, cmd):
@@ -329,8 +350,13 @@ def inc_reg(reg):
 def inc_tup(tup):
     tup[3][1] = inc_reg(tup[3][1])
 
+# JP (HL) jumps to HL rather than to what it points at
+def unref_tup(tup):
+    tup[3][0] = tup[3][0].strip('()')
+
 CHANGES = {
     ("3.3.1.", "5."): inc_tup,
+    ("3.3.8.", "3."): unref_tup,
 }
 
 def process_dict(file, opcodes, orig=True, prev_key=0):
@@ -443,7 +469,9 @@ def get_fn_str(cmd, regs, cycles):
     params = ', '.join([create_cmd_inp(inp) for inp in inps])
     if len(inps) > 0:
         params = ', ' + params
-    return '|mother| {' + cmd.lower() + '(mother' + params + '); return ' + cycles + ';}'
+    if flag:
+        return '|mother| {' + cycles + ' + ' + cmd.lower() + '(mother' + params + ')}'
+    return '|mother| {' + cmd.lower() + '(mother' + params + '); ' + cycles + '}'
 
 
 def get_sub_fn_str(prev_key, key):
@@ -456,7 +484,6 @@ def write_body(file, opcodes):
     file.write('use crate::cpu::{Flag, Reg};\n')
     file.write('use crate::motherboard::Motherboard;\n\n')
     file.write('use std::collections::HashMap;\n\n')
-    file.write('type OpInp = (Option<u8>, u8);\n')
     file.write('type CmdFns = fn(&mut Motherboard) -> u8;\n\n')
     file.write('pub struct OpCmds {\n')
     file.write('    op_map: HashMap<u16, CmdFns>,\n')
@@ -475,6 +502,11 @@ def write_body(file, opcodes):
     file.write('    pub fn exe_op(&self, mother: &mut Motherboard, op: u16) -> u8 {\n')
     file.write('        self.op_map.get(&op).unwrap()(mother)\n')
     file.write('    }\n')
+    file.write('}\n\n')
+    file.write('impl Default for OpCmds {\n')
+    file.write('    fn default() -> Self {\n')
+    file.write('        Self::new()\n')
+    file.write('    }\n')
     file.write('}\n')
 
 def write_file(path, opcodes):
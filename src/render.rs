@@ -0,0 +1,486 @@
+// Scanline renderer. Each line of background, window and objects is drawn in
+// one go from VRAM, OAM and the registers as they stand when pixel transfer
+// for that line ends. Pixels come out as DMG shades 0-3, or as 15 bit colors
+// from palette RAM on CGB, in CGB mode or coloring a DMG cartridge's shades.
+
+use crate::ppu::Ppu;
+
+pub const WIDTH: usize = 160;
+pub const HEIGHT: usize = 144;
+
+const LCDC_BG_ENABLE: u8 = 0x01;
+const LCDC_OBJ_ENABLE: u8 = 0x02;
+const LCDC_OBJ_TALL: u8 = 0x04;
+const LCDC_BG_MAP: u8 = 0x08;
+const LCDC_TILE_DATA: u8 = 0x10;
+const LCDC_WINDOW_ENABLE: u8 = 0x20;
+const LCDC_WINDOW_MAP: u8 = 0x40;
+
+// object attributes, and on CGB background map attributes from VRAM bank 1
+const ATTR_BG_PRIORITY: u8 = 0x80;
+const ATTR_Y_FLIP: u8 = 0x40;
+const ATTR_X_FLIP: u8 = 0x20;
+const ATTR_PALETTE: u8 = 0x10;
+const ATTR_BANK: u8 = 0x08;
+const ATTR_CGB_PALETTE: u8 = 0x07;
+
+const OBJS_PER_LINE: usize = 10;
+
+// offsets into vram of the two tile maps, and the size of a bank
+const MAP_LOW: usize = 0x1800;
+const MAP_HIGH: usize = 0x1C00;
+pub const VRAM_BANK_SIZE: usize = 0x2000;
+
+// white to black
+pub const SHADES_RGBA: [[u8; 4]; 4] = [
+    [0xFF, 0xFF, 0xFF, 0xFF],
+    [0xAA, 0xAA, 0xAA, 0xFF],
+    [0x55, 0x55, 0x55, 0xFF],
+    [0x00, 0x00, 0x00, 0xFF],
+];
+
+#[derive(Clone, Copy)]
+pub struct Object {
+    pub y: u8,
+    pub x: u8,
+    pub tile: u8,
+    pub attr: u8,
+    pub index: usize,
+}
+
+// bit planes of row `row` of tile `tile`, through the LCDC addressing mode
+pub fn tile_row(vram: &[u8], lcdc: u8, tile: u8, row: u8) -> (u8, u8) {
+    let base = if lcdc & LCDC_TILE_DATA != 0 {
+        tile as usize * 16
+    }
+    else {
+        (0x1000 + tile as i8 as isize * 16) as usize
+    };
+    let addr = base + row as usize * 2;
+    (vram[addr], vram[addr + 1])
+}
+
+// the VRAM bank tiles with attributes `attr` are read from
+pub fn bank(vram: &[u8], attr: u8) -> &[u8] {
+    if attr & ATTR_BANK != 0 { &vram[VRAM_BANK_SIZE..] } else { &vram[..VRAM_BANK_SIZE] }
+}
+
+// color index of pixel `x` (0 is leftmost) in a row of bit planes
+pub fn row_pixel(planes: (u8, u8), x: u8) -> u8 {
+    let bit = 7 - x;
+    ((planes.1 >> bit) & 1) << 1 | ((planes.0 >> bit) & 1)
+}
+
+pub fn shade(palette: u8, color: u8) -> u8 {
+    (palette >> (color * 2)) & 3
+}
+
+pub fn obj_height(lcdc: u8) -> u8 {
+    if lcdc & LCDC_OBJ_TALL != 0 { 16 } else { 8 }
+}
+
+// the first ten objects in OAM order that cover `line`
+pub fn scan_oam(oam: &[u8], lcdc: u8, line: u8) -> Vec<Object> {
+    let height = obj_height(lcdc);
+    oam.chunks(4)
+        .enumerate()
+        .map(|(index, entry)| Object {
+            y: entry[0],
+            x: entry[1],
+            tile: entry[2],
+            attr: entry[3],
+            index,
+        })
+        .filter(|obj| {
+            let top = obj.y as i16 - 16;
+            (top..top + height as i16).contains(&(line as i16))
+        })
+        .take(OBJS_PER_LINE)
+        .collect()
+}
+
+// orders the objects of a line by priority: leftmost first on DMG (and on
+// CGB when OPRI asks for it), otherwise by OAM index alone
+pub fn sort_objs(ppu: &Ppu, objs: &mut [Object]) {
+    if !ppu.cgb || ppu.opri & 1 != 0 {
+        objs.sort_by_key(|obj| obj.x);
+    }
+}
+
+// whether objects sharing a pixel are ordered by OAM index rather than x
+pub fn oam_priority(ppu: &Ppu) -> bool {
+    ppu.cgb && ppu.opri & 1 == 0
+}
+
+// color index of `obj` at screen column `x`, if it covers it
+pub fn obj_pixel(ppu: &Ppu, obj: &Object, line: u8, x: u8) -> Option<u8> {
+    let col = x as i16 - (obj.x as i16 - 8);
+    if !(0..8).contains(&col) {
+        return None;
+    }
+    let height = obj_height(ppu.lcdc);
+    let mut row = (line as i16 - (obj.y as i16 - 16)) as u8;
+    if obj.attr & ATTR_Y_FLIP != 0 {
+        row = height - 1 - row;
+    }
+    let col = if obj.attr & ATTR_X_FLIP != 0 { 7 - col as u8 } else { col as u8 };
+    let tile = if height == 16 { obj.tile & 0xFE } else { obj.tile };
+    let attr = if ppu.cgb { obj.attr } else { 0 };
+    // objects always use 0x8000 addressing
+    let planes = tile_row(bank(ppu.vram(), attr), LCDC_TILE_DATA, tile.wrapping_add(row / 8), row % 8);
+    Some(row_pixel(planes, col))
+}
+
+pub fn window_visible(ppu: &Ppu, line: u8) -> bool {
+    // on DMG clearing LCDC bit 0 hides the window along with the background
+    let needed = if ppu.cgb { LCDC_WINDOW_ENABLE } else { LCDC_BG_ENABLE | LCDC_WINDOW_ENABLE };
+    ppu.lcdc & needed == needed && line >= ppu.wy && ppu.wx <= 166
+}
+
+pub fn bg_map(lcdc: u8, window: bool) -> usize {
+    let bit = if window { LCDC_WINDOW_MAP } else { LCDC_BG_MAP };
+    if lcdc & bit != 0 { MAP_HIGH } else { MAP_LOW }
+}
+
+// attributes of the map entry at `map_addr`, always 0 outside CGB mode
+pub fn map_attr(ppu: &Ppu, map_addr: usize) -> u8 {
+    if ppu.cgb { ppu.vram()[VRAM_BANK_SIZE + map_addr] } else { 0 }
+}
+
+// row `row` of a background tile with map attributes `attr`, after flipping
+pub fn attr_row(attr: u8, row: u8) -> u8 {
+    if attr & ATTR_Y_FLIP != 0 { 7 - row } else { row }
+}
+
+// bit planes of row `row` of the tile at `map_addr`, with its attributes
+// applied
+pub fn map_tile_row(ppu: &Ppu, map_addr: usize, attr: u8, row: u8) -> (u8, u8) {
+    let tile = ppu.vram()[map_addr];
+    tile_row(bank(ppu.vram(), attr), ppu.lcdc, tile, attr_row(attr, row))
+}
+
+// color index of pixel `x` of a fetched background tile row
+pub fn bg_row_pixel(planes: (u8, u8), attr: u8, x: u8) -> u8 {
+    row_pixel(planes, if attr & ATTR_X_FLIP != 0 { 7 - x } else { x })
+}
+
+// final value of a pixel with background color `bg` and map attributes
+// `bg_attr`, under object pixel `obj`
+pub fn mix(ppu: &Ppu, bg: u8, bg_attr: u8, obj: Option<(&Object, u8)>) -> u16 {
+    // on DMG clearing LCDC bit 0 blanks the background, on CGB it only takes
+    // away its priority over objects
+    let bg_enabled = ppu.lcdc & LCDC_BG_ENABLE != 0;
+    let bg = if ppu.cgb || bg_enabled { bg } else { 0 };
+
+    if let Some((obj, color)) = obj {
+        if color != 0 && ppu.lcdc & LCDC_OBJ_ENABLE != 0 {
+            let bg_wins = if ppu.cgb {
+                bg_enabled && bg != 0 && (bg_attr | obj.attr) & ATTR_BG_PRIORITY != 0
+            }
+            else {
+                obj.attr & ATTR_BG_PRIORITY != 0 && bg != 0
+            };
+            if !bg_wins {
+                return if ppu.cgb {
+                    ppu.obj_color(obj.attr & ATTR_CGB_PALETTE, color)
+                }
+                else {
+                    let obp1 = obj.attr & ATTR_PALETTE != 0;
+                    let shade = shade(if obp1 { ppu.obp1 } else { ppu.obp0 }, color);
+                    if ppu.compat { ppu.obj_color(obp1 as u8, shade) } else { shade as u16 }
+                };
+            }
+        }
+    }
+    if ppu.cgb {
+        ppu.bg_color(bg_attr & ATTR_CGB_PALETTE, bg)
+    }
+    else if ppu.compat {
+        ppu.bg_color(0, shade(ppu.bgp, bg))
+    }
+    else {
+        shade(ppu.bgp, bg) as u16
+    }
+}
+
+// draws `line` into `out`, advancing the window's line counter if the window
+// was drawn on it
+pub fn render_line(ppu: &Ppu, line: u8, window_line: &mut u8, out: &mut [u16]) {
+    let lcdc = ppu.lcdc;
+    // color index and map attributes of each background pixel
+    let mut bg = [(0u8, 0u8); WIDTH];
+
+    if ppu.cgb || lcdc & LCDC_BG_ENABLE != 0 {
+        let y = ppu.scy.wrapping_add(line);
+        let map = bg_map(lcdc, false);
+        for (x, pixel) in bg.iter_mut().enumerate() {
+            let x = ppu.scx.wrapping_add(x as u8);
+            let map_addr = map + (y as usize / 8) * 32 + x as usize / 8;
+            let attr = map_attr(ppu, map_addr);
+            *pixel = (bg_row_pixel(map_tile_row(ppu, map_addr, attr, y % 8), attr, x % 8), attr);
+        }
+    }
+
+    if window_visible(ppu, line) {
+        let start = ppu.wx as i16 - 7;
+        let y = *window_line;
+        let map = bg_map(lcdc, true);
+        for (x, pixel) in bg.iter_mut().enumerate().skip(start.max(0) as usize) {
+            let x = (x as i16 - start) as u8;
+            let map_addr = map + (y as usize / 8) * 32 + x as usize / 8;
+            let attr = map_attr(ppu, map_addr);
+            *pixel = (bg_row_pixel(map_tile_row(ppu, map_addr, attr, y % 8), attr, x % 8), attr);
+        }
+        *window_line += 1;
+    }
+
+    let mut objs = if lcdc & LCDC_OBJ_ENABLE != 0 { scan_oam(ppu.oam(), lcdc, line) } else { Vec::new() };
+    sort_objs(ppu, &mut objs);
+    for (x, pixel) in out.iter_mut().enumerate() {
+        let obj = objs
+            .iter()
+            .find_map(|obj| obj_pixel(ppu, obj, line, x as u8).filter(|color| *color != 0).map(|color| (obj, color)));
+        let (color, attr) = bg[x];
+        *pixel = mix(ppu, color, attr, obj);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppu::{OAM_START, VRAM_START};
+
+    // lcd, background and objects on, tiles at 0x8000, identity palettes
+    // except OBP1, which is reversed
+    fn ppu() -> Ppu {
+        let mut ppu = Ppu::new();
+        ppu.lcdc = 0x80 | LCDC_TILE_DATA | LCDC_OBJ_ENABLE | LCDC_BG_ENABLE;
+        ppu.bgp = 0xE4;
+        ppu.obp0 = 0xE4;
+        ppu.obp1 = 0x1B;
+        ppu
+    }
+
+    // fills tile `tile` at 0x8000 with color `color`
+    fn solid_tile(ppu: &mut Ppu, tile: u8, color: u8) {
+        for row in 0..8 {
+            let addr = VRAM_START + tile as u16 * 16 + row * 2;
+            ppu.write_vram(addr, if color & 1 != 0 { 0xFF } else { 0 });
+            ppu.write_vram(addr + 1, if color & 2 != 0 { 0xFF } else { 0 });
+        }
+    }
+
+    fn set_map(ppu: &mut Ppu, map: usize, idx: usize, tile: u8) {
+        ppu.write_vram(VRAM_START + (map + idx) as u16, tile);
+    }
+
+    fn set_obj(ppu: &mut Ppu, idx: u16, y: u8, x: u8, tile: u8, attr: u8) {
+        for (i, val) in [y, x, tile, attr].iter().enumerate() {
+            ppu.write_oam(OAM_START + idx * 4 + i as u16, *val);
+        }
+    }
+
+    fn line(ppu: &Ppu, line: u8) -> Vec<u16> {
+        let mut out = vec![0; WIDTH];
+        render_line(ppu, line, &mut 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn tile_addressing() {
+        let mut vram = vec![0; VRAM_BANK_SIZE];
+        vram[0x0010] = 1;
+        vram[0x0FF0] = 2;
+        vram[0x1010] = 3;
+        assert_eq!(tile_row(&vram, LCDC_TILE_DATA, 1, 0).0, 1);
+        // 0x8800 addressing takes tile numbers as signed from 0x9000
+        assert_eq!(tile_row(&vram, 0, 1, 0).0, 3);
+        assert_eq!(tile_row(&vram, 0, 0xFF, 0).0, 2);
+    }
+
+    #[test]
+    fn pixels_and_shades() {
+        assert_eq!(row_pixel((0x80, 0x00), 0), 1);
+        assert_eq!(row_pixel((0x01, 0x01), 7), 3);
+        assert_eq!(row_pixel((0x00, 0x40), 1), 2);
+        assert_eq!(shade(0xE4, 2), 2);
+        assert_eq!(shade(0x1B, 0), 3);
+    }
+
+    #[test]
+    fn scrolled_background() {
+        let mut ppu = ppu();
+        solid_tile(&mut ppu, 1, 3);
+        set_map(&mut ppu, MAP_LOW, 33, 1);
+        ppu.scx = 4;
+        ppu.scy = 5;
+        // row 1 of the map starts 3 lines down
+        let out = line(&ppu, 3);
+        assert_eq!(out[3], 0);
+        assert_eq!(out[4..12], [3; 8]);
+        assert_eq!(out[12], 0);
+        assert_eq!(line(&ppu, 11)[4], 0);
+        // on DMG clearing LCDC bit 0 blanks it
+        ppu.lcdc &= !LCDC_BG_ENABLE;
+        assert_eq!(line(&ppu, 3)[4], 0);
+    }
+
+    #[test]
+    fn window() {
+        let mut ppu = ppu();
+        solid_tile(&mut ppu, 2, 2);
+        for idx in 0..32 {
+            set_map(&mut ppu, MAP_HIGH, idx, 2);
+        }
+        ppu.lcdc |= LCDC_WINDOW_ENABLE | LCDC_WINDOW_MAP;
+        ppu.wy = 10;
+        ppu.wx = 87;
+        let mut window_line = 0;
+        let mut out = vec![0; WIDTH];
+        render_line(&ppu, 9, &mut window_line, &mut out);
+        assert_eq!(window_line, 0);
+        assert_eq!(out[80], 0);
+        render_line(&ppu, 10, &mut window_line, &mut out);
+        assert_eq!(window_line, 1);
+        assert_eq!(out[79], 0);
+        assert_eq!(out[80..], [2; 80]);
+        // past the right edge it is hidden
+        ppu.wx = 167;
+        assert!(!window_visible(&ppu, 10));
+    }
+
+    #[test]
+    fn object_palettes_and_flips() {
+        let mut ppu = ppu();
+        // a tile whose left column is color 1, the rest 0
+        for row in 0..8 {
+            ppu.write_vram(VRAM_START + 16 + row * 2, 0x80);
+        }
+        set_obj(&mut ppu, 0, 16, 8, 1, 0);
+        set_obj(&mut ppu, 1, 16, 28, 1, ATTR_X_FLIP | ATTR_PALETTE);
+        let out = line(&ppu, 0);
+        assert_eq!(out[0], 1);
+        assert_eq!(out[1], 0);
+        // flipped to the right column, through OBP1
+        assert_eq!(out[20], 0);
+        assert_eq!(out[27], 2);
+        ppu.lcdc &= !LCDC_OBJ_ENABLE;
+        assert_eq!(line(&ppu, 0)[0], 0);
+    }
+
+    #[test]
+    fn object_priority() {
+        let mut ppu = ppu();
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 2);
+        solid_tile(&mut ppu, 3, 3);
+        // the leftmost object wins on DMG, whatever the OAM order
+        set_obj(&mut ppu, 0, 16, 12, 1, 0);
+        set_obj(&mut ppu, 1, 16, 10, 2, 0);
+        assert_eq!(line(&ppu, 0)[4], 2);
+        // behind the background, unless that is color 0
+        set_map(&mut ppu, MAP_LOW, 0, 3);
+        set_obj(&mut ppu, 1, 16, 10, 2, ATTR_BG_PRIORITY);
+        let out = line(&ppu, 0);
+        assert_eq!(out[2], 3);
+        assert_eq!(out[8], 2);
+    }
+
+    #[test]
+    fn ten_objects_a_line() {
+        let mut ppu = ppu();
+        solid_tile(&mut ppu, 1, 3);
+        for idx in 0..12 {
+            set_obj(&mut ppu, idx, 16, 8 + idx as u8 * 8, 1, 0);
+        }
+        let out = line(&ppu, 0);
+        assert_eq!(out[79], 3);
+        assert_eq!(out[80], 0);
+        assert_eq!(scan_oam(ppu.oam(), ppu.lcdc, 8).len(), 0);
+    }
+
+    fn cgb() -> Ppu {
+        let mut ppu = ppu();
+        ppu.cgb = true;
+        for palette in 0..8 {
+            let colors = [0, 1, 2, 3].map(|color| palette * 0x100 + color);
+            ppu.set_palette(false, palette as u8, colors);
+            ppu.set_palette(true, palette as u8, colors.map(|color| color | 0x1000));
+        }
+        ppu
+    }
+
+    // sets the bank 1 map attributes of map entry `idx`
+    fn set_attr(ppu: &mut Ppu, idx: usize, attr: u8) {
+        ppu.write_vram(VRAM_START + (VRAM_BANK_SIZE + MAP_LOW + idx) as u16, attr);
+    }
+
+    #[test]
+    fn map_attributes() {
+        let mut ppu = cgb();
+        // tile 1 is color 1 in bank 0 and, top row only, color 2 in bank 1
+        solid_tile(&mut ppu, 1, 1);
+        ppu.write_vram(VRAM_START + VRAM_BANK_SIZE as u16 + 17, 0xFF);
+        for idx in 0..3 {
+            set_map(&mut ppu, MAP_LOW, idx, 1);
+        }
+        set_attr(&mut ppu, 0, 5);
+        set_attr(&mut ppu, 1, ATTR_BANK);
+        set_attr(&mut ppu, 2, ATTR_BANK | ATTR_Y_FLIP);
+        let top = line(&ppu, 0);
+        assert_eq!(top[0], 0x0501);
+        assert_eq!(top[8], 0x0002);
+        assert_eq!(top[16], 0x0000);
+        assert_eq!(line(&ppu, 7)[16], 0x0002);
+    }
+
+    #[test]
+    fn cgb_objects() {
+        let mut ppu = cgb();
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 3);
+        set_obj(&mut ppu, 0, 16, 12, 1, 6);
+        set_obj(&mut ppu, 1, 16, 10, 2, 2);
+        // OAM order wins on CGB, unless OPRI asks for DMG priority
+        assert_eq!(line(&ppu, 0)[4], 0x1601);
+        ppu.opri = 1;
+        assert_eq!(line(&ppu, 0)[4], 0x1203);
+        // the map priority bit puts background colors 1-3 in front
+        ppu.opri = 0;
+        set_map(&mut ppu, MAP_LOW, 0, 1);
+        set_attr(&mut ppu, 0, ATTR_BG_PRIORITY);
+        assert_eq!(line(&ppu, 0)[4], 0x0001);
+        // LCDC bit 0 takes that priority away
+        ppu.lcdc &= !LCDC_BG_ENABLE;
+        assert_eq!(line(&ppu, 0)[4], 0x1601);
+    }
+
+    #[test]
+    fn compat_colors_dmg_shades() {
+        let mut ppu = cgb();
+        ppu.enter_compat();
+        ppu.bgp = 0x1B;
+        solid_tile(&mut ppu, 1, 1);
+        set_obj(&mut ppu, 0, 16, 8, 1, ATTR_PALETTE | 7);
+        let out = line(&ppu, 0);
+        // shade 3 of background palette 0, and shade 2 of object palette 1
+        // whatever the CGB palette bits say
+        assert_eq!(out[8], 0x0003);
+        assert_eq!(out[0], 0x1102);
+    }
+
+    #[test]
+    fn tall_objects() {
+        let mut ppu = ppu();
+        solid_tile(&mut ppu, 4, 1);
+        solid_tile(&mut ppu, 5, 2);
+        // the low bit of the tile number is ignored
+        set_obj(&mut ppu, 0, 16, 8, 5, 0);
+        assert_eq!(line(&ppu, 8)[0], 0);
+        ppu.lcdc |= LCDC_OBJ_TALL;
+        assert_eq!(line(&ppu, 0)[0], 1);
+        assert_eq!(line(&ppu, 8)[0], 2);
+        set_obj(&mut ppu, 0, 16, 8, 5, ATTR_Y_FLIP);
+        assert_eq!(line(&ppu, 0)[0], 2);
+    }
+}
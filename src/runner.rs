@@ -0,0 +1,49 @@
+// Pieces shared by the headless test rom runners.
+
+use crate::boot_rom::BootRom;
+use crate::gameboy::GameBoy;
+use crate::model::Model;
+use crate::motherboard::Motherboard;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Timeout,
+    Crashed(String),
+}
+
+// a GameBoy with `rom` loaded, about to run `boot_rom` on the model it is
+// for or, without one, the cartridge entry point on a DMG
+pub fn load(rom: &[u8], boot_rom: Option<&BootRom>) -> GameBoy {
+    let mut mother = Motherboard::new();
+    mother.load_rom(rom);
+    let model = boot_rom.map_or(Model::DMG, |boot_rom| boot_rom.model);
+    mother.power_on(boot_rom.cloned(), model).expect("boot rom runs on its own model");
+    GameBoy::new(mother)
+}
+
+fn find_roms_into(dir: &Path, roms: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_roms_into(&path, roms)?;
+        }
+        else if path.extension().is_some_and(|ext| ext == "gb" || ext == "gbc") {
+            roms.push(path);
+        }
+    }
+    Ok(())
+}
+
+// every rom under `dir`, in path order
+pub fn find_roms(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut roms = Vec::new();
+    find_roms_into(dir, &mut roms)?;
+    roms.sort();
+    Ok(roms)
+}
@@ -0,0 +1,335 @@
+// Serial port registers SB (0xFF01) and SC (0xFF02). Setting the start bit
+// in SC shifts SB out a bit at a time, most significant first, while the
+// partner's byte shifts in. With the internal clock the bits go at 8192 Hz,
+// or 262144 Hz with the CGB's fast clock bit, both counted in cpu cycles so
+// double speed doubles them. With the external clock the port waits, for
+// ever if need be, until the partner clocks a byte in, at 8192 Hz of real
+// time whatever the speed. A finished transfer clears the start bit and
+// requests the serial interrupt. After start_output, each byte sent is also
+// kept once its transfer finishes, for test runners to inspect.
+
+pub const SB: u16 = 0xFF01;
+pub const SC: u16 = 0xFF02;
+
+const SC_START: u8 = 0x80;
+const SC_FAST: u8 = 0x02;
+const SC_INTERNAL: u8 = 0x01;
+const SC_UNUSED: u8 = 0x7C;
+// the fast clock bit isn't there on a DMG
+const SC_UNUSED_DMG: u8 = 0x7E;
+
+// cpu cycles a bit takes to shift
+const BIT_CYCLES: u32 = 512;
+const BIT_CYCLES_FAST: u32 = 16;
+
+// whatever is on the other end of the link cable
+pub trait LinkPartner {
+    // trades bytes for a transfer this side clocks, getting ours and
+    // returning theirs
+    fn exchange(&mut self, out: u8) -> u8;
+
+    // asked while this side waits on the external clock: the partner's byte
+    // if it starts a transfer now, given ours
+    fn clock_in(&mut self, _out: u8) -> Option<u8> {
+        None
+    }
+}
+
+// no cable: all ones shift in, and nothing ever clocks a transfer
+pub struct NoCable;
+
+impl LinkPartner for NoCable {
+    fn exchange(&mut self, _out: u8) -> u8 {
+        0xFF
+    }
+}
+
+struct Transfer {
+    // our byte, as SB held it at the start
+    outgoing: u8,
+    // the partner's byte, shifted out of the top as it goes into SB
+    incoming: u8,
+    bits: u8,
+    // cpu cycles into the current bit
+    cycles: u32,
+    bit_cycles: u32,
+}
+
+pub struct Serial {
+    cgb: bool,
+    sb: u8,
+    sc: u8,
+    transfer: Option<Transfer>,
+    partner: Box<dyn LinkPartner>,
+    // the bytes sent, when asked for
+    output: Option<Vec<u8>>,
+}
+
+impl Serial {
+    pub fn new() -> Self {
+        Self {
+            cgb: false,
+            sb: 0,
+            sc: 0,
+            transfer: None,
+            partner: Box::new(NoCable),
+            output: None,
+        }
+    }
+
+    // the mode, for the fast clock bit
+    pub fn set_cgb(&mut self, cgb: bool) {
+        self.cgb = cgb;
+    }
+
+    pub fn set_partner(&mut self, partner: Box<dyn LinkPartner>) {
+        self.partner = partner;
+    }
+
+    // starts keeping the bytes sent, dropping any kept so far
+    pub fn start_output(&mut self) {
+        self.output = Some(Vec::new());
+    }
+
+    // the bytes sent since start_output
+    pub fn output(&self) -> &[u8] {
+        self.output.as_deref().unwrap_or_default()
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            SB => self.sb,
+            _ if self.cgb => self.sc | SC_UNUSED,
+            _ => self.sc | SC_UNUSED_DMG,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, val: u8) {
+        match addr {
+            SB => self.sb = val,
+            _ => {
+                self.sc = val;
+                self.transfer = None;
+                if val & SC_START == 0 {
+                    return;
+                }
+                if val & SC_INTERNAL != 0 {
+                    let incoming = self.partner.exchange(self.sb);
+                    let fast = self.cgb && val & SC_FAST != 0;
+                    self.start(incoming, if fast { BIT_CYCLES_FAST } else { BIT_CYCLES });
+                }
+            },
+        }
+    }
+
+    fn start(&mut self, incoming: u8, bit_cycles: u32) {
+        self.transfer = Some(Transfer {
+            outgoing: self.sb,
+            incoming,
+            bits: 0,
+            cycles: 0,
+            bit_cycles,
+        });
+    }
+
+    // advances by `cycles` cpu cycles, returning whether a transfer finished
+    pub fn tick(&mut self, cycles: u8, double_speed: bool) -> bool {
+        if self.sc & SC_START == 0 {
+            return false;
+        }
+        if self.transfer.is_none() {
+            // waiting on the external clock, which takes twice the cpu
+            // cycles in double speed
+            let bit_cycles = if double_speed { BIT_CYCLES * 2 } else { BIT_CYCLES };
+            match self.partner.clock_in(self.sb) {
+                Some(incoming) => self.start(incoming, bit_cycles),
+                None => return false,
+            }
+        }
+        let Some(transfer) = &mut self.transfer else {
+            return false;
+        };
+        transfer.cycles += cycles as u32;
+        while transfer.cycles >= transfer.bit_cycles && transfer.bits < 8 {
+            transfer.cycles -= transfer.bit_cycles;
+            self.sb = (self.sb << 1) | (transfer.incoming >> 7);
+            transfer.incoming <<= 1;
+            transfer.bits += 1;
+        }
+        if transfer.bits < 8 {
+            return false;
+        }
+        if let Some(output) = &mut self.output {
+            output.push(transfer.outgoing);
+        }
+        self.transfer = None;
+        self.sc &= !SC_START;
+        true
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // sends back its bytes in turn, starting transfers itself if `clocks`
+    struct Partner {
+        bytes: Vec<u8>,
+        clocks: bool,
+    }
+
+    impl LinkPartner for Partner {
+        fn exchange(&mut self, _out: u8) -> u8 {
+            self.bytes.remove(0)
+        }
+
+        fn clock_in(&mut self, _out: u8) -> Option<u8> {
+            self.clocks.then(|| self.bytes.remove(0))
+        }
+    }
+
+    fn serial(cgb: bool, bytes: &[u8], clocks: bool) -> Serial {
+        let mut serial = Serial::new();
+        serial.set_cgb(cgb);
+        serial.set_partner(Box::new(Partner {
+            bytes: bytes.to_vec(),
+            clocks,
+        }));
+        serial.start_output();
+        serial
+    }
+
+    // ticks `cycles` cpu cycles in steps of 4, returning the cycle the
+    // transfer finished on
+    fn run(serial: &mut Serial, cycles: u32, double_speed: bool) -> Option<u32> {
+        let mut finished = None;
+        for step in 1..=cycles / 4 {
+            if serial.tick(4, double_speed) && finished.is_none() {
+                finished = Some(step * 4);
+            }
+        }
+        finished
+    }
+
+    #[test]
+    fn registers() {
+        let mut serial = serial(false, &[], false);
+        serial.write(SB, 0x5A);
+        assert_eq!(serial.read(SB), 0x5A);
+        assert_eq!(serial.read(SC), 0x7E);
+        serial.write(SC, 0x03);
+        assert_eq!(serial.read(SC), 0x7F);
+        serial.set_cgb(true);
+        serial.write(SC, 0x00);
+        assert_eq!(serial.read(SC), 0x7C);
+        serial.write(SC, 0x02);
+        assert_eq!(serial.read(SC), 0x7E);
+    }
+
+    #[test]
+    fn internal_clock() {
+        let mut serial = serial(false, &[0xC3], false);
+        serial.write(SB, 0x81);
+        serial.write(SC, SC_START | SC_INTERNAL);
+        assert_eq!(serial.read(SC), 0xFF);
+        // four bits in, half the partner's byte has shifted in
+        assert_eq!(run(&mut serial, 4 * BIT_CYCLES, false), None);
+        assert_eq!(serial.read(SB), 0x1C);
+        assert!(serial.output().is_empty());
+        assert_eq!(run(&mut serial, 4 * BIT_CYCLES, false), Some(4 * BIT_CYCLES));
+        assert_eq!(serial.read(SB), 0xC3);
+        assert_eq!(serial.read(SC), 0x7F);
+        assert_eq!(serial.output(), [0x81]);
+        // done until started again
+        assert_eq!(run(&mut serial, 8 * BIT_CYCLES, false), None);
+    }
+
+    #[test]
+    fn fast_clock() {
+        let mut serial = serial(true, &[0x00], false);
+        serial.write(SC, SC_START | SC_FAST | SC_INTERNAL);
+        assert_eq!(run(&mut serial, 8 * BIT_CYCLES, false), Some(8 * BIT_CYCLES_FAST));
+        // a DMG has no fast clock
+        let mut serial = self::serial(false, &[0x00], false);
+        serial.write(SC, SC_START | SC_FAST | SC_INTERNAL);
+        assert_eq!(run(&mut serial, 8 * BIT_CYCLES, false), Some(8 * BIT_CYCLES));
+    }
+
+    #[test]
+    fn internal_clock_in_double_speed() {
+        // counted in cpu cycles, so twice as fast in real time
+        let mut serial = serial(true, &[0x00], false);
+        serial.write(SC, SC_START | SC_INTERNAL);
+        assert_eq!(run(&mut serial, 16 * BIT_CYCLES, true), Some(8 * BIT_CYCLES));
+    }
+
+    #[test]
+    fn no_cable() {
+        let mut serial = Serial::new();
+        serial.start_output();
+        serial.write(SB, 0x42);
+        serial.write(SC, SC_START | SC_INTERNAL);
+        run(&mut serial, 8 * BIT_CYCLES, false);
+        assert_eq!(serial.read(SB), 0xFF);
+        assert_eq!(serial.output(), [0x42]);
+        // nothing clocks an external transfer
+        serial.write(SC, SC_START);
+        assert_eq!(run(&mut serial, 100 * BIT_CYCLES, false), None);
+        assert_eq!(serial.read(SC), 0xFE);
+        assert_eq!(serial.output(), [0x42]);
+    }
+
+    #[test]
+    fn output_is_opt_in() {
+        let mut serial = Serial::new();
+        serial.write(SB, 0x42);
+        serial.write(SC, SC_START | SC_INTERNAL);
+        run(&mut serial, 8 * BIT_CYCLES, false);
+        assert!(serial.output().is_empty());
+        serial.start_output();
+        serial.write(SC, SC_START | SC_INTERNAL);
+        run(&mut serial, 8 * BIT_CYCLES, false);
+        assert_eq!(serial.output(), [0xFF]);
+    }
+
+    #[test]
+    fn external_clock() {
+        let mut serial = serial(false, &[0x99], true);
+        serial.write(SB, 0x24);
+        serial.write(SC, SC_START);
+        assert!(serial.output().is_empty());
+        assert_eq!(run(&mut serial, 16 * BIT_CYCLES, false), Some(8 * BIT_CYCLES));
+        assert_eq!(serial.read(SB), 0x99);
+        assert_eq!(serial.output(), [0x24]);
+    }
+
+    #[test]
+    fn external_clock_in_double_speed() {
+        // the partner's clock doesn't speed up with the cpu
+        let mut serial = serial(true, &[0x99], true);
+        serial.write(SC, SC_START);
+        assert_eq!(run(&mut serial, 32 * BIT_CYCLES, true), Some(16 * BIT_CYCLES));
+    }
+
+    #[test]
+    fn sc_write_cancels() {
+        let mut serial = serial(false, &[0xFF, 0x00], false);
+        serial.write(SB, 0x11);
+        serial.write(SC, SC_START | SC_INTERNAL);
+        run(&mut serial, 4 * BIT_CYCLES, false);
+        serial.write(SC, 0);
+        assert_eq!(run(&mut serial, 8 * BIT_CYCLES, false), None);
+        assert!(serial.output().is_empty());
+        // a new transfer sends what SB holds now
+        serial.write(SC, SC_START | SC_INTERNAL);
+        assert_eq!(run(&mut serial, 8 * BIT_CYCLES, false), Some(8 * BIT_CYCLES));
+        assert_eq!(serial.output(), [0x1F]);
+        assert_eq!(serial.read(SB), 0x00);
+    }
+}
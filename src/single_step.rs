@@ -0,0 +1,345 @@
+// Runner for the sm83 single step tests (github.com/SingleStepTests/sm83).
+// Each json file holds the cases for one opcode, giving the cpu and ram state
+// before and after a single instruction plus the bus activity per m-cycle.
+// Memory accesses are checked in order; m-cycles without one are only counted,
+// as handlers don't place their accesses within the instruction.
+
+use crate::common::RegBytes;
+use crate::cpu::Reg;
+use crate::gameboy::{self, GameBoy};
+use crate::motherboard::{self, BusAccess, Motherboard};
+
+use serde_json::Value;
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const REGS_8: [(&str, Reg); 8] = [
+    ("a", Reg::A),
+    ("b", Reg::B),
+    ("c", Reg::C),
+    ("d", Reg::D),
+    ("e", Reg::E),
+    ("f", Reg::F),
+    ("h", Reg::H),
+    ("l", Reg::L),
+];
+const REGS_16: [(&str, Reg); 2] = [("pc", Reg::PC), ("sp", Reg::SP)];
+
+pub struct CaseFailure {
+    pub name: String,
+    pub diffs: Vec<String>,
+}
+
+pub struct OpReport {
+    pub op: String,
+    pub passed: usize,
+    pub failures: Vec<CaseFailure>,
+}
+
+impl OpReport {
+    pub fn total(&self) -> usize {
+        self.passed + self.failures.len()
+    }
+}
+
+pub struct Report {
+    pub ops: Vec<OpReport>,
+}
+
+impl Report {
+    pub fn failed_ops(&self) -> impl Iterator<Item = &OpReport> {
+        self.ops.iter().filter(|op| !op.failures.is_empty())
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.failed_ops().next().is_none()
+    }
+
+    // one line per opcode, followed by the diffs of its first failing case
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for op in &self.ops {
+            let status = if op.failures.is_empty() { "pass" } else { "FAIL" };
+            writeln!(out, "{} {:>5}/{:<5} {}", status, op.passed, op.total(), op.op).unwrap();
+            if let Some(failure) = op.failures.first() {
+                writeln!(out, "    {}", failure.name).unwrap();
+                for diff in &failure.diffs {
+                    writeln!(out, "        {}", diff).unwrap();
+                }
+            }
+        }
+        let passed = self.ops.len() - self.failed_ops().count();
+        writeln!(out, "{}/{} opcodes passed", passed, self.ops.len()).unwrap();
+        out
+    }
+}
+
+fn field(state: &Value, name: &str) -> u16 {
+    state[name].as_u64().unwrap_or(0) as u16
+}
+
+fn ram_entries(state: &Value) -> Vec<(u16, u8)> {
+    state["ram"]
+        .as_array()
+        .map(|ram| {
+            ram.iter()
+                .map(|entry| (entry[0].as_u64().unwrap_or(0) as u16, entry[1].as_u64().unwrap_or(0) as u8))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// the accesses of the `cycles` entries that touch memory. idle m-cycles are
+// null, or carry a null value or a kind without r or w
+fn bus_entries(cycles: &[Value]) -> Vec<BusAccess> {
+    cycles
+        .iter()
+        .filter_map(|entry| {
+            let kind = entry[2].as_str()?;
+            let write = kind.contains('w');
+            if !write && !kind.contains('r') {
+                return None;
+            }
+            Some(BusAccess {
+                addr: entry[0].as_u64()? as u16,
+                val: entry[1].as_u64()? as u8,
+                write,
+            })
+        })
+        .collect()
+}
+
+fn show(access: Option<&BusAccess>) -> String {
+    match access {
+        Some(access) => {
+            let kind = if access.write { "write" } else { "read" };
+            format!("{} {:02x} at {:04x}", kind, access.val, access.addr)
+        },
+        None => "nothing".to_string(),
+    }
+}
+
+fn diff_bus(got: &[BusAccess], want: &[BusAccess]) -> Vec<String> {
+    let mut diffs = Vec::new();
+    for idx in 0..got.len().max(want.len()) {
+        if got.get(idx) != want.get(idx) {
+            diffs.push(format!("access {}: got {}, want {}", idx, show(got.get(idx)), show(want.get(idx))));
+        }
+    }
+    diffs
+}
+
+fn load_state(mother: &mut Motherboard, state: &Value) {
+    mother.cpu.ime = field(state, "ime") != 0;
+    mother.put_mem_at(motherboard::IE, field(state, "ie") as u8);
+    for (name, reg) in REGS_8.iter() {
+        mother.cpu.write_reg(*reg, RegBytes::new_single(field(state, name) as u8));
+    }
+    for (name, reg) in REGS_16.iter() {
+        mother.cpu.write_reg(*reg, RegBytes::new_double(field(state, name)));
+    }
+    for (addr, val) in ram_entries(state) {
+        mother.put_mem_at(addr, val);
+    }
+}
+
+fn diff_state(mother: &mut Motherboard, state: &Value) -> Vec<String> {
+    let mut diffs = Vec::new();
+    for (name, reg) in REGS_8.iter() {
+        let got = mother.cpu.read_reg(*reg).get_single() as u16;
+        let want = field(state, name);
+        if got != want {
+            diffs.push(format!("{}: got {:02x}, want {:02x}", name, got, want));
+        }
+    }
+    for (name, reg) in REGS_16.iter() {
+        let got = mother.cpu.read_reg(*reg).get_double();
+        let want = field(state, name);
+        if got != want {
+            diffs.push(format!("{}: got {:04x}, want {:04x}", name, got, want));
+        }
+    }
+    let ime = mother.cpu.ime as u16;
+    if ime != field(state, "ime") {
+        diffs.push(format!("ime: got {}, want {}", ime, field(state, "ime")));
+    }
+    let ie = mother.get_mem_at(motherboard::IE) as u16;
+    if ie != field(state, "ie") {
+        diffs.push(format!("ie: got {:02x}, want {:02x}", ie, field(state, "ie")));
+    }
+    for (addr, want) in ram_entries(state) {
+        let got = mother.get_mem_at(addr);
+        if got != want {
+            diffs.push(format!("[{:04x}]: got {:02x}, want {:02x}", addr, got, want));
+        }
+    }
+    diffs
+}
+
+// runs one test case, returning the differences from the expected final state
+pub fn run_case(gb: &mut GameBoy, case: &Value) -> Vec<String> {
+    gb.mother = Motherboard::new_flat();
+    load_state(&mut gb.mother, &case["initial"]);
+
+    let key = gb.current_op();
+    if !gb.has_op(key) {
+        return vec![format!("no handler for op {:03x}", key)];
+    }
+    gb.mother.start_trace();
+    let step = gb.try_step();
+    let trace = gb.mother.take_trace();
+    let cycles = match step {
+        Ok(cycles) => cycles,
+        Err(msg) => return vec![format!("panicked: {}", msg)],
+    };
+
+    let mut diffs = diff_state(&mut gb.mother, &case["final"]);
+    if let Some(bus) = case["cycles"].as_array() {
+        let want = bus.len() * 4;
+        if cycles as usize != want {
+            diffs.push(format!("cycles: got {}, want {}", cycles, want));
+        }
+        diffs.extend(diff_bus(&trace, &bus_entries(bus)));
+    }
+    diffs
+}
+
+pub fn run_file(path: &Path) -> io::Result<OpReport> {
+    let cases: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut gb = GameBoy::new(Motherboard::new_flat());
+    let mut report = OpReport {
+        op: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+        passed: 0,
+        failures: Vec::new(),
+    };
+
+    for case in cases.as_array().map(|cases| cases.as_slice()).unwrap_or_default() {
+        let diffs = run_case(&mut gb, case);
+        if diffs.is_empty() {
+            report.passed += 1;
+        }
+        else {
+            report.failures.push(CaseFailure {
+                name: case["name"].as_str().unwrap_or("?").to_string(),
+                diffs,
+            });
+        }
+    }
+    Ok(report)
+}
+
+// runs every json file in `dir`, in file name order
+pub fn run_dir(dir: &Path) -> io::Result<Report> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    // handlers panicking on bad input are reported as failures, not printed
+    let ops: io::Result<Vec<OpReport>> = gameboy::quiet_panics(|| paths.iter().map(|path| run_file(path)).collect());
+
+    Ok(Report {
+        ops: ops?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn state(pc: u16, extra: Value) -> Value {
+        let mut state = json!({"a": 0, "b": 0, "c": 0, "d": 0, "e": 0, "f": 0, "h": 0, "l": 0, "pc": pc, "sp": 0xD000, "ime": 0, "ie": 0, "ram": []});
+        for (key, val) in extra.as_object().unwrap() {
+            state[key] = val.clone();
+        }
+        state
+    }
+
+    fn run(case: Value) -> Vec<String> {
+        run_case(&mut GameBoy::new(Motherboard::new_flat()), &case)
+    }
+
+    fn ld_nn_a(write: Value) -> Value {
+        json!({
+            "name": "ea 0000",
+            "initial": state(0x0100, json!({"a": 0x42, "ram": [[0x0100, 0xEA], [0x0101, 0x00], [0x0102, 0xC0], [0xC000, 0]]})),
+            "final": state(0x0103, json!({"a": 0x42, "ram": [[0xC000, 0x42]]})),
+            "cycles": [[0x0100, 0xEA, "r-m"], [0x0101, 0x00, "r-m"], [0x0102, 0xC0, "r-m"], write],
+        })
+    }
+
+    #[test]
+    fn bus_trace_matches() {
+        assert_eq!(run(ld_nn_a(json!([0xC000, 0x42, "-wm"]))), Vec::<String>::new());
+    }
+
+    #[test]
+    fn bus_trace_mismatch() {
+        let diffs = run(ld_nn_a(json!([0xC001, 0x42, "-wm"])));
+        assert_eq!(diffs, vec!["access 3: got write 42 at c000, want write 42 at c001"]);
+        let diffs = run(ld_nn_a(json!([0xC000, 0x42, "r-m"])));
+        assert_eq!(diffs, vec!["access 3: got write 42 at c000, want read 42 at c000"]);
+    }
+
+    // an operand at pc is a second read on the bus, not part of the fetch
+    #[test]
+    fn repeated_reads_are_kept() {
+        let case = json!({
+            "name": "7e 0000",
+            "initial": state(0x0100, json!({"h": 0x01, "l": 0x00, "ram": [[0x0100, 0x7E]]})),
+            "final": state(0x0101, json!({"a": 0x7E, "h": 0x01, "l": 0x00})),
+            "cycles": [[0x0100, 0x7E, "r-m"], [0x0100, 0x7E, "r-m"]],
+        });
+        assert_eq!(run(case), Vec::<String>::new());
+    }
+
+    #[test]
+    fn immediates_are_read_once() {
+        let case = json!({
+            "name": "e8 0000",
+            "initial": state(0x0100, json!({"ram": [[0x0100, 0xE8], [0x0101, 0x01]]})),
+            "final": state(0x0102, json!({"sp": 0xD001})),
+            "cycles": [[0x0100, 0xE8, "r-m"], [0x0101, 0x01, "r-m"], null, null],
+        });
+        assert_eq!(run(case), Vec::<String>::new());
+    }
+
+    #[test]
+    fn idle_cycles_are_only_counted() {
+        let case = json!({
+            "name": "f9 0000",
+            "initial": state(0x0100, json!({"h": 0x12, "l": 0x12, "ram": [[0x0100, 0xF9]]})),
+            "final": state(0x0101, json!({"h": 0x12, "l": 0x12, "sp": 0x1212})),
+            "cycles": [[0x0100, 0xF9, "r-m"], null],
+        });
+        assert_eq!(run(case), Vec::<String>::new());
+    }
+
+    #[test]
+    fn ime_and_ie() {
+        let case = json!({
+            "name": "f3 0000",
+            "initial": state(0x0100, json!({"ime": 1, "ie": 0x1F, "ram": [[0x0100, 0xF3]]})),
+            "final": state(0x0101, json!({"ime": 1, "ie": 0x05})),
+            "cycles": [[0x0100, 0xF3, "r-m"]],
+        });
+        assert_eq!(run(case), vec!["ime: got 0, want 1", "ie: got 1f, want 05"]);
+    }
+
+    #[test]
+    fn register_mismatch() {
+        let case = json!({
+            "name": "3c 0000",
+            "initial": state(0x0100, json!({"a": 0x01, "ram": [[0x0100, 0x3C]]})),
+            "final": state(0x0101, json!({"a": 0x03})),
+            "cycles": [[0x0100, 0x3C, "r-m"]],
+        });
+        assert_eq!(run(case), vec!["a: got 02, want 03"]);
+    }
+}
@@ -0,0 +1,249 @@
+// DIV, TIMA, TMA and TAC. DIV is the top byte of a 16 bit counter running at
+// the cpu clock, and TIMA counts falling edges of one of its bits (gated by
+// the TAC enable bit), so resetting DIV or changing TAC can tick it early.
+// On overflow TIMA reads 0 for one m-cycle before TMA is loaded and the
+// interrupt requested.
+
+pub const DIV: u16 = 0xFF04;
+pub const TIMA: u16 = 0xFF05;
+pub const TMA: u16 = 0xFF06;
+pub const TAC: u16 = 0xFF07;
+
+const TAC_ENABLE: u8 = 0x04;
+const TAC_UNUSED: u8 = 0xF8;
+
+// counter bit TIMA follows for each TAC clock select
+const TAC_BITS: [u16; 4] = [9, 3, 5, 7];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    // TIMA overflowed this m-cycle and reads 0; writing it cancels the reload
+    Overflowed,
+    // TMA was loaded this m-cycle; TIMA writes are ignored and TMA writes
+    // go straight through to TIMA
+    Reloaded,
+}
+
+pub struct Timer {
+    counter: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+    state: State,
+    // T-cycles left over from the last tick
+    leftover: u8,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            counter: 0,
+            tima: 0,
+            tma: 0,
+            tac: 0,
+            state: State::Running,
+            leftover: 0,
+        }
+    }
+
+    // sets the internal counter, for starting without a boot rom
+    pub fn set_counter(&mut self, counter: u16) {
+        self.counter = counter;
+    }
+
+    pub fn counter(&self) -> u16 {
+        self.counter
+    }
+
+    fn signal(&self) -> bool {
+        let bit = TAC_BITS[(self.tac & 0x03) as usize];
+        self.tac & TAC_ENABLE != 0 && (self.counter >> bit) & 1 != 0
+    }
+
+    fn inc_tima(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+        if overflow {
+            self.state = State::Overflowed;
+        }
+    }
+
+    // runs `update`, ticking TIMA if it takes the selected bit from 1 to 0
+    fn edge<F: FnOnce(&mut Self)>(&mut self, update: F) {
+        let before = self.signal();
+        update(self);
+        if before && !self.signal() {
+            self.inc_tima();
+        }
+    }
+
+    // advances one m-cycle, returning true when the interrupt is requested
+    fn tick_m(&mut self) -> bool {
+        let irq = match self.state {
+            State::Overflowed => {
+                self.tima = self.tma;
+                self.state = State::Reloaded;
+                true
+            },
+            State::Reloaded => {
+                self.state = State::Running;
+                false
+            },
+            State::Running => false,
+        };
+        self.edge(|timer| timer.counter = timer.counter.wrapping_add(4));
+        irq
+    }
+
+    // advances `cycles` T-cycles, returning true if the timer interrupt was
+    // requested along the way
+    pub fn tick(&mut self, cycles: u8) -> bool {
+        let total = self.leftover as u16 + cycles as u16;
+        self.leftover = (total % 4) as u8;
+        let mut irq = false;
+        for _ in 0..total / 4 {
+            irq |= self.tick_m();
+        }
+        irq
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            DIV => (self.counter >> 8) as u8,
+            TIMA => self.tima,
+            TMA => self.tma,
+            _ => self.tac | TAC_UNUSED,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, val: u8) {
+        match addr {
+            DIV => self.edge(|timer| timer.counter = 0),
+            TIMA => match self.state {
+                State::Overflowed => {
+                    self.tima = val;
+                    self.state = State::Running;
+                },
+                State::Reloaded => (),
+                State::Running => self.tima = val,
+            },
+            TMA => {
+                self.tma = val;
+                if self.state == State::Reloaded {
+                    self.tima = val;
+                }
+            },
+            _ => self.edge(|timer| timer.tac = val & !TAC_UNUSED),
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // enabled at 262144 Hz, following counter bit 3
+    fn fast() -> Timer {
+        let mut timer = Timer::new();
+        timer.write(TAC, TAC_ENABLE | 1);
+        timer
+    }
+
+    #[test]
+    fn div_is_the_counter_high_byte() {
+        let mut timer = Timer::new();
+        timer.tick(0xFC);
+        assert_eq!(timer.read(DIV), 0);
+        timer.tick(4);
+        assert_eq!(timer.read(DIV), 1);
+        timer.write(DIV, 0x55);
+        assert_eq!(timer.counter(), 0);
+    }
+
+    #[test]
+    fn counts_at_the_selected_rate() {
+        let mut timer = fast();
+        timer.tick(16);
+        assert_eq!(timer.read(TIMA), 1);
+        timer.tick(15);
+        assert_eq!(timer.read(TIMA), 1);
+        // the leftover T-cycles carry over
+        timer.tick(1);
+        assert_eq!(timer.read(TIMA), 2);
+        assert_eq!(timer.read(TAC), 0xFD);
+    }
+
+    #[test]
+    fn div_write_ticks_tima_on_a_falling_edge() {
+        let mut timer = fast();
+        timer.tick(8);
+        assert_eq!(timer.counter() & 8, 8);
+        timer.write(DIV, 0);
+        assert_eq!(timer.read(TIMA), 1);
+        // with the bit low there is no edge
+        timer.tick(4);
+        timer.write(DIV, 0);
+        assert_eq!(timer.read(TIMA), 1);
+    }
+
+    #[test]
+    fn disabling_ticks_tima_with_the_bit_high() {
+        let mut timer = fast();
+        timer.tick(8);
+        timer.write(TAC, 1);
+        assert_eq!(timer.read(TIMA), 1);
+        // and switching to a bit that is low counts too
+        let mut timer = fast();
+        timer.tick(8);
+        timer.write(TAC, TAC_ENABLE);
+        assert_eq!(timer.read(TIMA), 1);
+    }
+
+    // TIMA at 0xFF one m-cycle before it overflows
+    fn about_to_overflow() -> Timer {
+        let mut timer = fast();
+        timer.write(TMA, 0x80);
+        timer.write(TIMA, 0xFF);
+        timer.tick(12);
+        timer
+    }
+
+    #[test]
+    fn reload_is_a_cycle_late() {
+        let mut timer = about_to_overflow();
+        assert!(!timer.tick(4));
+        assert_eq!(timer.read(TIMA), 0);
+        assert!(timer.tick(4));
+        assert_eq!(timer.read(TIMA), 0x80);
+    }
+
+    #[test]
+    fn tima_write_while_overflowed_cancels_the_reload() {
+        let mut timer = about_to_overflow();
+        timer.tick(4);
+        timer.write(TIMA, 0x10);
+        assert!(!timer.tick(4));
+        assert_eq!(timer.read(TIMA), 0x10);
+    }
+
+    #[test]
+    fn tima_write_during_reload_is_ignored() {
+        let mut timer = about_to_overflow();
+        timer.tick(8);
+        timer.write(TIMA, 0x10);
+        assert_eq!(timer.read(TIMA), 0x80);
+        // a TMA write in the same cycle goes through to TIMA
+        timer.write(TMA, 0x20);
+        assert_eq!(timer.read(TIMA), 0x20);
+        timer.tick(4);
+        timer.write(TMA, 0x30);
+        assert_eq!(timer.read(TIMA), 0x20);
+    }
+}
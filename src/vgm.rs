@@ -0,0 +1,228 @@
+// Export of an APU log as a VGM file, the register dump format chiptune
+// players read. The Game Boy's sound chip is "GB DMG" from VGM 1.61, and
+// each write becomes command 0xB3 with the register's offset from NR10.
+// VGM counts time in samples at 44.1 kHz, and the waits between writes are
+// the differences of their stamps in samples.
+
+use crate::apu::{self, CLOCK_RATE};
+use crate::apu_log::ApuLog;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const VERSION: u32 = 0x171;
+const HEADER_SIZE: usize = 0x100;
+const SAMPLE_RATE: u64 = 44_100;
+
+// header fields
+const EOF_OFFSET: usize = 0x04;
+const VERSION_OFFSET: usize = 0x08;
+const TOTAL_SAMPLES: usize = 0x18;
+const LOOP_OFFSET: usize = 0x1C;
+const LOOP_SAMPLES: usize = 0x20;
+const DATA_OFFSET: usize = 0x34;
+const GB_DMG_CLOCK: usize = 0x80;
+
+const CMD_GB_WRITE: u8 = 0xB3;
+const CMD_WAIT: u8 = 0x61;
+const CMD_WAIT_SHORT: u8 = 0x70;
+const CMD_END: u8 = 0x66;
+
+fn samples(dots: u64) -> u64 {
+    apu::scale_dots(dots, SAMPLE_RATE)
+}
+
+fn put_u32(out: &mut [u8], at: usize, val: u32) {
+    out[at..at + 4].copy_from_slice(&val.to_le_bytes());
+}
+
+// waits from sample `now` until `until`, returning `until`
+fn wait_until(out: &mut Vec<u8>, now: u64, until: u64) -> u64 {
+    let mut samples = until - now;
+    while samples > 0 {
+        let chunk = samples.min(u16::MAX as u64);
+        if chunk <= 16 {
+            out.push(CMD_WAIT_SHORT + chunk as u8 - 1);
+        }
+        else {
+            out.push(CMD_WAIT);
+            out.extend_from_slice(&(chunk as u16).to_le_bytes());
+        }
+        samples -= chunk;
+    }
+    until
+}
+
+// the log as a VGM file, playing up to the log's current time
+pub fn encode(log: &ApuLog) -> Vec<u8> {
+    let mut out = vec![0; HEADER_SIZE];
+    let mut now = 0;
+    let mut loop_offset = None;
+    let loop_start = log.loop_start();
+
+    // the loop point comes before the write at its index, which may be past
+    // the last one
+    for idx in 0..=log.writes().len() {
+        if let Some((_, loop_time)) = loop_start.filter(|(loop_idx, _)| *loop_idx == idx) {
+            now = wait_until(&mut out, now, samples(loop_time));
+            loop_offset = Some(out.len());
+        }
+        if let Some(write) = log.writes().get(idx) {
+            now = wait_until(&mut out, now, samples(write.time));
+            out.extend_from_slice(&[CMD_GB_WRITE, (write.addr - apu::START) as u8, write.val]);
+        }
+    }
+    let total = samples(log.time());
+    wait_until(&mut out, now, total);
+    out.push(CMD_END);
+
+    out[..4].copy_from_slice(b"Vgm ");
+    let len = out.len();
+    put_u32(&mut out, EOF_OFFSET, (len - EOF_OFFSET) as u32);
+    put_u32(&mut out, VERSION_OFFSET, VERSION);
+    put_u32(&mut out, TOTAL_SAMPLES, total as u32);
+    if let (Some(offset), Some((_, loop_time))) = (loop_offset, loop_start) {
+        put_u32(&mut out, LOOP_OFFSET, (offset - LOOP_OFFSET) as u32);
+        put_u32(&mut out, LOOP_SAMPLES, (total - samples(loop_time)) as u32);
+    }
+    put_u32(&mut out, DATA_OFFSET, (HEADER_SIZE - DATA_OFFSET) as u32);
+    put_u32(&mut out, GB_DMG_CLOCK, CLOCK_RATE);
+    out
+}
+
+pub fn write(path: &Path, log: &ApuLog) -> io::Result<()> {
+    fs::write(path, encode(log))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(vgm: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([vgm[at], vgm[at + 1], vgm[at + 2], vgm[at + 3]])
+    }
+
+    // dots in `samples` VGM samples, rounded up
+    fn dots(samples: u64) -> u64 {
+        (samples * CLOCK_RATE as u64).div_ceil(SAMPLE_RATE)
+    }
+
+    fn advance(log: &mut ApuLog, dots: u64) {
+        for _ in 0..dots / 4 {
+            log.advance(4);
+        }
+        log.advance((dots % 4) as u8);
+    }
+
+    #[test]
+    fn header() {
+        let mut log = ApuLog::new(vec![]);
+        advance(&mut log, dots(100));
+        let vgm = encode(&log);
+        assert_eq!(&vgm[..4], b"Vgm ");
+        assert_eq!(u32_at(&vgm, EOF_OFFSET) as usize, vgm.len() - 4);
+        assert_eq!(u32_at(&vgm, VERSION_OFFSET), 0x171);
+        assert_eq!(u32_at(&vgm, TOTAL_SAMPLES), 100);
+        assert_eq!(u32_at(&vgm, LOOP_OFFSET), 0);
+        assert_eq!(u32_at(&vgm, LOOP_SAMPLES), 0);
+        // relative to the field itself, so the data starts at 0x100
+        assert_eq!(u32_at(&vgm, DATA_OFFSET) as usize + DATA_OFFSET, HEADER_SIZE);
+        assert_eq!(u32_at(&vgm, GB_DMG_CLOCK), CLOCK_RATE);
+        assert_eq!(&vgm[HEADER_SIZE..], &[CMD_WAIT, 100, 0, CMD_END]);
+    }
+
+    #[test]
+    fn writes() {
+        let mut log = ApuLog::new(vec![(apu::NR52, 0x80), (apu::WAVE_START, 0x12)]);
+        advance(&mut log, dots(3));
+        log.record(apu::NR12, 0xF0);
+        log.record(apu::NR14, 0x87);
+        let vgm = encode(&log);
+        assert_eq!(
+            &vgm[HEADER_SIZE..],
+            &[
+                CMD_GB_WRITE, 0x16, 0x80,
+                CMD_GB_WRITE, 0x20, 0x12,
+                CMD_WAIT_SHORT + 2,
+                CMD_GB_WRITE, 0x02, 0xF0,
+                CMD_GB_WRITE, 0x04, 0x87,
+                CMD_END,
+            ]
+        );
+    }
+
+    #[test]
+    fn long_waits() {
+        let mut log = ApuLog::new(vec![]);
+        advance(&mut log, dots(70_000));
+        let vgm = encode(&log);
+        assert_eq!(&vgm[HEADER_SIZE..], &[CMD_WAIT, 0xFF, 0xFF, CMD_WAIT, 0x71, 0x11, CMD_END]);
+    }
+
+    // samples waited in the commands after the header
+    fn waited(vgm: &[u8]) -> u64 {
+        let mut data = &vgm[HEADER_SIZE..];
+        let mut total = 0;
+        loop {
+            match data[0] {
+                CMD_END => return total,
+                CMD_GB_WRITE => data = &data[3..],
+                CMD_WAIT => {
+                    total += u16::from_le_bytes([data[1], data[2]]) as u64;
+                    data = &data[3..];
+                },
+                cmd => {
+                    total += (cmd - CMD_WAIT_SHORT) as u64 + 1;
+                    data = &data[1..];
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_doesnt_add_up() {
+        // each gap is just under a sample
+        let mut log = ApuLog::new(vec![]);
+        for _ in 0..1000 {
+            advance(&mut log, 95);
+            log.record(apu::NR50, 0x77);
+        }
+        let vgm = encode(&log);
+        assert_eq!(waited(&vgm), 95_000 * SAMPLE_RATE / CLOCK_RATE as u64);
+        assert_eq!(u32_at(&vgm, TOTAL_SAMPLES) as u64, waited(&vgm));
+    }
+
+    #[test]
+    fn loop_point() {
+        let mut log = ApuLog::new(vec![(apu::NR52, 0x80)]);
+        log.loop_at(dots(10));
+        advance(&mut log, dots(5));
+        log.record(apu::NR50, 0x11);
+        advance(&mut log, dots(5));
+        assert_eq!(log.loop_start(), Some((2, dots(10))));
+        log.record(apu::NR50, 0x22);
+        advance(&mut log, dots(30));
+        let vgm = encode(&log);
+        let offset = u32_at(&vgm, LOOP_OFFSET) as usize + LOOP_OFFSET;
+        assert_eq!(u32_at(&vgm, TOTAL_SAMPLES), 40);
+        assert_eq!(u32_at(&vgm, LOOP_SAMPLES), 30);
+        // the loop lands on the write right after the point
+        assert_eq!(&vgm[offset..offset + 3], &[CMD_GB_WRITE, 0x14, 0x22]);
+        assert_eq!(&vgm[offset - 1], &(CMD_WAIT_SHORT + 4));
+    }
+
+    #[test]
+    fn loop_after_the_last_write() {
+        let mut log = ApuLog::new(vec![(apu::NR52, 0x80)]);
+        advance(&mut log, dots(20));
+        log.mark_loop();
+        advance(&mut log, dots(20));
+        let vgm = encode(&log);
+        let offset = u32_at(&vgm, LOOP_OFFSET) as usize + LOOP_OFFSET;
+        assert_eq!(&vgm[offset..], &[CMD_WAIT, 20, 0, CMD_END]);
+        assert_eq!(u32_at(&vgm, LOOP_SAMPLES), 20);
+        log.clear_loop();
+        assert_eq!(u32_at(&encode(&log), LOOP_OFFSET), 0);
+    }
+}
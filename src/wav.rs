@@ -0,0 +1,87 @@
+// 16 bit PCM WAV files, for audio recordings.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER_SIZE: u32 = 44;
+const FORMAT_PCM: u16 = 1;
+const BITS_PER_SAMPLE: u16 = 16;
+
+// a WAV file holding `samples`, interleaved across `channels` channels
+pub fn encode(channels: u16, sample_rate: u32, samples: &[i16]) -> Vec<u8> {
+    let block_align = channels * BITS_PER_SAMPLE / 8;
+    let data_size = samples.len() as u32 * 2;
+
+    let mut out = Vec::with_capacity((HEADER_SIZE + data_size) as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(HEADER_SIZE - 8 + data_size).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&FORMAT_PCM.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+    out
+}
+
+pub fn write(path: &Path, channels: u16, sample_rate: u32, samples: &[i16]) -> io::Result<()> {
+    fs::write(path, encode(channels, sample_rate, samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    #[test]
+    fn header() {
+        let wav = encode(2, 48_000, &[1, -1, 0x1234, -0x1234]);
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 4), 36 + 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(&wav, 16), 16);
+        assert_eq!(u16_at(&wav, 20), FORMAT_PCM);
+        assert_eq!(u16_at(&wav, 22), 2);
+        assert_eq!(u32_at(&wav, 24), 48_000);
+        assert_eq!(u32_at(&wav, 28), 48_000 * 4);
+        assert_eq!(u16_at(&wav, 32), 4);
+        assert_eq!(u16_at(&wav, 34), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(&wav, 40), 8);
+        assert_eq!(&wav[44..], &[0x01, 0x00, 0xFF, 0xFF, 0x34, 0x12, 0xCC, 0xED]);
+    }
+
+    #[test]
+    fn mono() {
+        let wav = encode(1, 44_100, &[7; 3]);
+        assert_eq!(u16_at(&wav, 22), 1);
+        assert_eq!(u32_at(&wav, 28), 44_100 * 2);
+        assert_eq!(u16_at(&wav, 32), 2);
+        assert_eq!(u32_at(&wav, 40), 6);
+    }
+
+    #[test]
+    fn empty() {
+        let wav = encode(2, 48_000, &[]);
+        assert_eq!(wav.len(), 44);
+        assert_eq!(u32_at(&wav, 4), 36);
+        assert_eq!(u32_at(&wav, 40), 0);
+    }
+}
@@ -0,0 +1,72 @@
+use rustgb::asm;
+use rustgb::blargg;
+use rustgb::runner::Outcome;
+
+use std::env;
+use std::path::PathBuf;
+
+// point BLARGG_ROMS at a directory of Blargg's test roms to run these
+#[test]
+fn blargg_roms() {
+    let dir = match env::var_os("BLARGG_ROMS") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            eprintln!("BLARGG_ROMS not set, skipping");
+            return;
+        },
+    };
+    let results = blargg::run_dir(&dir, None, blargg::DEFAULT_CYCLE_BUDGET).unwrap();
+    for (path, result) in &results {
+        println!("{:?} {} ({} cycles)", result.outcome, path.display(), result.cycles);
+    }
+    assert!(results.iter().all(|(_, result)| result.outcome == Outcome::Passed));
+}
+
+// a rom printing `text` over serial a byte at a time, then spinning
+fn printing_rom(text: &str) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x0100..0x0103].copy_from_slice(&asm!("jp $0150", 0x0100));
+    let main = asm!(
+        &format!(
+            "main: ld hl, text
+            .next: ld a, [hl+]
+            and a
+            jr z, .done
+            ldh [$01], a
+            ld a, $81
+            ldh [$02], a
+            .wait: ldh a, [$02]
+            bit 7, a
+            jr nz, .wait
+            jr .next
+            .done: jr .done
+            text: db \"{}\", 0",
+            text
+        ),
+        0x0150
+    );
+    rom[0x0150..0x0150 + main.len()].copy_from_slice(&main);
+    rom
+}
+
+#[test]
+fn passed_over_serial() {
+    let result = blargg::run_rom(&printing_rom("cpu_instrs 01:ok Passed"), None, blargg::DEFAULT_CYCLE_BUDGET);
+    assert_eq!(result.outcome, Outcome::Passed);
+    assert_eq!(result.output, "cpu_instrs 01:ok Passed");
+}
+
+#[test]
+fn failed_over_serial() {
+    let result = blargg::run_rom(&printing_rom("01:01 Failed"), None, blargg::DEFAULT_CYCLE_BUDGET);
+    assert_eq!(result.outcome, Outcome::Failed);
+    assert_eq!(result.output, "01:01 Failed");
+}
+
+#[test]
+fn silence_times_out() {
+    let result = blargg::run_rom(&printing_rom("halt_bug"), None, 100_000);
+    assert_eq!(result.outcome, Outcome::Timeout);
+    assert_eq!(result.output, "halt_bug");
+    assert!(result.cycles >= 100_000);
+}
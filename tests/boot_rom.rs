@@ -0,0 +1,191 @@
+mod common;
+
+use rustgb::asm;
+use rustgb::boot_rom::{self, BootRom, BootRomError};
+use rustgb::gameboy::GameBoy;
+use rustgb::model::{self, Model};
+use rustgb::motherboard::Motherboard;
+use rustgb::ppu;
+
+const BG: [u16; 4] = [0x001F, 0x03E0, 0x7C00, 0x7FFF];
+
+// a stand in for a boot rom: it marks 0xC000, picks DMG_MODE in KEY0 and
+// hands over at 0x0100
+fn stand_in(model: Model) -> BootRom {
+    let size = if model.is_cgb() { boot_rom::CGB_SIZE } else { boot_rom::DMG_SIZE };
+    let mut data = vec![0; size];
+    let main = asm!("ld a, $42\nld [$C000], a\nld a, $04\nldh [$4C], a\njp $00FC");
+    data[..main.len()].copy_from_slice(&main);
+    data[0xFC..0x100].copy_from_slice(&asm!("ld a, $01\nldh [$50], a", 0x00FC));
+    BootRom::new_unverified(data, model).unwrap()
+}
+
+// a cartridge that spins at its entry point
+fn cart() -> Vec<u8> {
+    let mut rom = vec![0xAA; 0x8000];
+    rom[0x0100..0x0102].copy_from_slice(&asm!("jr @", 0x0100));
+    rom
+}
+
+// a stand in for the cgb boot rom: it fills background palette 0 and object
+// palettes 0 and 1, picks the mode in KEY0 from the header and hands over
+// at 0x0100
+fn cgb_boot_rom() -> BootRom {
+    let mut data = vec![0; boot_rom::CGB_SIZE];
+    let main = asm!(
+        "boot: ld a, $80
+        ldh [$68], a
+        ldh [$6A], a
+        ld hl, colors
+        ld b, 8
+        .bg: ld a, [hl+]
+        ldh [$69], a
+        dec b
+        jr nz, .bg
+        ld b, 16
+        .obj: ld a, [hl+]
+        ldh [$6B], a
+        dec b
+        jr nz, .obj
+        ld a, [$0143]
+        bit 7, a
+        jr nz, .cgb
+        ld a, $04
+        .cgb: ldh [$4C], a
+        jp $00FC
+        colors: dw $001F, $03E0, $7C00, $7FFF
+        dw $7FFF, $5294, $294A, $0000
+        dw $0000, $294A, $5294, $7FFF"
+    );
+    data[..main.len()].copy_from_slice(&main);
+    data[0xFC..0x100].copy_from_slice(&asm!("ld a, $01\nldh [$50], a", 0x00FC));
+    BootRom::new_unverified(data, Model::CGB).unwrap()
+}
+
+// a cartridge with `cgb_flag` in its header that turns the lcd on with BGP
+// reversed and spins
+fn color_cart(cgb_flag: u8) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x0143] = cgb_flag;
+    rom[0x0100..0x0103].copy_from_slice(&asm!("jp $0150", 0x0100));
+    let main = asm!("ld a, $1B\nldh [$47], a\nld a, $91\nldh [$40], a\nloop: jr loop", 0x0150);
+    rom[0x0150..0x0150 + main.len()].copy_from_slice(&main);
+    rom
+}
+
+// runs `rom` through `boot_rom` up to the cartridge entry point
+fn boot(rom: &[u8], boot_rom: BootRom) -> GameBoy {
+    let mut mother = Motherboard::new();
+    mother.load_rom(rom);
+    let model = boot_rom.model;
+    mother.power_on(Some(boot_rom), model).unwrap();
+    let mut gb = GameBoy::new(mother);
+    while gb.mother.cpu.pc != 0x0100 {
+        gb.step();
+        assert!(gb.cycles < 100_000);
+    }
+    gb
+}
+
+#[test]
+fn boot_rom_for_another_model_is_refused() {
+    let dmg = BootRom::new_unverified(vec![0; boot_rom::DMG_SIZE], Model::DMG).unwrap();
+    let mut mother = Motherboard::new();
+    let err = mother.power_on(Some(dmg.clone()), Model::CGB).unwrap_err();
+    assert!(matches!(err, BootRomError::WrongModel(Model::DMG, Model::CGB)));
+    assert_eq!(err.to_string(), "DMG boot rom can't start a CGB");
+    assert!(mother.power_on(Some(dmg), Model::DMG).is_ok());
+}
+
+#[test]
+fn overlay_until_boot_off() {
+    let mut mother = Motherboard::new();
+    mother.load_rom(&cart());
+    mother.power_on(Some(stand_in(Model::DMG)), Model::DMG).unwrap();
+    assert_eq!(mother.cpu.pc, 0x0000);
+    assert_eq!(mother.get_mem_at(0x0000), 0x3E);
+    assert_eq!(mother.get_mem_at(0x0100), 0x18);
+
+    let mut gb = boot(&cart(), stand_in(Model::DMG));
+    assert_eq!(gb.mother.get_mem_at(0xC000), 0x42);
+    assert_eq!(gb.mother.get_mem_at(0x0000), 0xAA);
+    assert_eq!(gb.mother.get_mem_at(0x00FC), 0xAA);
+}
+
+// the cgb boot rom leaves the header to the cartridge, and covers 0x0200 on
+#[test]
+fn cgb_overlay_skips_the_header() {
+    let mut mother = Motherboard::new();
+    mother.load_rom(&cart());
+    mother.power_on(Some(stand_in(Model::CGB)), Model::CGB).unwrap();
+    assert_eq!(mother.get_mem_at(0x0100), 0x18);
+    assert_eq!(mother.get_mem_at(0x0200), 0x00);
+    assert_eq!(mother.get_mem_at(0x0900), 0xAA);
+}
+
+#[test]
+fn key0_locks_at_the_handover() {
+    let mut gb = boot(&cart(), stand_in(Model::CGB));
+    assert_eq!(gb.mother.get_mem_at(boot_rom::KEY0), boot_rom::DMG_MODE);
+    gb.mother.put_mem_at(boot_rom::KEY0, 0x80);
+    assert_eq!(gb.mother.get_mem_at(boot_rom::KEY0), boot_rom::DMG_MODE);
+    gb.mother.put_mem_at(boot_rom::BOOT_OFF, 1);
+    assert!(gb.mother.ppu.compat);
+}
+
+// only the cgb boot rom can write KEY0
+#[test]
+fn key0_is_cgb_only() {
+    let mut gb = boot(&cart(), stand_in(Model::DMG));
+    assert_eq!(gb.mother.get_mem_at(boot_rom::KEY0), 0x00);
+}
+
+#[test]
+fn cgb_mode_until_the_handover() {
+    let mut mother = Motherboard::new();
+    mother.load_rom(&color_cart(0));
+    mother.power_on(Some(cgb_boot_rom()), Model::CGB).unwrap();
+    assert!(mother.ppu.cgb);
+    assert!(!mother.ppu.compat);
+}
+
+#[test]
+fn dmg_cartridge_on_the_cgb_boot_rom() {
+    let mut gb = boot(&color_cart(0), cgb_boot_rom());
+    assert!(!gb.mother.ppu.cgb);
+    assert!(gb.mother.ppu.compat);
+    // the cgb registers are gone, the palettes the boot rom wrote stay
+    assert_eq!(gb.mother.get_mem_at(ppu::BCPD), 0xFF);
+    assert_eq!(gb.mother.ppu.bg_color(0, 3), 0x7FFF);
+    assert_eq!(gb.mother.ppu.obj_color(1, 3), 0x7FFF);
+
+    // the cartridge is drawn as on DMG, with BGP picking from palette 0
+    gb.run_frame();
+    gb.run_frame();
+    assert_eq!(gb.mother.ppu.rgb555()[0], BG[3]);
+}
+
+#[test]
+fn cgb_cartridge_on_the_cgb_boot_rom() {
+    let mut gb = boot(&color_cart(0x80), cgb_boot_rom());
+    assert!(gb.mother.ppu.cgb);
+    assert!(!gb.mother.ppu.compat);
+    gb.run_frame();
+    gb.run_frame();
+    // BGP means nothing in CGB mode, color 0 of palette 0 is drawn
+    assert_eq!(gb.mother.ppu.rgb555()[0], BG[0]);
+}
+
+#[test]
+fn dmg_cartridge_on_cgb_without_a_boot_rom() {
+    let mother = common::board(Model::CGB, &color_cart(0));
+    assert!(mother.ppu.compat);
+    for color in 0..4 {
+        assert_eq!(mother.ppu.bg_color(0, color), model::COMPAT_GREYS[color as usize]);
+        assert_eq!(mother.ppu.obj_color(1, color), model::COMPAT_GREYS[color as usize]);
+    }
+    let mut gb = GameBoy::new(mother);
+    gb.run_frame();
+    gb.run_frame();
+    assert_eq!(gb.mother.ppu.rgb555()[0], model::COMPAT_GREYS[3]);
+}
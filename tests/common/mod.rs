@@ -0,0 +1,56 @@
+// Fixtures shared by the integration tests. Each test binary builds its own
+// copy of this module and uses only some of it
+#![allow(dead_code)]
+
+use rustgb::asm;
+use rustgb::gameboy::GameBoy;
+use rustgb::model::Model;
+use rustgb::motherboard::Motherboard;
+use rustgb::ppu::{self, Mode};
+
+const CGB_FLAG: usize = 0x0143;
+
+// 32 KiB of `fill`, with the header asking for CGB mode when `cgb` is set
+pub fn cart(cgb: bool, fill: u8) -> Vec<u8> {
+    let mut rom = vec![fill; 0x8000];
+    rom[CGB_FLAG] = if cgb { 0x80 } else { 0 };
+    rom
+}
+
+// assembles `src` into `rom` at `addr`
+pub fn put_code(rom: &mut [u8], addr: u16, src: &str) {
+    let code = asm!(src, addr);
+    rom[addr as usize..addr as usize + code.len()].copy_from_slice(&code);
+}
+
+// `model` started on `rom` without a boot rom, ready to run from 0x0100
+pub fn board(model: Model, rom: &[u8]) -> Motherboard {
+    let mut mother = Motherboard::new();
+    mother.load_rom(rom);
+    mother.power_on(None, model).unwrap();
+    mother
+}
+
+// fills `len` bytes from 0xC000 counting up from 1
+pub fn count_up(mother: &mut Motherboard, len: u16) {
+    for i in 0..len {
+        mother.put_mem_at(0xC000 + i, i as u8 + 1);
+    }
+}
+
+// ticks until the ppu is in `mode` on a visible line
+pub fn run_to_mode(mother: &mut Motherboard, mode: Mode) {
+    while mother.ppu.mode() != mode || mother.ppu.line() >= ppu::VBLANK_LINE {
+        mother.tick(4);
+    }
+}
+
+// loads `src` at 0x0000 on a flat bus, with the stack at 0xD000
+pub fn load(src: &str) -> GameBoy {
+    let mut mother = Motherboard::new_flat();
+    for (i, byte) in asm!(src).into_iter().enumerate() {
+        mother.put_mem_at(i as u16, byte);
+    }
+    mother.cpu.sp = 0xD000;
+    GameBoy::new(mother)
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use common::load;
+use rustgb::common::RegBytes;
+use rustgb::cpu::{Flag, Reg};
+use rustgb::gameboy::GameBoy;
+use rustgb::motherboard::Motherboard;
+
+const Z: u8 = Flag::Z as u8;
+const N: u8 = Flag::N as u8;
+const H: u8 = Flag::H as u8;
+const C: u8 = Flag::C as u8;
+
+fn run(src: &str, steps: usize) -> GameBoy {
+    let mut gb = load(src);
+    for _ in 0..steps {
+        gb.step();
+    }
+    gb
+}
+
+fn a(gb: &GameBoy) -> u8 {
+    gb.mother.cpu.read_reg(Reg::A).get_single()
+}
+
+fn f(gb: &GameBoy) -> u8 {
+    gb.mother.cpu.read_reg(Reg::F).get_single()
+}
+
+fn reg16(gb: &GameBoy, reg: Reg) -> u16 {
+    gb.mother.cpu.read_reg(reg).get_double()
+}
+
+#[test]
+fn jr_is_signed_from_the_next_instruction() {
+    let mut gb = load("jr fwd\nnop\nfwd: jr fwd");
+    assert_eq!(gb.step(), 12);
+    assert_eq!(gb.mother.cpu.pc, 0x0003);
+    // jumping to itself is an offset of -2
+    gb.step();
+    assert_eq!(gb.mother.cpu.pc, 0x0003);
+    assert_eq!(gb.mother.get_mem_at(0x0004), 0xFE);
+}
+
+#[test]
+fn conditional_branch_cycles() {
+    let mut gb = load("xor a\njr nz, $10\njr z, skip\nnop\nskip: call nz, $1000\ncall z, $2000");
+    gb.step();
+    assert_eq!(gb.step(), 8);
+    assert_eq!(gb.mother.cpu.pc, 0x0003);
+    assert_eq!(gb.step(), 12);
+    assert_eq!(gb.mother.cpu.pc, 0x0006);
+    assert_eq!(gb.step(), 12);
+    assert_eq!(gb.step(), 24);
+    assert_eq!(gb.mother.cpu.pc, 0x2000);
+}
+
+#[test]
+fn branch_cycles() {
+    let mut gb = load("jp $0010");
+    gb.mother.put_mem_at(0x0010, 0xCD);
+    gb.mother.put_mem_at(0x0011, 0x20);
+    gb.mother.put_mem_at(0x0012, 0x00);
+    gb.mother.put_mem_at(0x0020, 0xC9);
+    gb.mother.put_mem_at(0x0013, 0xFF);
+    assert_eq!(gb.step(), 16);
+    assert_eq!(gb.step(), 24);
+    assert_eq!(gb.step(), 16);
+    assert_eq!(gb.mother.cpu.pc, 0x0013);
+    assert_eq!(gb.step(), 16);
+    assert_eq!(gb.mother.cpu.pc, 0x0038);
+}
+
+#[test]
+fn jp_hl_jumps_to_hl() {
+    let gb = run("ld hl, $1234\njp hl", 2);
+    assert_eq!(gb.mother.cpu.pc, 0x1234);
+}
+
+#[test]
+fn add_wraps_and_flags() {
+    let gb = run("ld a, $FF\nadd a, $01", 2);
+    assert_eq!(a(&gb), 0x00);
+    assert_eq!(f(&gb), Z | H | C);
+    let gb = run("ld a, $38\nadd a, $08", 2);
+    assert_eq!(a(&gb), 0x40);
+    assert_eq!(f(&gb), H);
+}
+
+#[test]
+fn adc_adds_carry() {
+    let gb = run("scf\nld a, $0E\nadc a, $01", 3);
+    assert_eq!(a(&gb), 0x10);
+    assert_eq!(f(&gb), H);
+}
+
+#[test]
+fn sub_and_cp() {
+    let gb = run("ld a, $10\nsub a, $01", 2);
+    assert_eq!(a(&gb), 0x0F);
+    assert_eq!(f(&gb), N | H);
+    let gb = run("ld a, $10\ncp a, $20", 2);
+    assert_eq!(a(&gb), 0x10);
+    assert_eq!(f(&gb), N | C);
+    let gb = run("ld a, $42\nld hl, $C000\nld [hl], $42\ncp a, [hl]", 4);
+    assert_eq!(f(&gb), Z | N);
+}
+
+#[test]
+fn sbc_subtracts_carry() {
+    let gb = run("scf\nld a, $00\nsbc a, $00", 3);
+    assert_eq!(a(&gb), 0xFF);
+    assert_eq!(f(&gb), N | H | C);
+    let gb = run("scf\nld a, $05\nld e, $02\nsbc a, e", 4);
+    assert_eq!(a(&gb), 0x02);
+    assert_eq!(f(&gb), N);
+    let gb = run("scf\nsbc a, a", 2);
+    assert_eq!(a(&gb), 0xFF);
+}
+
+#[test]
+fn logic_flags() {
+    let gb = run("ld a, $F0\nand a, $0F", 2);
+    assert_eq!(f(&gb), Z | H);
+    let gb = run("scf\nld a, $F0\nor a, $0F", 3);
+    assert_eq!(a(&gb), 0xFF);
+    assert_eq!(f(&gb), 0);
+    let gb = run("ld a, $5A\nxor a, $5A", 2);
+    assert_eq!(f(&gb), Z);
+}
+
+#[test]
+fn inc_dec_keep_carry() {
+    let gb = run("scf\nld b, $FF\ninc b", 3);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::B).get_single(), 0);
+    assert_eq!(f(&gb), Z | H | C);
+    let gb = run("ld b, $10\ndec b", 2);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::B).get_single(), 0x0F);
+    assert_eq!(f(&gb), N | H);
+    let gb = run("ld bc, $0000\ndec bc", 2);
+    assert_eq!(reg16(&gb, Reg::BC), 0xFFFF);
+    assert_eq!(f(&gb), 0);
+}
+
+#[test]
+fn add_hl_keeps_zero() {
+    let gb = run("xor a\nld hl, $8FFF\nld bc, $7001\nadd hl, bc", 4);
+    assert_eq!(reg16(&gb, Reg::HL), 0x0000);
+    assert_eq!(f(&gb), Z | H | C);
+}
+
+#[test]
+fn stack_pointer_offsets() {
+    let mut gb = load("add sp, -1\nld hl, sp+$01");
+    gb.mother.cpu.sp = 0x00FF;
+    gb.step();
+    assert_eq!(gb.mother.cpu.sp, 0x00FE);
+    assert_eq!(f(&gb), H | C);
+    gb.step();
+    assert_eq!(reg16(&gb, Reg::HL), 0x00FF);
+    assert_eq!(f(&gb), 0);
+    assert_eq!(gb.mother.cpu.sp, 0x00FE);
+}
+
+#[test]
+fn store_immediate() {
+    let mut gb = load("ld hl, $C000\nld [hl], $42");
+    gb.mother.put_mem_at(0xC001, 0x99);
+    gb.step();
+    gb.step();
+    assert_eq!(gb.mother.get_mem_at(0xC000), 0x42);
+    assert_eq!(gb.mother.get_mem_at(0xC001), 0x99);
+}
+
+#[test]
+fn store_sp() {
+    let mut gb = run("ld [$C000], sp", 1);
+    assert_eq!(gb.mother.get_mem_at(0xC000), 0x00);
+    assert_eq!(gb.mother.get_mem_at(0xC001), 0xD0);
+}
+
+#[test]
+fn daa_after_add_and_sub() {
+    let gb = run("ld a, $19\nadd a, $28\ndaa", 3);
+    assert_eq!(a(&gb), 0x47);
+    let gb = run("ld a, $99\nadd a, $01\ndaa", 3);
+    assert_eq!(a(&gb), 0x00);
+    assert_eq!(f(&gb), Z | C);
+    let gb = run("ld a, $20\nsub a, $01\ndaa", 3);
+    assert_eq!(a(&gb), 0x19);
+    assert_eq!(f(&gb), N);
+}
+
+#[test]
+fn carry_flag_ops() {
+    let gb = run("scf", 1);
+    assert_eq!(f(&gb), C);
+    let gb = run("scf\nccf", 2);
+    assert_eq!(f(&gb), 0);
+    let gb = run("ld a, $0F\ncpl", 2);
+    assert_eq!(a(&gb), 0xF0);
+    assert_eq!(f(&gb), N | H);
+}
+
+#[test]
+fn rotates() {
+    let gb = run("ld a, $80\nrlca", 2);
+    assert_eq!(a(&gb), 0x01);
+    assert_eq!(f(&gb), C);
+    let gb = run("ld a, $80\nrla", 2);
+    assert_eq!(a(&gb), 0x00);
+    // the A forms never set Z
+    assert_eq!(f(&gb), C);
+    let gb = run("scf\nld a, $01\nrra", 3);
+    assert_eq!(a(&gb), 0x80);
+    assert_eq!(f(&gb), C);
+    let gb = run("ld b, $80\nrl b", 2);
+    assert_eq!(f(&gb), Z | C);
+    let gb = run("ld c, $01\nrrc c", 2);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::C).get_single(), 0x80);
+    assert_eq!(f(&gb), C);
+}
+
+#[test]
+fn shifts() {
+    let gb = run("ld d, $81\nsra d", 2);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::D).get_single(), 0xC0);
+    assert_eq!(f(&gb), C);
+    let gb = run("ld d, $01\nsrl d", 2);
+    assert_eq!(f(&gb), Z | C);
+    let gb = run("ld d, $C0\nsla d", 2);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::D).get_single(), 0x80);
+    assert_eq!(f(&gb), C);
+    let gb = run("ld d, $00\nswap d", 2);
+    assert_eq!(f(&gb), Z);
+}
+
+#[test]
+fn bit_test() {
+    let gb = run("scf\nld e, $10\nbit 4, e", 3);
+    assert_eq!(f(&gb), H | C);
+    let gb = run("ld e, $10\nbit 3, e", 2);
+    assert_eq!(f(&gb), Z | H);
+    let mut gb = load("ld hl, $C000\nbit 0, [hl]");
+    gb.step();
+    assert_eq!(gb.step(), 12);
+}
+
+#[test]
+fn flags_low_nibble() {
+    let mut mother = Motherboard::new_flat();
+    mother.cpu.write_reg(Reg::AF, RegBytes::new_double(0x12FF));
+    assert_eq!(mother.cpu.read_reg(Reg::F).get_single(), 0xF0);
+}
+
+// the first register of a pair is its high byte
+#[test]
+fn register_pairs() {
+    let mut mother = Motherboard::new_flat();
+    mother.cpu.write_reg(Reg::BC, RegBytes::new_double(0x1234));
+    assert_eq!(mother.cpu.read_reg(Reg::B).get_single(), 0x12);
+    assert_eq!(mother.cpu.read_reg(Reg::C).get_single(), 0x34);
+    mother.cpu.write_reg(Reg::H, RegBytes::new_single(0xAB));
+    mother.cpu.write_reg(Reg::L, RegBytes::new_single(0xCD));
+    assert_eq!(mother.cpu.read_reg(Reg::HL).get_double(), 0xABCD);
+}
+
+#[test]
+fn pair_immediates() {
+    let mut gb = run("ld de, $BEEF\nld hl, $C000\nld [hl], d", 3);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::D).get_single(), 0xBE);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::E).get_single(), 0xEF);
+    assert_eq!(gb.mother.get_mem_at(0xC000), 0xBE);
+    assert_eq!(reg16(&gb, Reg::HL), 0xC000);
+}
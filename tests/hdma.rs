@@ -0,0 +1,98 @@
+mod common;
+
+use common::run_to_mode;
+use rustgb::hdma;
+use rustgb::model::Model;
+use rustgb::motherboard::Motherboard;
+use rustgb::ppu::{self, Mode};
+
+// a cgb running a cgb cartridge, with 0xC000-0xC02F counting up and an
+// HBlank transfer from there to 0x8000 set up but not started
+fn board() -> Motherboard {
+    let mut mother = common::board(Model::CGB, &common::cart(true, 0));
+    common::count_up(&mut mother, 0x30);
+    for (reg, val) in [(hdma::HDMA1, 0xC0), (hdma::HDMA2, 0x00), (hdma::HDMA3, 0x00), (hdma::HDMA4, 0x00)] {
+        mother.put_mem_at(reg, val);
+    }
+    mother
+}
+
+// how many of the first three blocks have landed in VRAM
+fn blocks_copied(mother: &Motherboard) -> usize {
+    (0..3).filter(|block| mother.ppu.read_vram(0x8000 + block * 0x10) != 0).count()
+}
+
+#[test]
+fn started_in_vblank_waits_for_hblank() {
+    let mut mother = board();
+    assert_eq!(mother.ppu.mode(), Mode::VBlank);
+    mother.put_mem_at(hdma::HDMA5, 0x82);
+    assert_eq!(blocks_copied(&mother), 0);
+    run_to_mode(&mut mother, Mode::HBlank);
+    assert_eq!(blocks_copied(&mother), 1);
+    assert_eq!(mother.get_mem_at(hdma::HDMA5), 0x01);
+}
+
+#[test]
+fn started_in_hblank_copies_at_once() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::HBlank);
+    mother.put_mem_at(hdma::HDMA5, 0x82);
+    assert_eq!(blocks_copied(&mother), 1);
+    assert_eq!(mother.ppu.read_vram(0x8000), 1);
+    assert_eq!(mother.ppu.read_vram(0x800F), 0x10);
+    // and the next comes with the next HBlank, not this one
+    run_to_mode(&mut mother, Mode::OamScan);
+    assert_eq!(blocks_copied(&mother), 1);
+    run_to_mode(&mut mother, Mode::HBlank);
+    assert_eq!(blocks_copied(&mother), 2);
+}
+
+#[test]
+fn started_with_the_lcd_off_copies_at_once() {
+    let mut mother = board();
+    mother.put_mem_at(ppu::LCDC, 0);
+    mother.put_mem_at(hdma::HDMA5, 0x82);
+    assert_eq!(blocks_copied(&mother), 1);
+    // no more HBlanks come while it stays off
+    for _ in 0..1000 {
+        mother.tick(4);
+    }
+    assert_eq!(blocks_copied(&mother), 1);
+    assert_eq!(mother.get_mem_at(hdma::HDMA5), 0x01);
+}
+
+#[test]
+fn not_started_in_mode_3() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::Transfer);
+    mother.put_mem_at(hdma::HDMA5, 0x82);
+    assert_eq!(blocks_copied(&mother), 0);
+    run_to_mode(&mut mother, Mode::HBlank);
+    assert_eq!(blocks_copied(&mother), 1);
+}
+
+#[test]
+fn cancel_and_readback() {
+    let mut mother = board();
+    mother.put_mem_at(hdma::HDMA5, 0x82);
+    run_to_mode(&mut mother, Mode::HBlank);
+    mother.put_mem_at(hdma::HDMA5, 0x00);
+    assert_eq!(mother.get_mem_at(hdma::HDMA5), 0x81);
+    for _ in 0..2 {
+        run_to_mode(&mut mother, Mode::OamScan);
+        run_to_mode(&mut mother, Mode::HBlank);
+    }
+    assert_eq!(blocks_copied(&mother), 1);
+}
+
+#[test]
+fn general_purpose_copies_everything() {
+    let mut mother = board();
+    mother.put_mem_at(hdma::HDMA5, 0x02);
+    assert_eq!(blocks_copied(&mother), 3);
+    assert_eq!(mother.ppu.read_vram(0x802F), 0x30);
+    assert_eq!(mother.get_mem_at(hdma::HDMA5), 0xFF);
+    // the cpu is held while it runs, 32 dots a block
+    assert_eq!(mother.run_stall(), 3 * 32);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::load;
+use rustgb::asm;
+use rustgb::gameboy::GameBoy;
+use rustgb::model::Model;
+use rustgb::motherboard::{Motherboard, IE, IF};
+
+#[test]
+fn dispatch_pushes_pc_and_jumps() {
+    let mut gb = load("ei\nnop\nnop");
+    gb.mother.put_mem_at(IE, 0x05);
+    gb.mother.put_mem_at(IF, 0x04);
+    gb.step();
+    // EI only takes effect after the next instruction
+    assert_eq!(gb.step(), 4);
+    assert_eq!(gb.mother.cpu.pc, 0x0002);
+    assert_eq!(gb.step(), 20);
+    assert_eq!(gb.mother.cpu.pc, 0x0050);
+    assert!(!gb.mother.cpu.ime);
+    assert_eq!(gb.mother.get_mem_at(IF), 0x00);
+    assert_eq!(gb.mother.cpu.sp, 0xCFFE);
+    assert_eq!(gb.mother.get_mem_at(0xCFFE), 0x02);
+}
+
+#[test]
+fn priority_goes_to_the_lowest_bit() {
+    let mut gb = load("nop");
+    gb.mother.cpu.ime = true;
+    gb.mother.put_mem_at(IE, 0x1F);
+    gb.mother.put_mem_at(IF, 0x0A);
+    gb.step();
+    assert_eq!(gb.mother.cpu.pc, 0x0048);
+    assert_eq!(gb.mother.get_mem_at(IF), 0x08);
+}
+
+#[test]
+fn masked_interrupts_wait() {
+    let mut gb = load("nop\nnop");
+    gb.mother.cpu.ime = true;
+    gb.mother.put_mem_at(IE, 0x01);
+    gb.mother.put_mem_at(IF, 0x02);
+    gb.step();
+    assert_eq!(gb.mother.cpu.pc, 0x0001);
+}
+
+#[test]
+fn di_cancels_a_pending_ei() {
+    let mut gb = load("ei\ndi\nnop\nnop");
+    gb.mother.put_mem_at(IE, 0x01);
+    gb.mother.put_mem_at(IF, 0x01);
+    for _ in 0..3 {
+        gb.step();
+    }
+    assert_eq!(gb.mother.cpu.pc, 0x0003);
+    assert!(!gb.mother.cpu.ime);
+}
+
+#[test]
+fn reti_returns_and_enables() {
+    let mut gb = load("reti");
+    gb.mother.push(0x1234);
+    gb.step();
+    assert_eq!(gb.mother.cpu.pc, 0x1234);
+    assert!(gb.mother.cpu.ime);
+}
+
+#[test]
+fn halt_waits_for_an_interrupt() {
+    let mut gb = load("halt\nnop");
+    gb.mother.put_mem_at(IE, 0x01);
+    gb.step();
+    assert!(gb.mother.cpu.halted);
+    assert_eq!(gb.step(), 4);
+    assert_eq!(gb.mother.cpu.pc, 0x0001);
+    // with IME clear it wakes without dispatching
+    gb.mother.put_mem_at(IF, 0x01);
+    gb.step();
+    assert!(!gb.mother.cpu.halted);
+    assert_eq!(gb.mother.cpu.pc, 0x0002);
+    assert_eq!(gb.mother.get_mem_at(IF), 0x01);
+}
+
+#[test]
+fn halt_then_dispatch() {
+    let mut gb = load("halt\nnop");
+    gb.mother.cpu.ime = true;
+    gb.mother.put_mem_at(IE, 0x01);
+    gb.step();
+    gb.mother.put_mem_at(IF, 0x01);
+    assert_eq!(gb.step(), 20);
+    assert_eq!(gb.mother.cpu.pc, 0x0040);
+    assert_eq!(gb.mother.get_mem_at(0xCFFE), 0x01);
+}
+
+#[test]
+fn push_onto_ie_cancels() {
+    let mut gb = load("nop");
+    gb.mother.cpu.ime = true;
+    gb.mother.cpu.sp = 0x0000;
+    gb.mother.cpu.pc = 0x0200;
+    gb.mother.put_mem_at(IE, 0x01);
+    gb.mother.put_mem_at(IF, 0x01);
+    gb.step();
+    // pc's high byte, 0x02, replaced IE
+    assert_eq!(gb.mother.cpu.pc, 0x0000);
+}
+
+// the timer's request goes through IF to the handler at 0x0050
+#[test]
+fn timer_interrupt_is_serviced() {
+    let mut rom = vec![0; 0x8000];
+    let main = asm!("ld a, $04\nldh [$FF], a\nld a, $05\nldh [$07], a\nld a, $FF\nldh [$05], a\nei\nloop: halt\njr loop", 0x0100);
+    rom[0x0100..0x0100 + main.len()].copy_from_slice(&main);
+    let handler = asm!("ld a, $42\nld [$C000], a\nreti", 0x0050);
+    rom[0x0050..0x0050 + handler.len()].copy_from_slice(&handler);
+    let mut mother = Motherboard::new();
+    mother.load_rom(&rom);
+    mother.skip_boot(Model::DMG);
+    mother.put_mem_at(IF, 0);
+    let mut gb = GameBoy::new(mother);
+    while gb.cycles < 1000 {
+        gb.step();
+    }
+    assert_eq!(gb.mother.get_mem_at(0xC000), 0x42);
+    assert_eq!(gb.mother.get_mem_at(IF) & 0x04, 0x00);
+}
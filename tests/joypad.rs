@@ -0,0 +1,251 @@
+mod common;
+
+use common::{board, cart, put_code};
+use rustgb::cpu::Reg;
+use rustgb::gameboy::GameBoy;
+use rustgb::hdma::HDMA5;
+use rustgb::joypad::{Button, ButtonState, P1};
+use rustgb::model::Model;
+use rustgb::motherboard::{Interrupt, Motherboard, IF};
+use rustgb::timer::DIV;
+
+// `model` running `main` from 0x0100, with a joypad handler that loads B
+fn boot(model: Model, main: &str) -> GameBoy {
+    let mut rom = cart(model.is_cgb(), 0);
+    put_code(&mut rom, 0x0100, main);
+    put_code(&mut rom, 0x0060, "ld b, $42\njr @");
+    GameBoy::new(board(model, &rom))
+}
+
+fn joypad_requested(mother: &mut Motherboard) -> bool {
+    mother.get_mem_at(IF) & Interrupt::Joypad as u8 != 0
+}
+
+fn clear_if(mother: &mut Motherboard) {
+    mother.put_mem_at(IF, 0);
+}
+
+#[test]
+fn select_lines() {
+    let mut mother = boot(Model::DMG, "jr @").mother;
+    mother.press(Button::A);
+    mother.press(Button::Down);
+    // nothing selected reads all lines high
+    mother.put_mem_at(P1, 0x30);
+    assert_eq!(mother.get_mem_at(P1), 0xFF);
+    // active low: A is line 0, Down line 3
+    mother.put_mem_at(P1, 0x10);
+    assert_eq!(mother.get_mem_at(P1), 0xDE);
+    mother.put_mem_at(P1, 0x20);
+    assert_eq!(mother.get_mem_at(P1), 0xE7);
+    mother.put_mem_at(P1, 0x00);
+    assert_eq!(mother.get_mem_at(P1), 0xC6);
+    mother.release(Button::A);
+    assert_eq!(mother.get_mem_at(P1), 0xC7);
+    // only the select bits are writable
+    mother.put_mem_at(P1, 0xFF);
+    assert_eq!(mother.get_mem_at(P1), 0xFF);
+}
+
+#[test]
+fn set_buttons() {
+    let mut mother = boot(Model::DMG, "jr @").mother;
+    let mut buttons = ButtonState::new();
+    buttons.press(Button::Start);
+    buttons.press(Button::Left);
+    mother.set_buttons(buttons);
+    assert!(mother.joypad.buttons().is_pressed(Button::Start));
+    assert!(!mother.joypad.buttons().is_pressed(Button::Select));
+    mother.put_mem_at(P1, 0x10);
+    assert_eq!(mother.get_mem_at(P1), 0xD7);
+    mother.put_mem_at(P1, 0x20);
+    assert_eq!(mother.get_mem_at(P1), 0xED);
+    mother.set_buttons(ButtonState::new());
+    assert_eq!(mother.get_mem_at(P1), 0xEF);
+}
+
+#[test]
+fn press_requests_the_interrupt() {
+    let mut mother = boot(Model::DMG, "jr @").mother;
+    mother.put_mem_at(P1, 0x10);
+    clear_if(&mut mother);
+    // a button of the group that isn't selected pulls no line low
+    mother.press(Button::Up);
+    assert!(!joypad_requested(&mut mother));
+    mother.press(Button::B);
+    assert!(joypad_requested(&mut mother));
+    // releasing is a rising edge
+    clear_if(&mut mother);
+    mother.release(Button::B);
+    assert!(!joypad_requested(&mut mother));
+    // a second button on a line already low doesn't fall again
+    mother.put_mem_at(P1, 0x00);
+    clear_if(&mut mother);
+    mother.press(Button::Select);
+    mother.press(Button::Left);
+    clear_if(&mut mother);
+    mother.press(Button::B);
+    assert!(!joypad_requested(&mut mother));
+}
+
+#[test]
+fn falling_edge_on_reselect() {
+    let mut mother = boot(Model::DMG, "jr @").mother;
+    mother.put_mem_at(P1, 0x30);
+    mother.press(Button::Start);
+    clear_if(&mut mother);
+    // the held button's line falls once its group is selected
+    mother.put_mem_at(P1, 0x20);
+    assert!(!joypad_requested(&mut mother));
+    mother.put_mem_at(P1, 0x10);
+    assert!(joypad_requested(&mut mother));
+    clear_if(&mut mother);
+    mother.put_mem_at(P1, 0x10);
+    assert!(!joypad_requested(&mut mother));
+    // switching away and back again
+    mother.put_mem_at(P1, 0x20);
+    mother.put_mem_at(P1, 0x10);
+    assert!(joypad_requested(&mut mother));
+}
+
+// selects the buttons, enables only the joypad interrupt and stops
+const STOP: &str = "ld a, $10
+    ldh [$FF00], a
+    ldh [$FFFF], a
+    xor a
+    ldh [$FF0F], a
+    ei
+    stop
+    nop
+    jr @";
+
+fn stopped(model: Model) -> GameBoy {
+    let mut gb = boot(model, STOP);
+    while !gb.mother.stopped() {
+        gb.step();
+    }
+    gb
+}
+
+#[test]
+fn stop_waits_for_a_press() {
+    let mut gb = stopped(Model::DMG);
+    let pc = gb.mother.cpu.pc;
+    for _ in 0..10_000 {
+        gb.step();
+    }
+    assert!(gb.mother.stopped());
+    assert_eq!(gb.mother.cpu.pc, pc);
+    // a d-pad button isn't selected
+    gb.mother.press(Button::Right);
+    assert!(gb.mother.stopped());
+    gb.mother.press(Button::A);
+    assert!(!gb.mother.stopped());
+    assert!(joypad_requested(&mut gb.mother));
+}
+
+#[test]
+fn wake_services_the_interrupt() {
+    let mut gb = stopped(Model::DMG);
+    gb.mother.press(Button::Start);
+    for _ in 0..4 {
+        gb.step();
+    }
+    assert_eq!(gb.mother.cpu.read_reg(Reg::B).get_single(), 0x42);
+    assert!(!joypad_requested(&mut gb.mother));
+    assert_eq!(gb.mother.cpu.pc, 0x0062);
+}
+
+#[test]
+fn held_button_skips_stop() {
+    let mut gb = boot(Model::DMG, STOP);
+    gb.mother.press(Button::A);
+    for _ in 0..8 {
+        gb.step();
+        assert!(!gb.mother.stopped());
+    }
+}
+
+#[test]
+fn stop_freezes_the_hardware() {
+    let mut gb = stopped(Model::DMG);
+    let (line, dot) = (gb.mother.ppu.line(), gb.mother.ppu.dot());
+    let div = gb.mother.get_mem_at(DIV);
+    for _ in 0..100_000 {
+        gb.step();
+    }
+    assert_eq!((gb.mother.ppu.line(), gb.mother.ppu.dot()), (line, dot));
+    assert_eq!(gb.mother.get_mem_at(DIV), div);
+    gb.mother.press(Button::A);
+    gb.step();
+    assert_ne!((gb.mother.ppu.line(), gb.mother.ppu.dot()), (line, dot));
+}
+
+#[test]
+fn stop_freezes_oam_dma() {
+    let mut gb = boot(
+        Model::DMG,
+        "ld a, $10
+        ldh [$FF00], a
+        ld a, $80
+        ldh [$FF46], a
+        nop
+        nop
+        nop
+        nop
+        stop
+        nop
+        jr @",
+    );
+    // from VRAM, which leaves the cpu's bus to the rom free
+    for i in 0..0xA0 {
+        gb.mother.poke(0x8000 + i, 0xA5);
+    }
+    while !gb.mother.stopped() {
+        gb.step();
+    }
+    for _ in 0..1000 {
+        gb.step();
+    }
+    let copied = gb.mother.ppu.oam().iter().filter(|val| **val == 0xA5).count();
+    assert!(copied > 0 && copied < 0xA0, "{}", copied);
+    gb.mother.press(Button::A);
+    for _ in 0..200 {
+        gb.step();
+    }
+    assert!(gb.mother.ppu.oam().iter().all(|val| *val == 0xA5));
+}
+
+#[test]
+fn stop_freezes_hblank_dma() {
+    // four blocks from 0xC000 to 0x8000, one an HBlank
+    let mut gb = boot(
+        Model::CGB,
+        "ld a, $10
+        ldh [$FF00], a
+        ld a, $C0
+        ldh [$FF51], a
+        xor a
+        ldh [$FF52], a
+        ldh [$FF53], a
+        ldh [$FF54], a
+        ld a, $83
+        ldh [$FF55], a
+        stop
+        nop
+        jr @",
+    );
+    while !gb.mother.stopped() {
+        gb.step();
+    }
+    let left = gb.mother.get_mem_at(HDMA5);
+    assert_ne!(left, 0xFF);
+    for _ in 0..100_000 {
+        gb.step();
+    }
+    assert_eq!(gb.mother.get_mem_at(HDMA5), left);
+    gb.mother.press(Button::A);
+    while gb.mother.get_mem_at(HDMA5) != 0xFF {
+        gb.step();
+    }
+}
@@ -0,0 +1,57 @@
+use rustgb::mooneye;
+use rustgb::ppu::Renderer;
+use rustgb::render::{HEIGHT, WIDTH};
+use rustgb::runner;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+// shades 0-3 of a screenshot in the four DMG grays
+fn load_shades(path: &Path) -> Vec<u8> {
+    let mut decoder = png::Decoder::new(File::open(path).unwrap());
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert_eq!((info.width as usize, info.height as usize), (WIDTH, HEIGHT), "{}", path.display());
+    buf.chunks(info.color_type.samples())
+        .take(WIDTH * HEIGHT)
+        .map(|pixel| 3 - ((pixel[0] as u16 + 0x2A) / 0x55) as u8)
+        .collect()
+}
+
+// point MEALYBUG_ROMS at a directory of built mealybug-tearoom-tests roms,
+// each with its expected DMG screenshot next to it as <name>.png
+#[test]
+fn mealybug_tearoom() {
+    let dir = match env::var_os("MEALYBUG_ROMS") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            eprintln!("MEALYBUG_ROMS not set, skipping");
+            return;
+        },
+    };
+    let mut checked = 0;
+    let mut failed = Vec::new();
+    let roms = runner::find_roms(&dir).unwrap();
+    for path in &roms {
+        let expected = path.with_extension("png");
+        if !expected.exists() {
+            println!("no screenshot for {}", path.display());
+            continue;
+        }
+        let mut gb = runner::load(&fs::read(path).unwrap(), None);
+        gb.mother.ppu.renderer = Renderer::Fifo;
+        let result = mooneye::run(&mut gb, mooneye::DEFAULT_CYCLE_BUDGET);
+
+        checked += 1;
+        let matches = result.regs.is_some() && gb.mother.ppu.framebuffer() == load_shades(&expected).as_slice();
+        println!("{} {}", if matches { "pass" } else { "FAIL" }, path.display());
+        if !matches {
+            failed.push(path.clone());
+        }
+    }
+    println!("{}/{} matched", checked - failed.len(), checked);
+    assert!(failed.is_empty());
+}
@@ -0,0 +1,44 @@
+use rustgb::asm;
+use rustgb::cpu::Reg;
+use rustgb::gameboy::GameBoy;
+use rustgb::motherboard::Motherboard;
+
+fn run(src: &str, steps: usize) -> GameBoy {
+    let mut mother = Motherboard::new();
+    mother.load_rom(&asm!(src));
+    let mut gb = GameBoy::new(mother);
+    gb.mother.cpu.pc = 0;
+    for _ in 0..steps {
+        gb.step();
+    }
+    gb
+}
+
+// ldh (n) and (c) take their 8 bit operand as an offset into 0xFF00
+#[test]
+fn high_page_operands() {
+    let mut gb = run("ld a, $42\nldh ($80), a\nld c, $81\ninc a\nld ($FF00+c), a\nldh a, ($80)", 6);
+    assert_eq!(gb.mother.get_mem_at(0xFF80), 0x42);
+    assert_eq!(gb.mother.get_mem_at(0xFF81), 0x43);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::A).get_single(), 0x42);
+}
+
+// c = $FF reaches IE, the last byte of the page
+#[test]
+fn high_page_top() {
+    let gb = run("ld a, $99\nld ($FFFF), a\nld c, $FF\nld a, ($FF00+c)", 4);
+    assert_eq!(gb.mother.cpu.read_reg(Reg::A).get_single(), 0x99);
+}
+
+// ldh a, (n) reads DIV in its last m-cycle, 8 cycles after it starts
+#[test]
+fn io_reads_see_the_access_cycle() {
+    let mut mother = Motherboard::new();
+    mother.load_rom(&asm!("ldh a, ($04)"));
+    let mut gb = GameBoy::new(mother);
+    gb.mother.cpu.pc = 0;
+    gb.mother.timer.set_counter(0x00F8);
+    gb.step();
+    assert_eq!(gb.mother.cpu.read_reg(Reg::A).get_single(), 0x01);
+    assert_eq!(gb.mother.timer.counter(), 0x0104);
+}
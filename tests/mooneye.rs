@@ -0,0 +1,90 @@
+use rustgb::asm;
+use rustgb::mooneye;
+use rustgb::runner::{self, Outcome};
+
+use std::env;
+use std::path::PathBuf;
+
+// point MOONEYE_ROMS at a directory of built Mooneye test roms (for example
+// the acceptance folder) to run these
+#[test]
+fn mooneye_roms() {
+    let dir = match env::var_os("MOONEYE_ROMS") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            eprintln!("MOONEYE_ROMS not set, skipping");
+            return;
+        },
+    };
+    let results = mooneye::run_dir(&dir, None, mooneye::DEFAULT_CYCLE_BUDGET).unwrap();
+    for (path, result) in &results {
+        println!("{:?} {}", result.outcome, path.display());
+    }
+    let passed = results.iter().filter(|(_, result)| result.outcome == Outcome::Passed).count();
+    println!("{}/{} passed", passed, results.len());
+    assert_eq!(passed, results.len());
+}
+
+// a rom loading `regs` into B,C,D,E,H,L and hitting the breakpoint after
+// `delay` nops
+fn signature_rom(regs: [u8; 6], delay: usize) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x0100..0x0103].copy_from_slice(&asm!("jp $0150", 0x0100));
+    let main = asm!(
+        &format!(
+            "ld b, {}\nld c, {}\nld d, {}\nld e, {}\nld h, {}\nld l, {}\n{}ld b, b\nloop: jr loop",
+            regs[0],
+            regs[1],
+            regs[2],
+            regs[3],
+            regs[4],
+            regs[5],
+            "nop\n".repeat(delay)
+        ),
+        0x0150
+    );
+    rom[0x0150..0x0150 + main.len()].copy_from_slice(&main);
+    rom
+}
+
+#[test]
+fn breakpoint_with_pass_signature() {
+    let result = mooneye::run_rom(&signature_rom(mooneye::PASS_SIGNATURE, 0), None, mooneye::DEFAULT_CYCLE_BUDGET);
+    assert_eq!(result.outcome, Outcome::Passed);
+    assert_eq!(result.regs, Some(mooneye::PASS_SIGNATURE));
+}
+
+#[test]
+fn breakpoint_with_fail_signature() {
+    let result = mooneye::run_rom(&signature_rom(mooneye::FAIL_SIGNATURE, 10), None, mooneye::DEFAULT_CYCLE_BUDGET);
+    assert_eq!(result.outcome, Outcome::Failed);
+    assert_eq!(result.regs, Some(mooneye::FAIL_SIGNATURE));
+}
+
+#[test]
+fn breakpoint_with_other_signature() {
+    let result = mooneye::run_rom(&signature_rom([1, 2, 3, 4, 5, 6], 0), None, mooneye::DEFAULT_CYCLE_BUDGET);
+    assert_eq!(result.outcome, Outcome::Crashed("breakpoint with unknown signature [01, 02, 03, 04, 05, 06]".to_string()));
+    assert_eq!(result.regs, Some([1, 2, 3, 4, 5, 6]));
+}
+
+#[test]
+fn no_breakpoint_times_out() {
+    let mut rom = vec![0; 0x8000];
+    rom[0x0100..0x0102].copy_from_slice(&asm!("jr @", 0x0100));
+    let result = mooneye::run_rom(&rom, None, 10_000);
+    assert_eq!(result.outcome, Outcome::Timeout);
+    assert_eq!(result.regs, None);
+}
+
+#[test]
+fn breakpoint_is_flagged_on_gameboy() {
+    let mut gb = runner::load(&signature_rom(mooneye::PASS_SIGNATURE, 0), None);
+    for _ in 0..7 {
+        gb.step();
+        assert!(!gb.breakpoint);
+    }
+    gb.step();
+    assert!(gb.breakpoint);
+    assert_eq!(gb.mother.cpu.pc, 0x015D);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use rustgb::common::RegBytes;
+use rustgb::cpu::Reg;
+use rustgb::gameboy::GameBoy;
+use rustgb::model::Model;
+
+// `model` about to run `code` at 0x0100 with HL at 0xFE00 and each OAM byte
+// holding its offset, stopped at `dot` of line 1
+fn boot(model: Model, code: &str, dot: u16) -> GameBoy {
+    let mut rom = common::cart(false, 0);
+    common::put_code(&mut rom, 0x0100, code);
+    let mut mother = common::board(model, &rom);
+    mother.cpu.write_reg(Reg::HL, RegBytes::new_double(0xFE00));
+    mother.cpu.sp = 0xFE20;
+    for i in 0..0xA0 {
+        mother.poke(0xFE00 + i, i as u8);
+    }
+    while mother.ppu.line() != 1 || mother.ppu.dot() != dot {
+        mother.tick(4);
+    }
+    GameBoy::new(mother)
+}
+
+fn oam(gb: &GameBoy) -> Vec<u8> {
+    gb.mother.ppu.oam().to_vec()
+}
+
+fn untouched() -> Vec<u8> {
+    (0..0xA0).map(|i| i as u8).collect()
+}
+
+#[test]
+fn inc_hl_in_oam_scan() {
+    // the access lands 4 dots in, as the ppu reads row 10
+    let mut gb = boot(Model::DMG, "inc hl", 36);
+    gb.step();
+    let oam = oam(&gb);
+    assert_ne!(oam[80..88], untouched()[80..88]);
+    assert_eq!(oam[82..88], untouched()[74..80]);
+    assert_eq!(oam[..80], untouched()[..80]);
+    assert_eq!(oam[88..], untouched()[88..]);
+}
+
+#[test]
+fn outside_oam_scan() {
+    let mut gb = boot(Model::DMG, "inc hl", 100);
+    gb.step();
+    assert_eq!(oam(&gb), untouched());
+}
+
+#[test]
+fn other_addresses() {
+    let mut gb = boot(Model::DMG, "inc bc\ninc de", 36);
+    gb.step();
+    gb.step();
+    assert_eq!(oam(&gb), untouched());
+}
+
+#[test]
+fn fixed_on_cgb() {
+    let mut gb = boot(Model::CGB, "inc hl", 36);
+    while gb.mother.ppu.line() != 1 || gb.mother.ppu.dot() != 36 {
+        gb.mother.tick(4);
+    }
+    gb.step();
+    assert_eq!(oam(&gb), untouched());
+}
+
+#[test]
+fn push_and_pop() {
+    let mut gb = boot(Model::DMG, "push bc", 20);
+    gb.step();
+    assert_ne!(oam(&gb), untouched());
+    let mut gb = boot(Model::DMG, "pop bc", 20);
+    gb.step();
+    assert_ne!(oam(&gb), untouched());
+}
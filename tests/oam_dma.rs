@@ -0,0 +1,74 @@
+mod common;
+
+use rustgb::model::Model;
+use rustgb::motherboard::Motherboard;
+use rustgb::oam_dma;
+use rustgb::ppu;
+
+// `model` with the lcd off, so only the DMA locks OAM, 0xC000-0xC09F
+// counting up from 1, and rom filled with 0x77
+fn board(model: Model) -> Motherboard {
+    let mut mother = common::board(model, &common::cart(model.is_cgb(), 0x77));
+    mother.put_mem_at(ppu::LCDC, 0);
+    common::count_up(&mut mother, oam_dma::OAM_BYTES as u16);
+    mother.put_mem_at(0xFF80, 0x42);
+    mother
+}
+
+// starts a transfer from 0xC000 and runs `cycles` m-cycles of it
+fn start(mother: &mut Motherboard, cycles: u32) {
+    mother.put_mem_at(oam_dma::DMA, 0xC0);
+    for _ in 0..cycles {
+        mother.tick(4);
+    }
+}
+
+#[test]
+fn copies_into_oam() {
+    let mut mother = board(Model::DMG);
+    start(&mut mother, 162);
+    assert!(!mother.oam_dma.transferring());
+    assert_eq!(mother.get_mem_at(0xFE00), 1);
+    assert_eq!(mother.get_mem_at(0xFE9F), 0xA0);
+}
+
+#[test]
+fn oam_is_locked() {
+    let mut mother = board(Model::DMG);
+    start(&mut mother, 10);
+    assert_eq!(mother.get_mem_at(0xFE00), 0xFF);
+    mother.put_mem_at(0xFE50, 0x99);
+    start(&mut mother, 162);
+    assert_eq!(mother.get_mem_at(0xFE50), 0x51);
+}
+
+#[test]
+fn bus_conflict_on_dmg() {
+    let mut mother = board(Model::DMG);
+    start(&mut mother, 5);
+    // the byte being copied, whatever is asked for on the external bus,
+    // work ram and rom alike
+    let copying = mother.oam_dma.last();
+    assert_eq!(copying, 3);
+    assert_eq!(mother.get_mem_at(0xC050), copying);
+    assert_eq!(mother.get_mem_at(0x1234), copying);
+    // VRAM and HRAM are off that bus
+    assert_eq!(mother.get_mem_at(0xFF80), 0x42);
+    assert_eq!(mother.get_mem_at(0x8000), 0);
+}
+
+#[test]
+fn work_ram_has_its_own_bus_on_cgb() {
+    let mut mother = board(Model::CGB);
+    start(&mut mother, 5);
+    assert_eq!(mother.get_mem_at(0xC050), mother.oam_dma.last());
+    assert_eq!(mother.get_mem_at(0x1234), 0x77);
+}
+
+#[test]
+fn no_conflict_before_it_starts() {
+    let mut mother = board(Model::DMG);
+    start(&mut mother, 1);
+    assert!(!mother.oam_dma.transferring());
+    assert_eq!(mother.get_mem_at(0xC050), 0x51);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::run_to_mode;
+use rustgb::model::Model;
+use rustgb::motherboard::Motherboard;
+use rustgb::ppu::{self, Mode};
+
+fn board() -> Motherboard {
+    let mut mother = common::board(Model::DMG, &common::cart(false, 0));
+    mother.poke(0x8000, 0x11);
+    mother.poke(0xFE00, 0x22);
+    mother
+}
+
+#[test]
+fn oam_scan_locks_oam() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::OamScan);
+    assert_eq!(mother.get_mem_at(0x8000), 0x11);
+    assert_eq!(mother.get_mem_at(0xFE00), 0xFF);
+    mother.put_mem_at(0xFE00, 0x33);
+    mother.put_mem_at(0x8000, 0x44);
+    assert_eq!(mother.peek(0xFE00), 0x22);
+    assert_eq!(mother.peek(0x8000), 0x44);
+}
+
+#[test]
+fn transfer_locks_both() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::Transfer);
+    assert_eq!(mother.get_mem_at(0x8000), 0xFF);
+    assert_eq!(mother.get_mem_at(0xFE00), 0xFF);
+    mother.put_mem_at(0x8000, 0x44);
+    mother.put_mem_at(0xFE00, 0x33);
+    assert_eq!(mother.peek(0x8000), 0x11);
+    assert_eq!(mother.peek(0xFE00), 0x22);
+}
+
+#[test]
+fn hblank_and_vblank_are_open() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::HBlank);
+    assert_eq!(mother.get_mem_at(0x8000), 0x11);
+    assert_eq!(mother.get_mem_at(0xFE00), 0x22);
+    while mother.ppu.mode() != Mode::VBlank {
+        mother.tick(4);
+    }
+    mother.put_mem_at(0x8000, 0x44);
+    mother.put_mem_at(0xFE00, 0x33);
+    assert_eq!(mother.get_mem_at(0x8000), 0x44);
+    assert_eq!(mother.get_mem_at(0xFE00), 0x33);
+}
+
+#[test]
+fn lcd_off_is_open() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::Transfer);
+    mother.put_mem_at(ppu::LCDC, 0);
+    assert_eq!(mother.get_mem_at(0x8000), 0x11);
+    assert_eq!(mother.get_mem_at(0xFE00), 0x22);
+}
+
+#[test]
+fn inspection_sees_through() {
+    let mut mother = board();
+    run_to_mode(&mut mother, Mode::Transfer);
+    mother.poke(0x8001, 0x55);
+    assert_eq!(mother.peek(0x8001), 0x55);
+    assert_eq!(mother.peek(0xFE00), 0x22);
+}
@@ -0,0 +1,62 @@
+use rustgb::motherboard::{BankSelect, Motherboard};
+
+const BANK_SIZE: usize = 0x4000;
+
+// a rom whose banks are filled with their own numbers
+fn board(banks: usize) -> Motherboard {
+    let rom: Vec<u8> = (0..banks * BANK_SIZE).map(|i| (i / BANK_SIZE) as u8).collect();
+    let mut mother = Motherboard::new();
+    mother.load_rom(&rom);
+    mother
+}
+
+#[test]
+fn switchable_bank_starts_at_one() {
+    let mut mother = board(4);
+    assert_eq!(mother.get_mem_at(0x0000), 0);
+    assert_eq!(mother.get_mem_at(0x3FFF), 0);
+    assert_eq!(mother.get_mem_at(0x4000), 1);
+    assert_eq!(mother.get_mem_at(0x7FFF), 1);
+}
+
+#[test]
+fn bank_select() {
+    let mut mother = board(4);
+    mother.put_mem_at(0x2000, 3);
+    assert_eq!(mother.get_mem_at(0x4000), 3);
+    assert_eq!(mother.get_mem_at(0x0000), 0);
+    // anywhere in 0x2000-0x3FFF selects
+    mother.put_mem_at(0x3FFF, 2);
+    assert_eq!(mother.get_mem_at(0x5000), 2);
+}
+
+#[test]
+fn bank_zero_selects_one() {
+    let mut mother = board(4);
+    mother.put_mem_at(0x2000, 3);
+    mother.put_mem_at(0x2000, 0);
+    assert_eq!(mother.get_mem_at(0x4000), 1);
+}
+
+#[test]
+fn bank_wraps_to_rom_size() {
+    let mut mother = board(4);
+    mother.put_mem_at(0x2000, 6);
+    assert_eq!(mother.get_mem_at(0x4000), 2);
+    // only the low five bits are the bank number
+    mother.put_mem_at(0x2000, 0x23);
+    assert_eq!(mother.get_mem_at(0x4000), 3);
+}
+
+// MBC1 only sees the low 5 bits, where a whole byte select sees them all
+#[test]
+fn byte_bank_select() {
+    let mut mother = board(0x40);
+    mother.put_mem_at(0x2000, 0x21);
+    assert_eq!(mother.get_mem_at(0x4000), 0x01);
+    mother.bank_select = BankSelect::Byte;
+    mother.put_mem_at(0x2000, 0x21);
+    assert_eq!(mother.get_mem_at(0x4000), 0x21);
+    mother.put_mem_at(0x2000, 0x00);
+    assert_eq!(mother.get_mem_at(0x4000), 0x01);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use rustgb::clock::KEY1;
+use rustgb::model::Model;
+use rustgb::motherboard::{Interrupt, Motherboard, IF};
+use rustgb::serial::{LinkPartner, SB, SC};
+
+// a partner on the other end that starts a transfer of 0x5A as soon as asked
+struct Clocking;
+
+impl LinkPartner for Clocking {
+    fn exchange(&mut self, _out: u8) -> u8 {
+        0x5A
+    }
+
+    fn clock_in(&mut self, _out: u8) -> Option<u8> {
+        Some(0x5A)
+    }
+}
+
+fn board(model: Model) -> Motherboard {
+    let mut mother = common::board(model, &common::cart(model.is_cgb(), 0));
+    mother.serial.set_partner(Box::new(Clocking));
+    mother.serial.start_output();
+    mother.put_mem_at(IF, 0);
+    mother
+}
+
+// cpu cycles until the serial interrupt is requested
+fn transfer(mother: &mut Motherboard) -> u32 {
+    let mut cycles = 0;
+    while mother.get_mem_at(IF) & Interrupt::Serial as u8 == 0 {
+        assert!(mother.serial.output().is_empty());
+        mother.tick(4);
+        cycles += 4;
+        assert!(cycles < 100_000);
+    }
+    cycles
+}
+
+#[test]
+fn internal_clock() {
+    let mut mother = board(Model::DMG);
+    mother.put_mem_at(SB, 0x3C);
+    mother.put_mem_at(SC, 0x81);
+    assert_eq!(transfer(&mut mother), 8 * 512);
+    assert_eq!(mother.get_mem_at(SB), 0x5A);
+    assert_eq!(mother.get_mem_at(SC), 0x7F);
+    assert_eq!(mother.serial.output(), [0x3C]);
+}
+
+#[test]
+fn external_clock() {
+    let mut mother = board(Model::DMG);
+    mother.put_mem_at(SB, 0x3C);
+    mother.put_mem_at(SC, 0x80);
+    assert_eq!(transfer(&mut mother), 8 * 512);
+    assert_eq!(mother.serial.output(), [0x3C]);
+}
+
+#[test]
+fn double_speed() {
+    let mut mother = board(Model::CGB);
+    mother.put_mem_at(KEY1, 0x01);
+    mother.stop();
+    mother.run_stall();
+    assert!(mother.clock.double_speed());
+    mother.put_mem_at(IF, 0);
+    // the cpu's clock, doubled along with it
+    mother.put_mem_at(SC, 0x81);
+    assert_eq!(transfer(&mut mother), 8 * 512);
+    // the partner's, which isn't
+    mother.put_mem_at(IF, 0);
+    mother.serial.start_output();
+    mother.put_mem_at(SC, 0x80);
+    assert_eq!(transfer(&mut mother), 2 * 8 * 512);
+    // and the CGB's fast clock
+    mother.put_mem_at(IF, 0);
+    mother.serial.start_output();
+    mother.put_mem_at(SC, 0x83);
+    assert_eq!(transfer(&mut mother), 8 * 16);
+}
@@ -0,0 +1,20 @@
+use rustgb::single_step;
+
+use std::env;
+use std::path::PathBuf;
+
+// point SM83_TESTS at a local copy of the sm83 single step tests (the
+// directory holding 00.json ... cb ff.json) to run these
+#[test]
+fn sm83_single_step() {
+    let dir = match env::var_os("SM83_TESTS") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            eprintln!("SM83_TESTS not set, skipping");
+            return;
+        },
+    };
+    let report = single_step::run_dir(&dir).unwrap();
+    print!("{}", report.summary());
+    assert!(report.all_passed());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use rustgb::clock::{self, KEY1};
+use rustgb::gameboy::GameBoy;
+use rustgb::model::Model;
+use rustgb::timer::DIV;
+
+// `model` running `main` from 0x0100, on a cgb cartridge when `cgb` is set
+fn boot(model: Model, cgb: bool, main: &str) -> GameBoy {
+    let mut rom = common::cart(cgb, 0);
+    common::put_code(&mut rom, 0x0100, main);
+    GameBoy::new(common::board(model, &rom))
+}
+
+const SWITCH: &str = "ld a, $01\nldh [$4D], a\nstop\nnop";
+
+#[test]
+fn stop_switches_speed_after_a_delay() {
+    let mut gb = boot(Model::CGB, true, SWITCH);
+    gb.step();
+    gb.step();
+    assert_eq!(gb.mother.get_mem_at(KEY1), 0x7F);
+    let before = gb.cycles;
+    gb.step();
+    assert!(gb.mother.clock.double_speed());
+    assert_eq!(gb.mother.get_mem_at(KEY1), 0xFE);
+    // the cpu is held for the switch, and doesn't sleep after it
+    assert_eq!(gb.cycles - before, 4 + clock::SWITCH_DELAY as u64 * 4);
+    assert!(!gb.mother.stopped());
+    assert_eq!(gb.mother.cpu.pc, 0x0106);
+}
+
+#[test]
+fn divider_is_reset_by_the_switch() {
+    let mut gb = boot(Model::CGB, true, SWITCH);
+    for _ in 0..3 {
+        gb.step();
+    }
+    // DIV counted from 0 through the delay only
+    let expected = ((4 + clock::SWITCH_DELAY * 4) >> 8) as u8;
+    assert!((expected - 1..=expected).contains(&gb.mother.get_mem_at(DIV)));
+}
+
+#[test]
+fn ppu_runs_at_half_the_cpu_rate() {
+    let mut gb = boot(Model::CGB, true, SWITCH);
+    for _ in 0..3 {
+        gb.step();
+    }
+    let line = gb.mother.ppu.line();
+    let dot = gb.mother.ppu.dot();
+    for _ in 0..114 {
+        gb.mother.tick(8);
+    }
+    assert_eq!((gb.mother.ppu.line(), gb.mother.ppu.dot()), ((line + 1) % 154, dot));
+}
+
+#[test]
+fn no_switch_without_cgb_mode() {
+    let mut gb = boot(Model::CGB, false, SWITCH);
+    gb.step();
+    gb.step();
+    assert_eq!(gb.mother.get_mem_at(KEY1), 0xFF);
+    gb.step();
+    assert!(!gb.mother.clock.double_speed());
+    // a plain STOP, sleeping until a button is pressed
+    assert!(gb.mother.stopped());
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use rustgb::cpu::Reg;
+use rustgb::gameboy::GameBoy;
+use rustgb::motherboard::Motherboard;
+
+// runs `src` from 0x0000 on a flat bus, with the stack at 0xD000
+fn run(src: &str, steps: usize) -> GameBoy {
+    let mut gb = common::load(src);
+    for _ in 0..steps {
+        gb.step();
+    }
+    gb
+}
+
+fn reg(gb: &GameBoy, reg: Reg) -> u16 {
+    gb.mother.cpu.read_reg(reg).get_double()
+}
+
+// push stores the high byte at sp-1 and the low byte at sp-2
+#[test]
+fn push_is_little_endian() {
+    let mut gb = run("ld bc, $1234\npush bc", 2);
+    assert_eq!(gb.mother.cpu.sp, 0xCFFE);
+    assert_eq!(gb.mother.get_mem_at(0xCFFF), 0x12);
+    assert_eq!(gb.mother.get_mem_at(0xCFFE), 0x34);
+}
+
+#[test]
+fn pop_reverses_push() {
+    let gb = run("ld de, $BEEF\npush de\npop hl", 3);
+    assert_eq!(reg(&gb, Reg::HL), 0xBEEF);
+    assert_eq!(gb.mother.cpu.sp, 0xD000);
+}
+
+#[test]
+fn stack_wraps() {
+    let mut mother = Motherboard::new_flat();
+    mother.cpu.sp = 0x0001;
+    mother.push(0x1234);
+    assert_eq!(mother.cpu.sp, 0xFFFF);
+    assert_eq!(mother.get_mem_at(0x0000), 0x12);
+    assert_eq!(mother.get_mem_at(0xFFFF), 0x34);
+    assert_eq!(mother.pop(), 0x1234);
+    assert_eq!(mother.cpu.sp, 0x0001);
+}
+
+// call returns past its 3 bytes
+#[test]
+fn call_and_ret() {
+    let mut gb = run("nop\ncall $0010", 2);
+    assert_eq!(gb.mother.cpu.pc, 0x0010);
+    assert_eq!(gb.mother.get_mem_at(0xCFFE), 0x04);
+    assert_eq!(gb.mother.get_mem_at(0xCFFF), 0x00);
+
+    let gb = run("call $0010\nld a, $01\nnop\nnop\nnop\nnop\nnop\nnop\nnop\nnop\nnop\nnop\nnop\nret", 2);
+    assert_eq!(gb.mother.cpu.pc, 0x0003);
+    assert_eq!(gb.mother.cpu.sp, 0xD000);
+}
+
+// rst returns past its 1 byte
+#[test]
+fn rst() {
+    let mut gb = run("nop\nnop\nrst $28", 3);
+    assert_eq!(gb.mother.cpu.pc, 0x0028);
+    assert_eq!(gb.mother.get_mem_at(0xCFFE), 0x03);
+}